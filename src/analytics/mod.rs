@@ -0,0 +1,7 @@
+//! `analytics` defines the analytics time series of a hub.
+
+/// `series` defines the time series of the analytics of a hub.
+pub mod series;
+
+/// `view` defines the typed queries and the merged view of the analytics of a hub.
+pub mod view;
@@ -0,0 +1,256 @@
+//! `series` defines the time series of the analytics of a hub.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::http::analytics::{DaySnapshot, EonSnapshot};
+use crate::wallet::clock::EonClock;
+
+use std::collections::BTreeMap;
+
+/// `Series` is a time series of values, keyed by eon number or by day.
+///
+/// Series can only be joined with series of the same key, so that day and eon
+/// series must first be aligned with `to_eons` or `to_days`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Series<K: Ord> {
+    points: BTreeMap<K, f64>,
+}
+
+/// `EonSeries` is a `Series` keyed by eon number.
+pub type EonSeries = Series<u64>;
+
+/// `DaySeries` is a `Series` keyed by UTC day.
+pub type DaySeries = Series<NaiveDate>;
+
+/// `Step` is a key of a `Series` with a successor, so that missing keys can be told
+/// apart from adjacent ones.
+pub trait Step: Ord + Copy {
+    /// `successor` returns the key following this one, if any.
+    fn successor(&self) -> Option<Self>;
+}
+
+impl Step for u64 {
+    fn successor(&self) -> Option<u64> {
+        self.checked_add(1)
+    }
+}
+
+impl Step for NaiveDate {
+    fn successor(&self) -> Option<NaiveDate> {
+        self.succ_opt()
+    }
+}
+
+impl<K: Ord + Copy> Series<K> {
+    /// `new` creates a new empty `Series`.
+    pub fn new() -> Series<K> {
+        Series {
+            points: BTreeMap::new(),
+        }
+    }
+
+    /// `insert` sets the value of a key.
+    pub fn insert(&mut self, key: K, value: f64) {
+        self.points.insert(key, value);
+    }
+
+    /// `add` adds a value to the value of a key.
+    pub fn add(&mut self, key: K, value: f64) {
+        *self.points.entry(key).or_insert(0.0) += value;
+    }
+
+    /// `get` returns the value of a key.
+    pub fn get(&self, key: K) -> Option<f64> {
+        self.points.get(&key).copied()
+    }
+
+    /// `points` returns the points of the series, in key order.
+    pub fn points(&self) -> impl Iterator<Item = (K, f64)> + '_ {
+        self.points.iter().map(|(key, value)| (*key, *value))
+    }
+
+    /// `len` returns the number of points of the series.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// `is_empty` returns if the series has no point.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// `total` returns the sum of the values of the series.
+    pub fn total(&self) -> f64 {
+        self.points.values().sum()
+    }
+
+    /// `map` returns the series of a function of the values.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Series<K> {
+        Series {
+            points: self
+                .points
+                .iter()
+                .map(|(key, value)| (*key, f(*value)))
+                .collect(),
+        }
+    }
+
+    /// `cumulative` returns the series of the running sums of the values.
+    pub fn cumulative(&self) -> Series<K> {
+        let mut sum = 0.0;
+
+        Series {
+            points: self
+                .points
+                .iter()
+                .map(|(key, value)| {
+                    sum += value;
+                    (*key, sum)
+                })
+                .collect(),
+        }
+    }
+
+    /// `fill_forward` returns the series with a point at every key of another series,
+    /// set to the value of the closest previous point. The keys before the first point
+    /// are skipped.
+    pub fn fill_forward(&self, other: &Series<K>) -> Series<K> {
+        let mut points = self.points.clone();
+
+        for key in other.points.keys() {
+            if points.contains_key(key) {
+                continue;
+            }
+
+            if let Some((_, value)) = self.points.range(..*key).next_back() {
+                points.insert(*key, *value);
+            }
+        }
+
+        Series { points }
+    }
+
+    /// `join` returns the series of a function of the values of the keys present in both
+    /// series. The points for which the function returns `None` are skipped.
+    pub fn join<F>(&self, other: &Series<K>, f: F) -> Series<K>
+    where
+        F: Fn(f64, f64) -> Option<f64>,
+    {
+        Series {
+            points: self
+                .points
+                .iter()
+                .filter_map(|(key, value)| {
+                    let other = other.points.get(key)?;
+                    f(*value, *other).map(|value| (*key, value))
+                })
+                .collect(),
+        }
+    }
+
+    /// `ratio` returns the series of the values divided by those of another series,
+    /// skipping the keys of which the divisor is missing or zero. Divisors of running
+    /// values should be aligned first with `fill_forward`.
+    pub fn ratio(&self, other: &Series<K>) -> Series<K> {
+        self.join(other, |value, divisor| {
+            if divisor == 0.0 {
+                None
+            } else {
+                Some(value / divisor)
+            }
+        })
+    }
+
+    /// `merge` returns the series of a function of the values of the keys present in either
+    /// series, with the missing values set to zero.
+    pub fn merge<F>(&self, other: &Series<K>, f: F) -> Series<K>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let mut points = BTreeMap::new();
+
+        for key in self.points.keys().chain(other.points.keys()) {
+            let value = self.get(*key).unwrap_or(0.0);
+            let other = other.get(*key).unwrap_or(0.0);
+            points.insert(*key, f(value, other));
+        }
+
+        Series { points }
+    }
+}
+
+impl<K: Step> Series<K> {
+    /// `deltas` returns the series of the differences between the values of adjacent keys,
+    /// keyed by the later point. The points following a missing key have no delta.
+    pub fn deltas(&self) -> Series<K> {
+        let values: Vec<_> = self.points().collect();
+
+        Series {
+            points: values
+                .windows(2)
+                .filter(|pair| pair[0].0.successor() == Some(pair[1].0))
+                .map(|pair| (pair[1].0, pair[1].1 - pair[0].1))
+                .collect(),
+        }
+    }
+}
+
+impl EonSeries {
+    /// `from_eons` creates a new `EonSeries` of the counts of eon snapshots.
+    pub fn from_eons(snapshots: &[EonSnapshot]) -> EonSeries {
+        let mut series = Series::new();
+
+        for snapshot in snapshots {
+            series.add(snapshot.eon_number, snapshot.count as f64);
+        }
+
+        series
+    }
+
+    /// `to_days` aligns the series on days, attributing each eon to the day it starts.
+    pub fn to_days(&self, clock: &EonClock) -> DaySeries {
+        let mut series = Series::new();
+
+        for (eon_number, value) in self.points() {
+            series.add(clock.eon_start_time(eon_number).naive_utc().date(), value);
+        }
+
+        series
+    }
+}
+
+impl DaySeries {
+    /// `from_days` creates a new `DaySeries` of the counts of day snapshots.
+    pub fn from_days(snapshots: &[DaySnapshot]) -> DaySeries {
+        let mut series = Series::new();
+
+        for snapshot in snapshots {
+            series.add(
+                snapshot.day.as_datetime().naive_utc().date(),
+                snapshot.count as f64,
+            );
+        }
+
+        series
+    }
+
+    /// `to_eons` aligns the series on eons, attributing each day to the eon at its noon.
+    /// The days before the first eon are skipped.
+    pub fn to_eons(&self, clock: &EonClock) -> EonSeries {
+        let mut series = Series::new();
+
+        for (day, value) in self.points() {
+            let noon = match day.and_hms_opt(12, 0, 0) {
+                Some(noon) => Utc.from_utc_datetime(&noon),
+                None => continue,
+            };
+
+            if let Some(eon_number) = clock.eon_at(noon) {
+                series.add(eon_number, value);
+            }
+        }
+
+        series
+    }
+}
@@ -0,0 +1,132 @@
+//! `view` defines the typed queries and the merged view of the analytics of a hub.
+
+use futures::try_join;
+
+use crate::analytics::series::{DaySeries, EonSeries};
+use crate::model::http::analytics::{Admissions, Challenges, Deposits, Transfers, Withdrawals};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+/// `Metric` is a metric of the Analytics API.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Metric {
+    Admissions,
+    Challenges,
+    Deposits,
+    Transfers,
+    Withdrawals,
+}
+
+impl Metric {
+    /// `ALL` are all the metrics of the Analytics API.
+    pub const ALL: [Metric; 5] = [
+        Metric::Admissions,
+        Metric::Challenges,
+        Metric::Deposits,
+        Metric::Transfers,
+        Metric::Withdrawals,
+    ];
+}
+
+/// `AnalyticsView` is the merged view of all the metrics of the Analytics API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnalyticsView {
+    pub admissions: Admissions,
+    pub challenges: Challenges,
+    pub deposits: Deposits,
+    pub transfers: Transfers,
+    pub withdrawals: Withdrawals,
+}
+
+impl AnalyticsView {
+    /// `fetch` fetches all the metrics of the hub concurrently.
+    pub async fn fetch(client: &HubClient) -> Result<AnalyticsView> {
+        let (admissions, challenges, deposits, transfers, withdrawals) = try_join!(
+            client.analytics_admissions(),
+            client.analytics_challenges(),
+            client.analytics_deposits(),
+            client.analytics_transfers(),
+            client.analytics_withdrawals(),
+        )?;
+
+        Ok(AnalyticsView {
+            admissions,
+            challenges,
+            deposits,
+            transfers,
+            withdrawals,
+        })
+    }
+
+    /// `eon_series` returns the series of a metric per eon.
+    pub fn eon_series(&self, metric: Metric) -> EonSeries {
+        let snapshots = match metric {
+            Metric::Admissions => &self.admissions.eon_number,
+            Metric::Challenges => &self.challenges.eon_number,
+            Metric::Deposits => &self.deposits.eon_number,
+            Metric::Transfers => &self.transfers.eon_number,
+            Metric::Withdrawals => &self.withdrawals.eon_number,
+        };
+
+        EonSeries::from_eons(snapshots)
+    }
+
+    /// `day_series` returns the series of a metric per day, if the hub reports one.
+    pub fn day_series(&self, metric: Metric) -> Option<DaySeries> {
+        let snapshots = match metric {
+            Metric::Admissions => return None,
+            Metric::Challenges => &self.challenges.time,
+            Metric::Deposits => &self.deposits.time,
+            Metric::Transfers => &self.transfers.time,
+            Metric::Withdrawals => &self.withdrawals.time,
+        };
+
+        Some(DaySeries::from_days(snapshots))
+    }
+
+    /// `transfers_per_wallet` returns the series of the transfers of each eon
+    /// per wallet admitted up to the eon. The eons before the first admission are skipped.
+    pub fn transfers_per_wallet(&self) -> EonSeries {
+        let transfers = self.eon_series(Metric::Transfers);
+        let wallets = self
+            .eon_series(Metric::Admissions)
+            .cumulative()
+            .fill_forward(&transfers);
+
+        transfers.ratio(&wallets)
+    }
+}
+
+impl HubClient {
+    /// `analytics_view` returns all the aggregated metrics of the hub.
+    pub async fn analytics_view(&self) -> Result<AnalyticsView> {
+        AnalyticsView::fetch(self).await
+    }
+
+    /// `analytics_eon_series` returns the series of a metric of the hub per eon.
+    pub async fn analytics_eon_series(&self, metric: Metric) -> Result<EonSeries> {
+        let snapshots = match metric {
+            Metric::Admissions => self.analytics_admissions().await?.eon_number,
+            Metric::Challenges => self.analytics_challenges().await?.eon_number,
+            Metric::Deposits => self.analytics_deposits().await?.eon_number,
+            Metric::Transfers => self.analytics_transfers().await?.eon_number,
+            Metric::Withdrawals => self.analytics_withdrawals().await?.eon_number,
+        };
+
+        Ok(EonSeries::from_eons(&snapshots))
+    }
+
+    /// `analytics_day_series` returns the series of a metric of the hub per day,
+    /// if the hub reports one.
+    pub async fn analytics_day_series(&self, metric: Metric) -> Result<Option<DaySeries>> {
+        let snapshots = match metric {
+            Metric::Admissions => return Ok(None),
+            Metric::Challenges => self.analytics_challenges().await?.time,
+            Metric::Deposits => self.analytics_deposits().await?.time,
+            Metric::Transfers => self.analytics_transfers().await?.time,
+            Metric::Withdrawals => self.analytics_withdrawals().await?.time,
+        };
+
+        Ok(Some(DaySeries::from_days(&snapshots)))
+    }
+}
@@ -0,0 +1,118 @@
+//! `hash` defines the Keccak-256 hashing of the packed encodings
+//! used by the Liquidity Network protocol.
+
+use rug::integer::Order;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::error::Error;
+use crate::model::common::{Address, Decimal};
+use crate::result::Result;
+
+/// `HASH_LENGTH` is the length in bytes of a hash.
+pub const HASH_LENGTH: usize = 32;
+
+/// `Hash` is a Keccak-256 hash.
+pub type Hash = [u8; HASH_LENGTH];
+
+/// `keccak256` returns the Keccak-256 hash of some data.
+pub fn keccak256(data: &[u8]) -> Hash {
+    let mut hash = [0u8; HASH_LENGTH];
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+    keccak.finalize(&mut hash);
+    hash
+}
+
+/// `parse_hash` parses a hexadecimal hash, with or without the 0x prefix.
+pub fn parse_hash(s: &str) -> Result<Hash> {
+    let decoded = hex::decode(s.trim_start_matches("0x"))?;
+
+    if decoded.len() != HASH_LENGTH {
+        return Err(Error::InvalidLength {
+            length: decoded.len(),
+            expected: HASH_LENGTH,
+        });
+    }
+
+    let mut hash = [0u8; HASH_LENGTH];
+    hash.copy_from_slice(&decoded);
+
+    Ok(hash)
+}
+
+/// `format_hash` formats a hash as 0x prefixed hexadecimal.
+pub fn format_hash(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+/// `PackedEncoder` encodes values as the Solidity `abi.encodePacked` function.
+#[derive(Clone, Debug, Default)]
+pub struct PackedEncoder {
+    data: Vec<u8>,
+}
+
+impl PackedEncoder {
+    /// `new` creates a new `PackedEncoder`.
+    pub fn new() -> PackedEncoder {
+        PackedEncoder::default()
+    }
+
+    /// `address` encodes an `address`.
+    pub fn address(&mut self, address: &Address) -> &mut Self {
+        self.data.extend_from_slice(address.as_bytes());
+        self
+    }
+
+    /// `bytes32` encodes a `bytes32`.
+    pub fn bytes32(&mut self, value: &Hash) -> &mut Self {
+        self.data.extend_from_slice(value);
+        self
+    }
+
+    /// `bytes` encodes a dynamic `bytes`.
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(value);
+        self
+    }
+
+    /// `boolean` encodes a `bool`.
+    pub fn boolean(&mut self, value: bool) -> &mut Self {
+        self.data.push(value as u8);
+        self
+    }
+
+    /// `number` encodes a `uint256` from an unsigned integer.
+    pub fn number(&mut self, value: u64) -> &mut Self {
+        self.data.extend_from_slice(&[0u8; HASH_LENGTH - 8]);
+        self.data.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    /// `decimal` encodes a `uint256` from a `Decimal`.
+    pub fn decimal(&mut self, value: &Decimal) -> Result<&mut Self> {
+        let value = value.as_integer();
+        let digits = value.to_digits::<u8>(Order::Msf);
+
+        if value.cmp0().is_lt() || digits.len() > HASH_LENGTH {
+            return Err(Error::InvalidUint256 {
+                value: value.to_string(),
+            });
+        }
+
+        let padding = self.data.len() + HASH_LENGTH - digits.len();
+        self.data.resize(padding, 0);
+        self.data.extend_from_slice(&digits);
+
+        Ok(self)
+    }
+
+    /// `as_bytes` returns the encoded data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// `hash` returns the Keccak-256 hash of the encoded data.
+    pub fn hash(&self) -> Hash {
+        keccak256(&self.data)
+    }
+}
@@ -0,0 +1,130 @@
+//! `key` defines the secp256k1 keys used to sign and verify
+//! the Liquidity Network protocol messages.
+
+use rand::thread_rng;
+use secp256k1::{self, Message, PublicKey, RecoveryId, SecretKey};
+
+use crate::crypto::hash::{keccak256, Hash};
+use crate::error::Error;
+use crate::model::common::{Address, Signature};
+use crate::result::Result;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// `MESSAGE_PREFIX` is the prefix of the messages signed with `eth_sign`.
+pub const MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+/// `V_OFFSET` is the offset of the recovery id in Ethereum signatures.
+const V_OFFSET: u8 = 27;
+
+/// `PrivateKey` is a secp256k1 private key.
+#[derive(Clone, Eq, PartialEq)]
+pub struct PrivateKey(SecretKey);
+
+impl PrivateKey {
+    /// `LENGTH` is the length in bytes of a private key.
+    pub const LENGTH: usize = 32;
+
+    /// `random` creates a new random `PrivateKey`.
+    pub fn random() -> PrivateKey {
+        PrivateKey(SecretKey::random(&mut thread_rng()))
+    }
+
+    /// `from_bytes` creates a new `PrivateKey` from its bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PrivateKey> {
+        SecretKey::parse_slice(bytes)
+            .map(PrivateKey)
+            .map_err(|_| Error::InvalidPrivateKey)
+    }
+
+    /// `to_bytes` returns the bytes of the `PrivateKey`.
+    pub fn to_bytes(&self) -> [u8; Self::LENGTH] {
+        self.0.serialize()
+    }
+
+    /// `address` returns the `Address` controlled by the `PrivateKey`.
+    pub fn address(&self) -> Address {
+        public_key_address(&PublicKey::from_secret_key(&self.0))
+    }
+
+    /// `sign` signs a hash.
+    pub fn sign(&self, hash: &Hash) -> Signature {
+        let (signature, recovery_id) = secp256k1::sign(&Message::parse(hash), &self.0);
+
+        let mut bytes = [0u8; Signature::LENGTH];
+        bytes[..64].copy_from_slice(&signature.serialize());
+        bytes[64] = recovery_id.serialize() + V_OFFSET;
+
+        Signature::from_bytes(&bytes)
+    }
+
+    /// `sign_message` signs a hash prefixed as an Ethereum signed message.
+    pub fn sign_message(&self, hash: &Hash) -> Signature {
+        self.sign(&message_hash(hash))
+    }
+}
+
+impl FromStr for PrivateKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        PrivateKey::from_bytes(&hex::decode(s.trim_start_matches("0x"))?)
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PrivateKey({})", self.address())
+    }
+}
+
+/// `message_hash` returns the hash of a hash prefixed as an Ethereum signed message.
+pub fn message_hash(hash: &Hash) -> Hash {
+    let mut data = MESSAGE_PREFIX.to_vec();
+    data.extend_from_slice(hash);
+    keccak256(&data)
+}
+
+/// `public_key_address` returns the `Address` of a public key.
+pub fn public_key_address(public_key: &PublicKey) -> Address {
+    let hash = keccak256(&public_key.serialize()[1..]);
+    let mut address = [0u8; Address::LENGTH];
+    address.copy_from_slice(&hash[12..]);
+    Address::new(address)
+}
+
+/// `recover` recovers the `Address` that signed a hash.
+pub fn recover(hash: &Hash, signature: &Signature) -> Result<Address> {
+    let bytes = signature.to_bytes()?;
+
+    let v = bytes[64];
+    let v = if v >= V_OFFSET { v - V_OFFSET } else { v };
+
+    let recovery_id = RecoveryId::parse(v).map_err(|_| Error::InvalidSignature)?;
+    let signature =
+        secp256k1::Signature::parse_slice(&bytes[..64]).map_err(|_| Error::InvalidSignature)?;
+
+    secp256k1::recover(&Message::parse(hash), &signature, &recovery_id)
+        .map(|public_key| public_key_address(&public_key))
+        .map_err(|_| Error::InvalidSignature)
+}
+
+/// `recover_message` recovers the `Address` that signed a hash
+/// prefixed as an Ethereum signed message.
+pub fn recover_message(hash: &Hash, signature: &Signature) -> Result<Address> {
+    recover(&message_hash(hash), signature)
+}
+
+/// `verify` returns if a hash was signed by an `Address`.
+pub fn verify(hash: &Hash, signature: &Signature, address: &Address) -> bool {
+    recover(hash, signature)
+        .map(|signer| signer == *address)
+        .unwrap_or(false)
+}
+
+/// `verify_message` returns if a hash prefixed as an Ethereum signed message
+/// was signed by an `Address`.
+pub fn verify_message(hash: &Hash, signature: &Signature, address: &Address) -> bool {
+    verify(&message_hash(hash), signature, address)
+}
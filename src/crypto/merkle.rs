@@ -0,0 +1,109 @@
+//! `merkle` defines the Keccak-256 Merkle trees of the Liquidity Network protocol.
+
+use crate::crypto::hash::{Hash, PackedEncoder, HASH_LENGTH};
+
+/// `EMPTY_ROOT` is the root of an empty Merkle tree.
+pub const EMPTY_ROOT: Hash = [0u8; HASH_LENGTH];
+
+/// `node_hash` returns the hash of a node from its children.
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    PackedEncoder::new().bytes32(left).bytes32(right).hash()
+}
+
+/// `parent_level` returns the level above a level of a Merkle tree.
+/// The last node of an odd level is paired with itself.
+fn parent_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// `root` returns the root of the Merkle tree of a set of leaves.
+pub fn root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    let mut level = leaves.to_vec();
+
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+
+    level[0]
+}
+
+/// `proof` returns the sibling hashes proving the membership of
+/// a leaf in the Merkle tree of a set of leaves, from the bottom up.
+pub fn proof(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    let mut proof = Vec::new();
+
+    if index >= leaves.len() {
+        return proof;
+    }
+
+    let mut level = leaves.to_vec();
+    let mut index = index;
+
+    while level.len() > 1 {
+        let sibling = (index ^ 1).min(level.len() - 1);
+        proof.push(level[sibling]);
+
+        level = parent_level(&level);
+        index /= 2;
+    }
+
+    proof
+}
+
+/// `compute_root` returns the root of a Merkle tree from a leaf at
+/// an index and its sibling hashes, from the bottom up.
+pub fn compute_root(leaf: &Hash, index: u64, proof: &[Hash]) -> Hash {
+    let mut hash = *leaf;
+    let mut index = index;
+
+    for sibling in proof {
+        hash = if index & 1 == 0 {
+            node_hash(&hash, sibling)
+        } else {
+            node_hash(sibling, &hash)
+        };
+        index /= 2;
+    }
+
+    hash
+}
+
+/// `verify` returns if a leaf at an index belongs to the Merkle tree of a root.
+pub fn verify(leaf: &Hash, index: u64, proof: &[Hash], root: &Hash) -> bool {
+    compute_root(leaf, index, proof) == *root
+}
+
+/// `interval_leaf_hash` returns the hash of a leaf of a Merkle interval
+/// tree, allotting the interval from left to right.
+pub fn interval_leaf_hash(left: u64, hash: &Hash, right: u64) -> Hash {
+    PackedEncoder::new()
+        .number(left)
+        .bytes32(hash)
+        .number(right)
+        .hash()
+}
+
+/// `interval_node_hash` returns the hash of a node of a Merkle interval
+/// tree from its children, splitting its interval at middle.
+pub fn interval_node_hash(
+    left: u64,
+    left_hash: &Hash,
+    middle: u64,
+    right_hash: &Hash,
+    right: u64,
+) -> Hash {
+    PackedEncoder::new()
+        .number(left)
+        .bytes32(left_hash)
+        .number(middle)
+        .bytes32(right_hash)
+        .number(right)
+        .hash()
+}
@@ -0,0 +1,16 @@
+//! `crypto` defines the hashing and signing functions of the Liquidity Network protocol.
+
+/// `hash` defines the Keccak-256 hashing of the protocol packed encodings.
+pub mod hash;
+
+/// `key` defines the secp256k1 keys used to sign and verify the protocol messages.
+pub mod key;
+
+/// `merkle` defines the Merkle trees committing to the protocol messages.
+pub mod merkle;
+
+/// `proof` defines the offline verification of the hub Merkle proofs.
+pub mod proof;
+
+/// `protocol` defines the hashes of the protocol messages.
+pub mod protocol;
@@ -0,0 +1,277 @@
+//! `proof` defines the verification of the Merkle proofs of the Liquidity Network protocol.
+//!
+//! A checkpoint commits to the balances of all the wallets of a hub in an eon.
+//! Its root is the hash of two trees sharing the same leaves:
+//!
+//! - the allotment tree, a Merkle interval tree in which each wallet is
+//!   allotted the interval `[left, right]` of the hub funds, and
+//! - the membership tree, a Merkle tree of the wallet hashes indexed by their trail.
+//!
+//! The leaf of a wallet commits to its active state checksum and its passive checksum.
+//!
+//! The transaction set of an active state is committed to by its `tx_set_hash`.
+//! Active transfers are proven by a Merkle membership chain. Passive transfers are
+//! proven by a Merkle interval chain, in which each transfer is allotted the interval
+//! of the passive amounts it delivers, with `transfer_membership_values` as the
+//! sibling boundaries.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::crypto::hash::{keccak256, parse_hash, Hash, PackedEncoder};
+use crate::crypto::merkle;
+use crate::crypto::protocol::{wallet_hash, ActiveStateUpdate};
+use crate::error::Error;
+use crate::model::common::{Address, Decimal, DeliveryProof, MerkleProof, Wallet};
+use crate::result::Result;
+
+/// `ProofFailure` is the reason a Merkle proof failed to verify.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ProofFailure {
+    ActiveStateChecksum,
+    PassiveChecksum,
+    ChainLength,
+    Allotment,
+    CheckpointRoot,
+    TransferMembership,
+}
+
+impl fmt::Display for ProofFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            ProofFailure::ActiveStateChecksum => "active state checksum mismatch",
+            ProofFailure::PassiveChecksum => "passive checksum mismatch",
+            ProofFailure::ChainLength => "chain length mismatch",
+            ProofFailure::Allotment => "invalid allotment interval",
+            ProofFailure::CheckpointRoot => "checkpoint root mismatch",
+            ProofFailure::TransferMembership => "transfer not in transaction set",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// `passive_checksum` returns the checksum of the passive transfers received by a wallet.
+pub fn passive_checksum(passive_amount: u64, passive_marker: &Decimal) -> Result<Hash> {
+    let hash = PackedEncoder::new()
+        .number(passive_amount)
+        .decimal(passive_marker)?
+        .hash();
+
+    Ok(hash)
+}
+
+/// `checkpoint_root` returns the checkpoint root from its allotment and membership roots.
+pub fn checkpoint_root(allotment_root: &Hash, membership_root: &Hash) -> Hash {
+    merkle::node_hash(allotment_root, membership_root)
+}
+
+/// `ProofVerifier` verifies the Merkle proofs of the wallets of a hub contract, offline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofVerifier {
+    contract: Address,
+}
+
+impl ProofVerifier {
+    /// `new` creates a new `ProofVerifier` for the hub contract.
+    pub fn new(contract: Address) -> ProofVerifier {
+        ProofVerifier { contract }
+    }
+
+    /// `fail` returns an error for a proof failure.
+    fn fail<T>(failure: ProofFailure) -> Result<T> {
+        Err(Error::InvalidProof { failure })
+    }
+
+    /// `parse_chain` parses the hashes of a proof chain.
+    fn parse_chain(chain: &[String]) -> Result<Vec<Hash>> {
+        chain.iter().map(|hash| parse_hash(hash)).collect()
+    }
+
+    /// `leaf_hash` returns the leaf of a wallet in the checkpoint, checking its checksums.
+    pub fn leaf_hash(&self, wallet: &Wallet, proof: &MerkleProof) -> Result<Hash> {
+        let update = ActiveStateUpdate::from_active_state(
+            wallet,
+            proof.trail,
+            proof.eon_number,
+            &proof.active_state,
+        )?;
+        let active_state_checksum = update.hash(&self.contract)?;
+
+        if active_state_checksum != parse_hash(&proof.active_state_checksum)? {
+            return Self::fail(ProofFailure::ActiveStateChecksum);
+        }
+
+        let passive_marker = Decimal::from_str(&proof.passive_marker)?;
+        let passive = passive_checksum(proof.passive_amount, &passive_marker)?;
+
+        if passive != parse_hash(&proof.passive_checksum)? {
+            return Self::fail(ProofFailure::PassiveChecksum);
+        }
+
+        let hash = PackedEncoder::new()
+            .bytes32(&wallet_hash(&self.contract, wallet))
+            .bytes32(&active_state_checksum)
+            .bytes32(&passive)
+            .hash();
+
+        Ok(hash)
+    }
+
+    /// `interval_root` returns the root of a Merkle interval tree from a leaf allotted
+    /// the interval `[left, right]`, along with the interval covered by the root.
+    ///
+    /// At each level, the sibling of the current node covers the interval between the
+    /// current node and the sibling boundary in `values`, on the side given by the trail.
+    fn interval_root(
+        leaf: &Hash,
+        (left, right): (u64, u64),
+        trail: u64,
+        chain: &[Hash],
+        values: &[u64],
+        failure: ProofFailure,
+    ) -> Result<(Hash, u64, u64)> {
+        if chain.len() != values.len() {
+            return Self::fail(ProofFailure::ChainLength);
+        }
+
+        if left > right {
+            return Self::fail(failure);
+        }
+
+        let (mut left, mut right) = (left, right);
+        let mut hash = merkle::interval_leaf_hash(left, leaf, right);
+        let mut trail = trail;
+
+        for (sibling, &boundary) in chain.iter().zip(values) {
+            if trail & 1 == 0 {
+                if boundary < right {
+                    return Self::fail(failure);
+                }
+
+                hash = merkle::interval_node_hash(left, &hash, right, sibling, boundary);
+                right = boundary;
+            } else {
+                if boundary > left {
+                    return Self::fail(failure);
+                }
+
+                hash = merkle::interval_node_hash(boundary, sibling, left, &hash, right);
+                left = boundary;
+            }
+
+            trail /= 2;
+        }
+
+        Ok((hash, left, right))
+    }
+
+    /// `allotment_root` returns the root of the allotment tree from a wallet proof,
+    /// along with the interval of the hub funds it covers.
+    pub fn allotment_root(&self, wallet: &Wallet, proof: &MerkleProof) -> Result<(Hash, u64, u64)> {
+        Self::interval_root(
+            &self.leaf_hash(wallet, proof)?,
+            (proof.left, proof.right),
+            proof.trail,
+            &Self::parse_chain(&proof.allotment_chain)?,
+            &proof.values,
+            ProofFailure::Allotment,
+        )
+    }
+
+    /// `membership_root` returns the root of the membership tree from a wallet proof.
+    pub fn membership_root(&self, wallet: &Wallet, proof: &MerkleProof) -> Result<Hash> {
+        let chain = Self::parse_chain(&proof.membership_chain)?;
+        let leaf = keccak256(&wallet_hash(&self.contract, wallet));
+
+        Ok(merkle::compute_root(&leaf, proof.trail, &chain))
+    }
+
+    /// `checkpoint_root` returns the checkpoint root committed to by a wallet proof.
+    pub fn checkpoint_root(&self, wallet: &Wallet, proof: &MerkleProof) -> Result<Hash> {
+        let (allotment_root, left, _) = self.allotment_root(wallet, proof)?;
+
+        if left != 0 {
+            return Self::fail(ProofFailure::Allotment);
+        }
+
+        Ok(checkpoint_root(
+            &allotment_root,
+            &self.membership_root(wallet, proof)?,
+        ))
+    }
+
+    /// `verify_merkle_proof` verifies that a wallet proof belongs to the checkpoint of a root.
+    pub fn verify_merkle_proof(
+        &self,
+        wallet: &Wallet,
+        proof: &MerkleProof,
+        root: &Hash,
+    ) -> Result<()> {
+        if self.checkpoint_root(wallet, proof)? != *root {
+            return Self::fail(ProofFailure::CheckpointRoot);
+        }
+
+        Ok(())
+    }
+
+    /// `verify_delivery_proof` verifies that an active transfer belongs to the transaction
+    /// set of a wallet proof, and that the proof belongs to the checkpoint of a root.
+    /// The membership chain of an active transfer carries no values.
+    pub fn verify_delivery_proof(
+        &self,
+        wallet: &Wallet,
+        transfer: &Hash,
+        proof: &DeliveryProof,
+        root: &Hash,
+    ) -> Result<()> {
+        self.verify_merkle_proof(wallet, &proof.merkle_proof, root)?;
+
+        if !proof.transfer_membership_values.is_empty() {
+            return Self::fail(ProofFailure::ChainLength);
+        }
+
+        let chain = Self::parse_chain(&proof.transfer_membership_chain)?;
+        let tx_set_hash = parse_hash(&proof.merkle_proof.active_state.tx_set_hash)?;
+
+        if !merkle::verify(
+            transfer,
+            proof.transfer_membership_trail,
+            &chain,
+            &tx_set_hash,
+        ) {
+            return Self::fail(ProofFailure::TransferMembership);
+        }
+
+        Ok(())
+    }
+
+    /// `verify_passive_delivery_proof` verifies that a passive transfer delivering
+    /// the passive amounts `[left, right]` belongs to the transaction set of a wallet
+    /// proof, and that the proof belongs to the checkpoint of a root.
+    pub fn verify_passive_delivery_proof(
+        &self,
+        wallet: &Wallet,
+        transfer: &Hash,
+        interval: (u64, u64),
+        proof: &DeliveryProof,
+        root: &Hash,
+    ) -> Result<()> {
+        self.verify_merkle_proof(wallet, &proof.merkle_proof, root)?;
+
+        let (tx_set_hash, _, _) = Self::interval_root(
+            transfer,
+            interval,
+            proof.transfer_membership_trail,
+            &Self::parse_chain(&proof.transfer_membership_chain)?,
+            &proof.transfer_membership_values,
+            ProofFailure::TransferMembership,
+        )?;
+
+        if tx_set_hash != parse_hash(&proof.merkle_proof.active_state.tx_set_hash)? {
+            return Self::fail(ProofFailure::TransferMembership);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! `protocol` defines the hashes of the Liquidity Network protocol messages,
+//! as computed by the hub contract.
+
+use crate::crypto::hash::{parse_hash, Hash, PackedEncoder};
+use crate::crypto::key::{recover_message, PrivateKey};
+use crate::model::common::{ActiveState, Address, Decimal, SenderActiveState, Signature, Wallet};
+use crate::result::Result;
+
+use std::str::FromStr;
+
+/// `wallet_hash` returns the hash identifying a wallet in a hub contract.
+pub fn wallet_hash(contract: &Address, wallet: &Wallet) -> Hash {
+    PackedEncoder::new()
+        .address(contract)
+        .address(&wallet.token)
+        .address(&wallet.address)
+        .hash()
+}
+
+/// `transfer_hash` returns the hash of a transfer between two wallets.
+pub fn transfer_hash(
+    contract: &Address,
+    sender: &Wallet,
+    recipient: &Wallet,
+    amount: u64,
+    nonce: u64,
+) -> Hash {
+    PackedEncoder::new()
+        .address(contract)
+        .address(&sender.token)
+        .address(&sender.address)
+        .address(&recipient.token)
+        .address(&recipient.address)
+        .number(amount)
+        .number(nonce)
+        .hash()
+}
+
+/// `swap_hash` returns the hash of a swap between two wallets of the same owner.
+pub fn swap_hash(
+    contract: &Address,
+    sender: &Wallet,
+    recipient: &Wallet,
+    amount: u64,
+    amount_swapped: u64,
+    nonce: u64,
+) -> Hash {
+    PackedEncoder::new()
+        .address(contract)
+        .address(&sender.token)
+        .address(&sender.address)
+        .address(&recipient.token)
+        .address(&recipient.address)
+        .number(amount)
+        .number(amount_swapped)
+        .number(nonce)
+        .hash()
+}
+
+/// `swap_freeze_hash` returns the hash authorizing the freezing of a swap in an eon.
+pub fn swap_freeze_hash(swap_hash: &Hash, eon_number: u64) -> Hash {
+    PackedEncoder::new()
+        .bytes32(swap_hash)
+        .number(eon_number)
+        .hash()
+}
+
+/// `balance_marker_hash` returns the hash attesting the balance of a wallet in an eon.
+pub fn balance_marker_hash(
+    contract: &Address,
+    wallet: &Wallet,
+    eon_number: u64,
+    balance: u64,
+) -> Hash {
+    PackedEncoder::new()
+        .bytes32(&wallet_hash(contract, wallet))
+        .number(eon_number)
+        .number(balance)
+        .hash()
+}
+
+/// `ActiveStateUpdate` is the content of an active state of a wallet in an eon,
+/// that is signed by both the wallet owner and the hub operator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActiveStateUpdate {
+    pub wallet: Wallet,
+    pub trail_identifier: u64,
+    pub eon_number: u64,
+    pub tx_set_hash: Hash,
+    pub updated_spendings: Decimal,
+    pub updated_gains: Decimal,
+}
+
+impl ActiveStateUpdate {
+    /// `from_state` creates a new `ActiveStateUpdate` from a `SenderActiveState`.
+    pub fn from_state(
+        wallet: &Wallet,
+        trail_identifier: u64,
+        eon_number: u64,
+        state: &SenderActiveState,
+    ) -> Result<ActiveStateUpdate> {
+        let update = ActiveStateUpdate {
+            wallet: wallet.clone(),
+            trail_identifier,
+            eon_number,
+            tx_set_hash: parse_hash(&state.tx_set_hash)?,
+            updated_spendings: Decimal::from_str(&state.updated_spendings)?,
+            updated_gains: Decimal::from_str(&state.updated_gains)?,
+        };
+
+        Ok(update)
+    }
+
+    /// `from_active_state` creates a new `ActiveStateUpdate` from an `ActiveState`.
+    pub fn from_active_state(
+        wallet: &Wallet,
+        trail_identifier: u64,
+        eon_number: u64,
+        state: &ActiveState,
+    ) -> Result<ActiveStateUpdate> {
+        let update = ActiveStateUpdate {
+            wallet: wallet.clone(),
+            trail_identifier,
+            eon_number,
+            tx_set_hash: parse_hash(&state.tx_set_hash)?,
+            updated_spendings: Decimal::from_str(&state.updated_spendings)?,
+            updated_gains: Decimal::from_str(&state.updated_gains)?,
+        };
+
+        Ok(update)
+    }
+
+    /// `hash` returns the hash of the `ActiveStateUpdate` in a hub contract.
+    pub fn hash(&self, contract: &Address) -> Result<Hash> {
+        let hash = PackedEncoder::new()
+            .bytes32(&wallet_hash(contract, &self.wallet))
+            .number(self.trail_identifier)
+            .number(self.eon_number)
+            .bytes32(&self.tx_set_hash)
+            .decimal(&self.updated_spendings)?
+            .decimal(&self.updated_gains)?
+            .hash();
+
+        Ok(hash)
+    }
+
+    /// `sign` signs the `ActiveStateUpdate`.
+    pub fn sign(&self, contract: &Address, key: &PrivateKey) -> Result<Signature> {
+        Ok(key.sign_message(&self.hash(contract)?))
+    }
+
+    /// `signer` recovers the `Address` that signed the `ActiveStateUpdate`.
+    pub fn signer(&self, contract: &Address, signature: &Signature) -> Result<Address> {
+        recover_message(&self.hash(contract)?, signature)
+    }
+}
@@ -1,10 +1,18 @@
 //! `error` defines the error type used in the library.
 
+use async_tungstenite::tungstenite;
 use http::uri;
 use rug::integer;
 use serde_json as json;
 use thiserror::Error;
 
+use crate::crypto::proof::ProofFailure;
+use crate::model::common::Address;
+use crate::request::http::error::ErrorResponse;
+use crate::request::http::transfer::TransferCreateError;
+use crate::wallet::swap::{SwapState, SwapTransitionError};
+use url;
+
 use std::io;
 
 /// `Error` is the error type used in the library.
@@ -20,16 +28,78 @@ pub enum Error {
         #[from]
         source: json::Error,
     },
+    #[error("TOML error: {source:?}")]
+    TOML {
+        #[from]
+        source: toml::de::Error,
+    },
     #[error("Invalid URI: {source:?}")]
     InvalidURI {
         #[from]
         source: uri::InvalidUri,
     },
+    #[error("Invalid URL: {source:?}")]
+    InvalidURL {
+        #[from]
+        source: url::ParseError,
+    },
     #[error("Invalid decimal: {source:?}")]
     InvalidDecimal {
         #[from]
         source: integer::ParseIntegerError,
     },
+    #[error("HTTP error: {source:?}")]
+    HTTP {
+        #[from]
+        source: surf::Exception,
+    },
+    #[error("Unexpected HTTP status {status:?}")]
+    HTTPStatus { status: u16 },
+    #[error("Hub error (status {status:?}): {response}")]
+    Hub {
+        status: u16,
+        response: ErrorResponse,
+    },
+    #[error("Transfer rejected: {codes:?}")]
+    TransferRejected { codes: Vec<TransferCreateError> },
+    #[error("Invalid proof: {failure}")]
+    InvalidProof { failure: ProofFailure },
+    #[error("Invalid swap transition from {from} to {to}: {reason}")]
+    InvalidSwapTransition {
+        from: SwapState,
+        to: SwapState,
+        reason: SwapTransitionError,
+    },
+    #[error("WS error: {source:?}")]
+    WS {
+        #[from]
+        source: tungstenite::Error,
+    },
+    #[error("WS error response: {message:?}")]
+    WSResponse { message: String },
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {source:?}")]
+    SQLite {
+        #[from]
+        source: rusqlite::Error,
+    },
+    #[error("Invalid hex: {source:?}")]
+    InvalidHex {
+        #[from]
+        source: hex::FromHexError,
+    },
+    #[error("Invalid address {address:?}")]
+    InvalidAddress { address: String },
+    #[error("Invalid checksum of address {address:?}")]
+    InvalidChecksum { address: String },
+    #[error("Invalid uint256 {value:?}")]
+    InvalidUint256 { value: String },
+    #[error("Invalid private key")]
+    InvalidPrivateKey,
+    #[error("Key of {signer} does not match the recipient {recipient}")]
+    RecipientMismatch { recipient: Address, signer: Address },
+    #[error("Invalid signature")]
+    InvalidSignature,
     #[error("Invalid length {length:?} when {expected:?} was expected")]
     InvalidLength { length: usize, expected: usize },
     #[error("Value {value:?} out of range {min:?}-{max:?}")]
@@ -38,6 +108,12 @@ pub enum Error {
         min: Option<i64>,
         max: Option<i64>,
     },
+    #[error("Overflow of {field:?}")]
+    Overflow { field: String },
+    #[error("Underflow of {field:?}")]
+    Underflow { field: String },
+    #[error("Unsupported format {format:?}")]
+    UnsupportedFormat { format: String },
     #[error("Missing field {field:?}")]
     MissingField { field: String },
     #[error("Unknown field {field:?}")]
@@ -9,8 +9,23 @@ pub mod error;
 /// Defines the result type used in the library.
 pub mod result;
 
+/// Defines the hashing and signing functions of the Liquidity Network protocol.
+pub mod crypto;
+
 /// Defines the models used throughout the library.
 pub mod model;
 
 /// Defines the types and functions for the WS and HTTP Liquidity Network hub APIs.
 pub mod request;
+
+/// Defines the high-level operations of wallets on a Liquidity Network hub.
+pub mod wallet;
+
+/// Defines the persistent stores of the client state.
+pub mod store;
+
+/// Defines the market data of the token pairs of a Liquidity Network hub.
+pub mod market;
+
+/// Defines the analytics time series of a Liquidity Network hub.
+pub mod analytics;
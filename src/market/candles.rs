@@ -0,0 +1,355 @@
+//! `candles` defines the trade history and the OHLCV candles of a token pair.
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+
+use crate::error::Error;
+use crate::model::common::Address;
+use crate::model::http::audit::TokenMatching;
+use crate::model::ws::ws::{Event, NotificationData, TokenPairEvent, WalletEvent};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+use crate::wallet::clock::EonClock;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// `Interval` is the interval of the candles of a `CandleSeries`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interval {
+    Minute,
+    Hour,
+    Day,
+    /// `Seconds` is a custom interval, in seconds.
+    Seconds(u64),
+    /// `Eon` is the interval of the eons of the hub, estimated by an `EonClock`.
+    Eon,
+}
+
+impl Interval {
+    /// `seconds` returns the length of the interval in seconds, unless it is per eon.
+    pub fn seconds(&self) -> Option<u64> {
+        match self {
+            Interval::Minute => Some(60),
+            Interval::Hour => Some(60 * 60),
+            Interval::Day => Some(24 * 60 * 60),
+            Interval::Seconds(seconds) => Some((*seconds).max(1)),
+            Interval::Eon => None,
+        }
+    }
+}
+
+/// `Candle` is the OHLCV aggregate of the trades of an interval.
+/// Times are UNIX timestamps in seconds, and the end of a candle is exclusive.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub start: u64,
+    pub end: u64,
+    pub eon_number: Option<u64>,
+    /// `open_time` is the time of the earliest trade of the candle.
+    pub open_time: u64,
+    /// `close_time` is the time of the latest trade of the candle.
+    pub close_time: u64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    pub volume: u64,
+    pub trades: u64,
+}
+
+impl Candle {
+    /// `CSV_HEADER` is the header of the CSV export of candles.
+    pub const CSV_HEADER: &'static str = "start,end,eon_number,open,high,low,close,volume,trades";
+
+    /// `new` creates a new `Candle` of an interval opening with a trade.
+    fn new(start: u64, end: u64, eon_number: Option<u64>, trade: &TokenMatching) -> Candle {
+        Candle {
+            start,
+            end,
+            eon_number,
+            open_time: trade.time,
+            close_time: trade.time,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.volume,
+            trades: 1,
+        }
+    }
+
+    /// `apply` records another trade of the interval, in any time order. Among the
+    /// trades of the same time, the first applied opens and the last applied closes.
+    /// The volume and the number of trades saturate at `u64::MAX`.
+    fn apply(&mut self, trade: &TokenMatching) {
+        if trade.time < self.open_time {
+            self.open_time = trade.time;
+            self.open = trade.price;
+        }
+
+        if trade.time >= self.close_time {
+            self.close_time = trade.time;
+            self.close = trade.price;
+        }
+
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.volume = self.volume.saturating_add(trade.volume);
+        self.trades = self.trades.saturating_add(1);
+    }
+}
+
+/// `ExportFormat` is the format of the export of candles.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ExportFormat {
+    CSV,
+    JSON,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ExportFormat> {
+        match s {
+            "csv" => Ok(ExportFormat::CSV),
+            "json" => Ok(ExportFormat::JSON),
+            format => Err(Error::UnsupportedFormat {
+                format: format.to_owned(),
+            }),
+        }
+    }
+}
+
+/// `CandleSeries` aggregates the trades of a token pair into OHLCV candles.
+///
+/// The series is backfilled from the pages of the matchings of the Audit API, and
+/// updated live by backfilling again on the `MATCHED_SWAP` events of the pair, so
+/// that every price is the one of the hub matchings. The hub lists the matchings
+/// from the oldest, so each backfill only reads those following the last one read.
+/// Trades may be applied in any time order.
+#[derive(Clone, Debug)]
+pub struct CandleSeries {
+    left_token: Address,
+    right_token: Address,
+    interval: Interval,
+    clock: Option<EonClock>,
+    candles: BTreeMap<u64, Candle>,
+    offset: usize,
+}
+
+impl CandleSeries {
+    /// `PAGE_SIZE` is the number of matchings requested per page when backfilling.
+    pub const PAGE_SIZE: usize = 500;
+
+    /// `new` creates a new empty `CandleSeries` of a token pair.
+    pub fn new(left_token: Address, right_token: Address, interval: Interval) -> CandleSeries {
+        CandleSeries {
+            left_token,
+            right_token,
+            interval,
+            clock: None,
+            candles: BTreeMap::new(),
+            offset: 0,
+        }
+    }
+
+    /// `with_clock` sets the eon clock of the hub, required by the per-eon interval.
+    pub fn with_clock(mut self, clock: EonClock) -> CandleSeries {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// `interval` returns the interval of the candles.
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// `clock_mut` returns the eon clock of the series, to record new observations.
+    pub fn clock_mut(&mut self) -> Option<&mut EonClock> {
+        self.clock.as_mut()
+    }
+
+    /// `candles` returns the candles of the series, in time order.
+    pub fn candles(&self) -> impl Iterator<Item = &Candle> {
+        self.candles.values()
+    }
+
+    /// `bucket` returns the key, the bounds and the eon of the candle of a time,
+    /// unless the end of the candle is beyond the range of `u64`.
+    fn bucket(&self, time: u64) -> Option<(u64, u64, u64, Option<u64>)> {
+        if let Some(seconds) = self.interval.seconds() {
+            let start = time - time % seconds;
+            return Some((start, start, start.checked_add(seconds)?, None));
+        }
+
+        let clock = self.clock.as_ref()?;
+        let eon_number = clock.eon_at(Utc.timestamp_opt(time as i64, 0).single()?)?;
+        let start = clock.eon_start_time(eon_number).timestamp().max(0) as u64;
+        let end = clock.eon_end_time(eon_number).timestamp().max(0) as u64;
+
+        Some((eon_number, start, end, Some(eon_number)))
+    }
+
+    /// `apply_matching` records a trade, and returns if it belongs to a candle.
+    pub fn apply_matching(&mut self, trade: &TokenMatching) -> bool {
+        let (key, start, end, eon_number) = match self.bucket(trade.time) {
+            Some(bucket) => bucket,
+            None => return false,
+        };
+
+        self.candles
+            .entry(key)
+            .and_modify(|candle| candle.apply(trade))
+            .or_insert_with(|| Candle::new(start, end, eon_number, trade));
+
+        true
+    }
+
+    /// `load` records the trades of a history, sorting them by time.
+    pub fn load(&mut self, history: &[TokenMatching]) {
+        let mut trades: Vec<_> = history.iter().collect();
+        trades.sort_by_key(|trade| trade.time);
+
+        for trade in trades {
+            self.apply_matching(trade);
+        }
+    }
+
+    /// `history` returns the matchings of the pair following the last one read,
+    /// walking the pages of the hub. A hub ignoring the pagination returns the same
+    /// page again, which ends the walk.
+    async fn history(&self, client: &HubClient) -> Result<Vec<TokenMatching>> {
+        let mut history = Vec::new();
+        let mut previous = None;
+
+        loop {
+            let page = client
+                .token_matchings_page(
+                    &self.left_token,
+                    &self.right_token,
+                    self.offset.saturating_add(history.len()),
+                    Self::PAGE_SIZE,
+                )
+                .await?;
+
+            if previous.as_ref() == Some(&page) {
+                break;
+            }
+
+            let full = page.len() == Self::PAGE_SIZE;
+            history.extend(page.iter().cloned());
+
+            if !full {
+                break;
+            }
+
+            previous = Some(page);
+        }
+
+        Ok(history)
+    }
+
+    /// `backfill` records the matchings of the hub following the last one read,
+    /// and returns the number of trades recorded.
+    pub async fn backfill(&mut self, client: &HubClient) -> Result<usize> {
+        let history = self.history(client).await?;
+        self.offset = self.offset.saturating_add(history.len());
+
+        Ok(history
+            .iter()
+            .filter(|trade| self.apply_matching(trade))
+            .count())
+    }
+
+    /// `is_pair_match` returns if a notification is a `MATCHED_SWAP` event of a swap of the pair.
+    pub fn is_pair_match(&self, notification: &NotificationData) -> bool {
+        let transfer = match &notification.data {
+            Event::TokenPair(TokenPairEvent::MatchedSwap(transfer))
+            | Event::Wallet(WalletEvent::MatchedSwap(transfer)) => transfer,
+            _ => return false,
+        };
+
+        let tokens = (transfer.wallet.token, transfer.recipient.token);
+
+        tokens == (self.left_token, self.right_token)
+            || tokens == (self.right_token, self.left_token)
+    }
+
+    /// `sync` backfills the matchings of the hub following the last one read on a
+    /// `MATCHED_SWAP` event of the pair, and returns the number of trades recorded.
+    pub async fn sync(
+        &mut self,
+        client: &HubClient,
+        notification: &NotificationData,
+    ) -> Result<usize> {
+        if !self.is_pair_match(notification) {
+            return Ok(0);
+        }
+
+        self.backfill(client).await
+    }
+
+    /// `to_csv` exports the candles as CSV, with a header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(Candle::CSV_HEADER);
+        csv.push('\n');
+
+        for candle in self.candles() {
+            let eon_number = candle
+                .eon_number
+                .map(|eon_number| eon_number.to_string())
+                .unwrap_or_default();
+
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{}",
+                candle.start,
+                candle.end,
+                eon_number,
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume,
+                candle.trades
+            );
+        }
+
+        csv
+    }
+
+    /// `to_json` exports the candles as a JSON array.
+    pub fn to_json(&self) -> Result<String> {
+        let candles: Vec<_> = self.candles().collect();
+        json::to_string(&candles).map_err(|e| e.into())
+    }
+
+    /// `export` exports the candles in a format.
+    pub fn export(&self, format: ExportFormat) -> Result<String> {
+        match format {
+            ExportFormat::CSV => Ok(self.to_csv()),
+            ExportFormat::JSON => self.to_json(),
+        }
+    }
+
+    /// `save` exports the candles to a CSV or JSON file,
+    /// depending on the file extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .parse()?;
+
+        fs::write(path, self.export(format)?)?;
+
+        Ok(())
+    }
+}
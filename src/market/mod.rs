@@ -0,0 +1,7 @@
+//! `market` defines the market data of the token pairs of a hub.
+
+/// `orderbook` defines the local order book of a token pair.
+pub mod orderbook;
+
+/// `candles` defines the trade history and the OHLCV candles of a token pair.
+pub mod candles;
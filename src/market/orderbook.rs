@@ -0,0 +1,448 @@
+//! `orderbook` defines the local order book of a token pair, maintained
+//! from the snapshots of the Audit API and the events of the token pair stream.
+
+use rug::Rational;
+
+use crate::model::common::{Address, TransferAudit};
+use crate::model::http::audit::{SwapAudit, TokenOrderBook};
+use crate::model::ws::ws::{Event, NotificationData, TokenPairEvent, WalletEvent};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+use std::collections::BTreeMap;
+
+/// `Side` is the side of an order of a token pair, relative to its left token.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Side {
+    /// `Bid` is an order buying the left token with the right token.
+    Bid,
+    /// `Ask` is an order selling the left token for the right token.
+    Ask,
+}
+
+/// `Order` is an open swap of a token pair.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    /// `id` is the id of the swap, unknown for the orders of a snapshot
+    /// until an event of the swap is applied.
+    pub id: Option<i64>,
+    pub side: Side,
+    pub amount: u64,
+    pub amount_swapped: u64,
+    pub remaining_in: u64,
+    pub remaining_out: u64,
+}
+
+impl Order {
+    /// `from_swap_audit` creates an `Order` from a swap of a snapshot.
+    fn from_swap_audit(side: Side, swap: &SwapAudit) -> Order {
+        Order {
+            id: None,
+            side,
+            amount: swap.amount,
+            amount_swapped: swap.amount_swapped,
+            remaining_in: swap.remaining_in,
+            remaining_out: swap.remaining_out,
+        }
+    }
+
+    /// `from_transfer` creates an `Order` from a swap of an event.
+    fn from_transfer(side: Side, transfer: &TransferAudit) -> Order {
+        let amount_swapped = transfer.amount_swapped.unwrap_or_default();
+        let matched = &transfer.matched_amounts;
+
+        Order {
+            id: Some(transfer.id),
+            side,
+            amount: transfer.amount,
+            amount_swapped,
+            remaining_in: transfer.amount.saturating_sub(matched.matched_in),
+            remaining_out: amount_swapped.saturating_sub(matched.matched_out),
+        }
+    }
+
+    /// `is_open` returns if the order has a remaining amount to swap.
+    pub fn is_open(&self) -> bool {
+        self.remaining_in > 0 && self.remaining_out > 0
+    }
+
+    /// `price` returns the price of the order in right tokens per left token,
+    /// implied by its remaining amounts.
+    pub fn price(&self) -> Option<Rational> {
+        if !self.is_open() {
+            return None;
+        }
+
+        let price = match self.side {
+            Side::Bid => Rational::from((self.remaining_in, self.remaining_out)),
+            Side::Ask => Rational::from((self.remaining_out, self.remaining_in)),
+        };
+
+        Some(price)
+    }
+
+    /// `volume` returns the remaining amount of the order in left tokens.
+    pub fn volume(&self) -> u64 {
+        match self.side {
+            Side::Bid => self.remaining_out,
+            Side::Ask => self.remaining_in,
+        }
+    }
+}
+
+/// `Level` is the aggregate of the orders of a side at a price.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Level {
+    pub price: Rational,
+    pub volume: u64,
+    pub orders: usize,
+}
+
+/// `BookUpdate` is the outcome of applying an event to an `OrderBook`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BookUpdate {
+    /// `Applied` is an event changing the book.
+    Applied,
+    /// `Ignored` is an event of another token pair, or of a swap already closed.
+    Ignored,
+    /// `Gap` is an event inconsistent with the book, which must be snapshotted again.
+    Gap,
+}
+
+/// `Lookup` is the outcome of looking up the order of a swap in an `OrderBook`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Lookup {
+    Found(usize),
+    Missing,
+    Ambiguous,
+}
+
+/// `OrderBook` is a local order book of a token pair. It loads the snapshots of the
+/// Audit API and applies the `INCOMING_SWAP` and `MATCHED_SWAP` events incrementally.
+///
+/// The book detects the events it cannot follow, such as the match of an unknown
+/// swap or a swap whose remaining amounts grow, and is then stale until it is
+/// snapshotted again.
+///
+/// This detection is a heuristic. The notifications carry no sequence number, so a
+/// missed event is only noticed once a later event of the same swap contradicts the
+/// book: a missed new swap that is never matched, or a missed match followed by no
+/// other event of its swap, goes unnoticed. The orders of a snapshot have no swap id,
+/// so the first event of a swap is matched to a snapshot order by its side, amounts
+/// and remaining amounts, and is a gap when several different orders could match.
+/// Consumers should also call `mark_gap` on every `WsEvent::Gap` of the `WsClient`,
+/// and snapshot the book again periodically.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderBook {
+    left_token: Address,
+    right_token: Address,
+    orders: Vec<Order>,
+    stale: bool,
+}
+
+impl OrderBook {
+    /// `new` creates a new empty and stale `OrderBook` of a token pair.
+    pub fn new(left_token: Address, right_token: Address) -> OrderBook {
+        OrderBook {
+            left_token,
+            right_token,
+            orders: Vec::new(),
+            stale: true,
+        }
+    }
+
+    /// `from_snapshot` creates a new `OrderBook` of a token pair from a snapshot.
+    pub fn from_snapshot(
+        left_token: Address,
+        right_token: Address,
+        snapshot: &TokenOrderBook,
+    ) -> OrderBook {
+        let mut book = OrderBook::new(left_token, right_token);
+        book.load_snapshot(snapshot);
+        book
+    }
+
+    /// `left_token` returns the left token of the pair.
+    pub fn left_token(&self) -> &Address {
+        &self.left_token
+    }
+
+    /// `right_token` returns the right token of the pair.
+    pub fn right_token(&self) -> &Address {
+        &self.right_token
+    }
+
+    /// `orders` returns the open orders of the book.
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    /// `is_stale` returns if the book must be snapshotted again.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// `mark_gap` marks the book as stale, such as when events may have been missed.
+    pub fn mark_gap(&mut self) {
+        self.stale = true;
+    }
+
+    /// `load_snapshot` replaces the orders of the book with those of a snapshot.
+    pub fn load_snapshot(&mut self, snapshot: &TokenOrderBook) {
+        let bids = snapshot
+            .buy_orders
+            .iter()
+            .map(|swap| Order::from_swap_audit(Side::Bid, swap));
+        let asks = snapshot
+            .sell_orders
+            .iter()
+            .map(|swap| Order::from_swap_audit(Side::Ask, swap));
+
+        self.orders = bids.chain(asks).filter(Order::is_open).collect();
+        self.stale = false;
+    }
+
+    /// `resnapshot` replaces the orders of the book with a snapshot of the hub.
+    pub async fn resnapshot(&mut self, client: &HubClient) -> Result<()> {
+        let snapshot = client
+            .order_book(&self.left_token, &self.right_token)
+            .await?;
+
+        self.load_snapshot(&snapshot);
+
+        Ok(())
+    }
+
+    /// `side_of` returns the side of a swap in the pair, if it swaps the tokens of the pair.
+    fn side_of(&self, transfer: &TransferAudit) -> Option<Side> {
+        let tokens = (transfer.wallet.token, transfer.recipient.token);
+
+        if tokens == (self.left_token, self.right_token) {
+            Some(Side::Ask)
+        } else if tokens == (self.right_token, self.left_token) {
+            Some(Side::Bid)
+        } else {
+            None
+        }
+    }
+
+    /// `lookup` returns the position of the order of a swap. The orders of a snapshot
+    /// are matched by their side and amounts, among those whose remaining amounts are
+    /// not below those of the swap. The match is ambiguous when these orders differ.
+    fn lookup(&self, order: &Order) -> Lookup {
+        if let Some(position) = self.orders.iter().position(|other| other.id == order.id) {
+            return Lookup::Found(position);
+        }
+
+        let mut candidates = self.orders.iter().enumerate().filter(|(_, other)| {
+            other.id.is_none()
+                && other.side == order.side
+                && other.amount == order.amount
+                && other.amount_swapped == order.amount_swapped
+                && other.remaining_in >= order.remaining_in
+                && other.remaining_out >= order.remaining_out
+        });
+
+        let (position, first) = match candidates.next() {
+            Some(candidate) => candidate,
+            None => return Lookup::Missing,
+        };
+
+        if candidates.any(|(_, other)| other != first) {
+            return Lookup::Ambiguous;
+        }
+
+        Lookup::Found(position)
+    }
+
+    /// `gap` marks the book as stale and returns a gap.
+    fn gap(&mut self) -> BookUpdate {
+        self.stale = true;
+        BookUpdate::Gap
+    }
+
+    /// `incoming_swap` applies a new swap of the pair.
+    fn incoming_swap(&mut self, transfer: &TransferAudit) -> BookUpdate {
+        let side = match self.side_of(transfer) {
+            Some(side) => side,
+            None => return BookUpdate::Ignored,
+        };
+
+        let order = Order::from_transfer(side, transfer);
+        let closed = transfer.cancelled || transfer.voided || !order.is_open();
+
+        match (self.lookup(&order), closed) {
+            (Lookup::Ambiguous, _) => return self.gap(),
+            (Lookup::Found(position), true) => {
+                self.orders.remove(position);
+            }
+            (Lookup::Found(position), false) => self.orders[position] = order,
+            (Lookup::Missing, true) => return BookUpdate::Ignored,
+            (Lookup::Missing, false) => self.orders.push(order),
+        }
+
+        BookUpdate::Applied
+    }
+
+    /// `matched_swap` applies a match of a swap of the pair.
+    fn matched_swap(&mut self, transfer: &TransferAudit) -> BookUpdate {
+        let side = match self.side_of(transfer) {
+            Some(side) => side,
+            None => return BookUpdate::Ignored,
+        };
+
+        let order = Order::from_transfer(side, transfer);
+
+        let position = match self.lookup(&order) {
+            Lookup::Found(position) => position,
+            Lookup::Missing | Lookup::Ambiguous => return self.gap(),
+        };
+
+        let current = &self.orders[position];
+
+        if order.remaining_in > current.remaining_in || order.remaining_out > current.remaining_out
+        {
+            return self.gap();
+        }
+
+        if order.is_open() && !transfer.cancelled && !transfer.voided {
+            self.orders[position] = order;
+        } else {
+            self.orders.remove(position);
+        }
+
+        BookUpdate::Applied
+    }
+
+    /// `closed_swap` removes a swap of the pair which was cancelled or finalized.
+    fn closed_swap(&mut self, transfer: &TransferAudit) -> BookUpdate {
+        let side = match self.side_of(transfer) {
+            Some(side) => side,
+            None => return BookUpdate::Ignored,
+        };
+
+        match self.lookup(&Order::from_transfer(side, transfer)) {
+            Lookup::Found(position) => {
+                self.orders.remove(position);
+                BookUpdate::Applied
+            }
+            Lookup::Missing => BookUpdate::Ignored,
+            Lookup::Ambiguous => self.gap(),
+        }
+    }
+
+    /// `apply_event` applies an event of the token pair stream.
+    /// Events are ignored while the book is stale.
+    pub fn apply_event(&mut self, event: &TokenPairEvent) -> BookUpdate {
+        if self.stale {
+            return BookUpdate::Gap;
+        }
+
+        match event {
+            TokenPairEvent::IncomingSwap(transfer) => self.incoming_swap(transfer),
+            TokenPairEvent::MatchedSwap(transfer) => self.matched_swap(transfer),
+        }
+    }
+
+    /// `apply_notification` applies the event of a notification. The `MATCHED_SWAP`
+    /// wallet events are applied as token pair events, and the cancelled and
+    /// finalized swaps of the wallet events are removed from the book.
+    pub fn apply_notification(&mut self, notification: &NotificationData) -> BookUpdate {
+        if self.stale {
+            return BookUpdate::Gap;
+        }
+
+        match &notification.data {
+            Event::TokenPair(event) => self.apply_event(event),
+            Event::Wallet(WalletEvent::MatchedSwap(transfer)) => self.matched_swap(transfer),
+            Event::Wallet(WalletEvent::CanceledSwap(transfer))
+            | Event::Wallet(WalletEvent::FinalizedSwap(transfer)) => self.closed_swap(transfer),
+            Event::Wallet(_) => BookUpdate::Ignored,
+        }
+    }
+
+    /// `sync` applies the event of a notification, and snapshots the book again
+    /// from the hub if it is stale.
+    pub async fn sync(
+        &mut self,
+        client: &HubClient,
+        notification: &NotificationData,
+    ) -> Result<BookUpdate> {
+        let update = self.apply_notification(notification);
+
+        if self.stale {
+            self.resnapshot(client).await?;
+        }
+
+        Ok(update)
+    }
+
+    /// `levels` returns the price levels of a side, from the best price.
+    /// The volume of a level saturates at `u64::MAX`.
+    pub fn levels(&self, side: Side) -> Vec<Level> {
+        let mut levels: BTreeMap<Rational, Level> = BTreeMap::new();
+
+        for order in self.orders.iter().filter(|order| order.side == side) {
+            let price = match order.price() {
+                Some(price) => price,
+                None => continue,
+            };
+
+            let level = levels.entry(price.clone()).or_insert(Level {
+                price,
+                volume: 0,
+                orders: 0,
+            });
+            level.volume = level.volume.saturating_add(order.volume());
+            level.orders += 1;
+        }
+
+        match side {
+            Side::Bid => levels.into_values().rev().collect(),
+            Side::Ask => levels.into_values().collect(),
+        }
+    }
+
+    /// `depth` returns the best price levels of a side, up to a number of levels.
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<Level> {
+        let mut depth = self.levels(side);
+        depth.truncate(levels);
+        depth
+    }
+
+    /// `volume_at` returns the volume of a side at a price, saturating at `u64::MAX`.
+    pub fn volume_at(&self, side: Side, price: &Rational) -> u64 {
+        self.orders
+            .iter()
+            .filter(|order| order.side == side)
+            .filter(|order| order.price().as_ref() == Some(price))
+            .map(Order::volume)
+            .fold(0, u64::saturating_add)
+    }
+
+    /// `best_bid` returns the bid level with the highest price.
+    pub fn best_bid(&self) -> Option<Level> {
+        self.levels(Side::Bid).into_iter().next()
+    }
+
+    /// `best_ask` returns the ask level with the lowest price.
+    pub fn best_ask(&self) -> Option<Level> {
+        self.levels(Side::Ask).into_iter().next()
+    }
+
+    /// `spread` returns the difference between the best ask and the best bid prices.
+    pub fn spread(&self) -> Option<Rational> {
+        match (self.best_ask(), self.best_bid()) {
+            (Some(ask), Some(bid)) => Some(ask.price - bid.price),
+            _ => None,
+        }
+    }
+
+    /// `mid_price` returns the average of the best ask and the best bid prices.
+    pub fn mid_price(&self) -> Option<Rational> {
+        match (self.best_ask(), self.best_bid()) {
+            (Some(ask), Some(bid)) => Some((ask.price + bid.price) / 2u32),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! `common` contains the common models.
 
+use crate::crypto::hash::keccak256;
 use crate::error::*;
 use chrono::Utc;
 use http::uri;
@@ -11,30 +12,40 @@ use std::str::FromStr;
 use uuid;
 
 /// `DateTime` is a UTC datetime.
-#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DateTime(chrono::DateTime<Utc>);
 
+impl DateTime {
+    /// `new` creates a new `DateTime` from a UTC chrono datetime.
+    pub fn new(value: chrono::DateTime<Utc>) -> DateTime {
+        DateTime(value)
+    }
+
+    /// `as_datetime` returns the UTC chrono datetime value of the `DateTime`.
+    pub fn as_datetime(&self) -> &chrono::DateTime<Utc> {
+        &self.0
+    }
+}
+
 /// `Uid` is a v4 UUID.
-#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Uuid(uuid::Uuid);
 
 /// `Uri` is an RFC3986 URI.
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Uri(uri::Uri);
 
 impl FromStr for Uri {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        uri::Uri::from_str(s)
-            .map(|uri| Uri(uri))
-            .map_err(|e| e.into())
+        uri::Uri::from_str(s).map(Uri).map_err(|e| e.into())
     }
 }
 
-impl ToString for Uri {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
     }
 }
 
@@ -74,41 +85,197 @@ impl<'de> Deserialize<'de> for Uri {
 }
 
 /// `Decimal` is a big integer value.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Decimal(Integer);
 
 impl FromStr for Decimal {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        rug::Integer::from_str(s)
-            .map(|bigint| Decimal(bigint))
-            .map_err(|e| e.into())
+        rug::Integer::from_str(s).map(Decimal).map_err(|e| e.into())
+    }
+}
+
+impl Decimal {
+    /// `new` creates a new `Decimal` from a big integer.
+    pub fn new(value: Integer) -> Decimal {
+        Decimal(value)
+    }
+
+    /// `as_integer` returns the big integer value of the `Decimal`.
+    pub fn as_integer(&self) -> &Integer {
+        &self.0
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(value: u64) -> Decimal {
+        Decimal(Integer::from(value))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// `Address` is an Ethereum address. Addresses are compared by value, so
+/// regardless of the case of their hexadecimal representation, and are
+/// serialized with their EIP-55 checksum.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Address([u8; Address::LENGTH]);
+
+impl Address {
+    /// `LENGTH` is the length in bytes of an address.
+    pub const LENGTH: usize = 20;
+
+    /// `HEX_LENGTH` is the length of the 0x-prefixed hexadecimal representation of an address.
+    pub const HEX_LENGTH: usize = 2 + 2 * Self::LENGTH;
+
+    /// `new` creates a new `Address` from its bytes.
+    pub fn new(bytes: [u8; Self::LENGTH]) -> Address {
+        Address(bytes)
+    }
+
+    /// `from_slice` creates a new `Address` from a slice of bytes.
+    pub fn from_slice(bytes: &[u8]) -> Result<Address, Error> {
+        if bytes.len() != Self::LENGTH {
+            return Err(Error::InvalidLength {
+                length: bytes.len(),
+                expected: Self::LENGTH,
+            });
+        }
+
+        let mut address = [0u8; Self::LENGTH];
+        address.copy_from_slice(bytes);
+
+        Ok(Address(address))
+    }
+
+    /// `as_bytes` returns the bytes of the `Address`.
+    pub fn as_bytes(&self) -> &[u8; Self::LENGTH] {
+        &self.0
+    }
+
+    /// `to_checksum` returns the EIP-55 checksummed representation of the `Address`.
+    pub fn to_checksum(&self) -> String {
+        let lower = hex::encode(self.0);
+        let hash = keccak256(lower.as_bytes());
+
+        let checksummed: String = lower
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        format!("0x{}", checksummed)
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    /// `from_str` parses a 0x-prefixed hexadecimal address. Mixed-case
+    /// addresses are validated against their EIP-55 checksum.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != Self::HEX_LENGTH {
+            return Err(Error::InvalidLength {
+                length: s.len(),
+                expected: Self::HEX_LENGTH,
+            });
+        }
+
+        let digits = s.strip_prefix("0x").ok_or_else(|| Error::InvalidAddress {
+            address: s.to_owned(),
+        })?;
+
+        let address = Address::from_slice(&hex::decode(digits)?)?;
+
+        let is_mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+            && digits.chars().any(|c| c.is_ascii_uppercase());
+
+        if is_mixed_case && address.to_checksum() != s {
+            return Err(Error::InvalidChecksum {
+                address: s.to_owned(),
+            });
+        }
+
+        Ok(address)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_checksum())
+    }
+}
+
+struct AddressVisitor;
+
+impl<'de> Visitor<'de> for AddressVisitor {
+    type Value = Address;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a valid 0x-prefixed hexadecimal address")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Address::from_str(value).map_err(|e| E::custom(e.to_string()))
     }
 }
 
-impl ToString for Decimal {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Address, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(AddressVisitor)
     }
 }
 
 /// `Wallet` represent a wallet coordinate.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Wallet {
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
 }
 
 /// `Block` represents a block coordinate.
-#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     pub block: u64,
     pub eon_number: u64,
 }
 
 /// `SenderActiveState` represents a sender state.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SenderActiveState {
     pub operator_signature: String,
     pub tx_set_hash: String,
@@ -120,7 +287,7 @@ pub struct SenderActiveState {
 }
 
 /// `ActiveState` represents an active state. // TODO: improve
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ActiveState {
     pub operator_signature: String,
     pub tx_set_hash: String,
@@ -130,7 +297,7 @@ pub struct ActiveState {
 }
 
 /// `MerkleProof` represents a Merkle proof.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct MerkleProof {
     pub active_state: ActiveState,
     pub active_state_checksum: String,
@@ -147,7 +314,7 @@ pub struct MerkleProof {
 }
 
 /// `DeliveryProof` represents a proof of delivery.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DeliveryProof {
     pub merkle_proof: MerkleProof,
     pub transfer_membership_chain: Vec<String>,
@@ -156,7 +323,7 @@ pub struct DeliveryProof {
 }
 
 /// `MatchedAmounts` represents a match of swapped amounts.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct MatchedAmounts {
     pub r#in: u64,
     pub matched_in: u64,
@@ -165,15 +332,15 @@ pub struct MatchedAmounts {
 }
 
 /// `TokenMatching` represents token information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Token {
-    pub address: String,
+    pub address: Address,
     pub name: Option<String>,
     pub short_name: Option<String>,
 }
 
 /// `TransferAudit` represents a transfer audit receipt.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TransferAudit {
     pub amount: u64,
     pub amount_swapped: Option<u64>,
@@ -208,8 +375,16 @@ pub struct TransferAudit {
     pub wallet_trail_identifier: i64,
 }
 
+impl TransferAudit {
+    /// `credited_amount` returns the amount the transfer credits to its recipient,
+    /// which is the amount swapped for the credit side of a swap.
+    pub fn credited_amount(&self) -> u64 {
+        self.amount_swapped.unwrap_or(self.amount)
+    }
+}
+
 /// `WithdrawalRequest` represents a withdrawal request.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WithdrawalRequest {
     pub amount: u64,
     pub block: i64,
@@ -220,7 +395,7 @@ pub struct WithdrawalRequest {
 }
 
 /// `Withdrawal` represents a withdrawal.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Withdrawal {
     pub amount: u64,
     pub block: i64,
@@ -231,7 +406,37 @@ pub struct Withdrawal {
 }
 
 /// Signature represents a signature.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Signature {
     pub value: String,
 }
+
+impl Signature {
+    /// `LENGTH` is the length in bytes of a signature.
+    pub const LENGTH: usize = 65;
+
+    /// `from_bytes` creates a new `Signature` from its bytes.
+    pub fn from_bytes(bytes: &[u8; Self::LENGTH]) -> Signature {
+        Signature {
+            value: format!("0x{}", hex::encode(&bytes[..])),
+        }
+    }
+
+    /// `to_bytes` returns the bytes of the `Signature`.
+    pub fn to_bytes(&self) -> Result<[u8; Self::LENGTH], Error> {
+        let value = self.value.trim_start_matches("0x");
+        let decoded = hex::decode(value)?;
+
+        if decoded.len() != Self::LENGTH {
+            return Err(Error::InvalidLength {
+                length: decoded.len(),
+                expected: Self::LENGTH,
+            });
+        }
+
+        let mut bytes = [0u8; Self::LENGTH];
+        bytes.copy_from_slice(&decoded);
+
+        Ok(bytes)
+    }
+}
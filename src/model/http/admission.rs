@@ -3,10 +3,12 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::model::common::Address;
+
 /// `Admission` represents a wallet admission information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Admission {
-    pub address: String,
+    pub address: Address,
     pub authorization: String,
-    pub token: String,
+    pub token: Address,
 }
@@ -6,14 +6,14 @@ use serde::{Deserialize, Serialize};
 use crate::model::common::*;
 
 /// `Blocks` represents blocks information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Blocks {
     pub confirmed: Vec<Block>,
     pub latest: Block,
 }
 
 /// `SwapAudit` represents swaps information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SwapAudit {
     pub amount: u64,
     pub amount_swapped: u64,
@@ -22,14 +22,14 @@ pub struct SwapAudit {
 }
 
 /// `TokenOrderBook` represents token order book information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TokenOrderBook {
     pub buy_orders: Vec<SwapAudit>,
     pub sell_orders: Vec<SwapAudit>,
 }
 
 /// `TokenMatching` represents token matching information.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TokenMatching {
     pub price: u64,
     pub time: u64,
@@ -37,7 +37,7 @@ pub struct TokenMatching {
 }
 
 /// `ConciseTransfer` is a concise representation of a transfer.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ConciseTransfer {
     pub amount: u64,
     pub amount_swapped: u64,
@@ -55,7 +55,7 @@ pub struct ConciseTransfer {
 }
 
 /// `Transfers` is an aggregated view of transfers.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Transfers {
     pub count: i64,
     pub next: Option<Uri>,
@@ -64,7 +64,7 @@ pub struct Transfers {
 }
 
 /// `Transfer` is a detailed view of a transfer.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Transfer {
     pub amount: u64,
     pub amount_swapped: Option<u64>,
@@ -99,7 +99,7 @@ pub struct Transfer {
 }
 
 /// `Deposit` is a view of a deposit.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Deposit {
     pub amount: u64,
     pub block: i64,
@@ -109,7 +109,7 @@ pub struct Deposit {
 }
 
 /// `Registration` represents a wallet registration data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Registration {
     pub eon_number: u64,
     pub operator_signature: String,
@@ -118,7 +118,7 @@ pub struct Registration {
 }
 
 /// `WalletState` represents the state of a wallet.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WalletState {
     pub deposits: Vec<Deposit>,
     pub merkle_proofs: Vec<MerkleProof>,
@@ -129,7 +129,7 @@ pub struct WalletState {
 }
 
 ///  `WhoIs` represents wallet admission data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WhoIs {
     pub eon_number: u64,
     pub operator_signature: String,
@@ -6,12 +6,14 @@ use serde::{Deserialize, Serialize};
 use crate::model::common::{SenderActiveState, Signature, Wallet};
 
 /// `Swap` represents a swap operation.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Swap {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     pub amount: u64,
     pub amount_swapped: Option<u64>,
     pub credit_balance_signature: Vec<Signature>,
-    pub credit_signature: Signature,
+    pub credit_signature: Vec<Signature>,
     pub debit_balance_signature: Vec<Signature>,
     pub debit_signature: Vec<Signature>,
     pub eon_number: u64,
@@ -26,20 +28,20 @@ pub struct Swap {
 }
 
 /// `SwapCancellation` represents a swap cancellation operation.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SwapCancellation {
     pub recipient_cancellation_signature: Vec<Signature>,
     pub sender_cancellation_signature: Vec<Signature>,
 }
 
 /// `SwapFinalization` is the result of a swap finalization.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SwapFinalization {
     pub finalization_signature: Vec<Signature>,
 }
 
 /// `SwapFreeze` is the result of a swap freeze operation.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SwapFreeze {
     pub freezing_signature: Vec<Signature>,
 }
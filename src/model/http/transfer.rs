@@ -3,10 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::model::common::{DateTime, SenderActiveState, Signature, Uuid, Wallet};
+use crate::model::common::{Address, DateTime, SenderActiveState, Signature, Uuid, Wallet};
 
 /// `Transfer` is a detailed view of a transfer.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Transfer {
     pub amount: u64,
     pub complete: bool,
@@ -18,7 +18,7 @@ pub struct Transfer {
     pub passive: bool,
     pub position: u64,
     pub processed: bool,
-    pub recipient: String,
+    pub recipient: Address,
     pub recipient_active_state: SenderActiveState,
     pub sender_active_state: SenderActiveState,
     pub sender_finalization_active_state: SenderActiveState,
@@ -31,11 +31,20 @@ pub struct Transfer {
 }
 
 /// `TransferReceipt` is the receipt of a transfer.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TransferReceipt {
+    pub amount: u64,
+    pub nonce: u64,
     pub recipient: Wallet,
-    pub recipient_active_state: SenderActiveState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient_active_state: Option<SenderActiveState>,
     pub sender_active_state: SenderActiveState,
     pub wallet: Wallet,
     pub wallet_signature: Signature,
 }
+
+/// `TransferUpdate` is the countersignature of a transfer by its recipient.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransferUpdate {
+    pub recipient_active_state: SenderActiveState,
+}
@@ -1 +1,5 @@
+//! `ws` defines the models of the Liquidity Network hub WS API.
 
+/// `ws` defines the WS Notification API models.
+#[allow(clippy::module_inception)]
+pub mod ws;
@@ -1,75 +1,140 @@
 //! `ws` contains the types and functions for the web socket notifications API of the Liquid Network hub.
 //! The notifications can be sent and received on both HTTP and HTTPS.
 
-use crate::utils::UUID;
+use crate::model::common::{Address, Uuid, Withdrawal, WithdrawalRequest};
+use crate::model::http::audit::{Deposit, WalletState};
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json as json;
+use std::fmt;
+
+/// `tag` implements the (de)serialization of a unit type as a constant string.
+macro_rules! tag {
+    ($name:ident, $value:expr) => {
+        impl $name {
+            /// `TAG` is the string representation of the type.
+            pub const TAG: &'static str = $value;
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(Self::TAG)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<$name, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(TagVisitor(Self::TAG))?;
+                Ok($name)
+            }
+        }
+    };
+}
 
-use serde::{Deserialize, Serialize};
+struct TagVisitor(&'static str);
+
+impl<'de> Visitor<'de> for TagVisitor {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the string {:?}", self.0)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if value == self.0 {
+            Ok(())
+        } else {
+            Err(E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+}
 
 /// `GenericRequest` is a generic request in the WS Notification API.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GenericRequest<Op, Args> {
     pub op: Op,
     pub args: Args,
 }
 
 /// `PingOp` is the operation type of a `PingRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PingOp;
 
+tag!(PingOp, "ping");
+
 /// `PingRequest` is a PING request in the WS Notification API.
 pub type PingRequest = GenericRequest<PingOp, ()>;
 
 /// `AckOp` is the operation type of an `AckRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AckOp;
 
+tag!(AckOp, "ack");
+
 /// `AckRequest` is an ACK request in the WS Notification API.
-pub type AckRequest = GenericRequest<AckOp, UUID>;
+pub type AckRequest = GenericRequest<AckOp, Uuid>;
 
 /// `SubscribeOp` is the operation type of a `SubscribeRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SubscribeOp;
 
+tag!(SubscribeOp, "subscribe");
+
 /// `SubscribeRequest` is a subscribe request in the WS Notification API.
 pub type SubscribeRequest = GenericRequest<SubscribeOp, Vec<String>>;
 
 /// `UnsubscribeOp` is the operation type of an `UnsubscribeRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UnsubscribeOp;
 
+tag!(UnsubscribeOp, "unsubscribe");
+
 /// `UnsubscribeRequest` is an unsubscribe request in the WS Notification API.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub type UnsubscribeRequest = GenericRequest<UnsubscribeOp, Vec<String>>;
 
 /// `GenericResponse` is a generic response in the WS Notification API.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GenericResponse<Type, Data> {
     pub r#type: Type,
-    pub uuid: UUID,
+    pub uuid: Uuid,
     pub data: Data,
 }
 
 /// `ErrorType` is the type of an `ErrorRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ErrorType;
 
+tag!(ErrorType, "error");
+
 /// `ErrorData` is the data of an `ErrorResponse`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ErrorData<Req> {
     pub message: String,
     pub cause: Req,
 }
 
 /// `ErrorResponse` is an error response in the WS Notification API.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub type ErrorResponse<Req> = GenericResponse<ErrorType, ErrorData<Req>>;
 
 /// `NotificationType` is the type of a `NotificationRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct NotificationType;
 
+tag!(NotificationType, "notification");
+
 /// `WalletEventType` is the set of wallet event types.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WalletEventType {
     IncomingTransfer,
     IncomingReceipt,
@@ -86,30 +151,29 @@ pub enum WalletEventType {
 }
 
 /// `TokenPairEventType` is the set of tokenpair event types.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TokenPairEventType {
     IncomingSwap,
     MatchedSwap,
 }
 
 /// `EventType` is the type of an `EventRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum EventType {
     Wallet(WalletEventType),
     TokenPair(TokenPairEventType),
 }
 
 /// `TransferAudit` is the equivalent of the TransferAuditSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
-pub struct TransferAudit {
-    // TODO: "TransferAuditSerializer check the models section of the docs"?
-}
+pub use crate::model::common::TransferAudit;
 
 /// `WalletAdmissionData` is the equivalent of the WalletAdmissionDataSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WalletAdmissionData {
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
     pub eon_number: i64,
     pub wallet_signature: String,
     pub operator_signature: String,
@@ -117,78 +181,259 @@ pub struct WalletAdmissionData {
 }
 
 /// `DepositNotification` is the equivalent of the DepositNotificationSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DepositNotification {
-    // TODO: "...Deposit (check the models section)"?
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
+    #[serde(flatten)]
+    pub deposit: Deposit,
 }
 
 /// `WithdrawalRequestNotification` is the equivalent of the WithdrawalRequestNotificationSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WithdrawalRequestNotification {
-    // TODO: "...WithdrawalRequest (check the models section)"?
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
+    #[serde(flatten)]
+    pub withdrawal_request: WithdrawalRequest,
 }
 
 /// `WithdrawalNotification` is the equivalent of the WithdrawalNotificationSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WithdrawalNotification {
-    // TODO: "...Withdrawal (check the models section)"?
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
+    #[serde(flatten)]
+    pub withdrawal: Withdrawal,
 }
 
 /// `WalletDataNotification` is the equivalent of the WalletDataNotificationSerializer model in the Swagger documentations.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WalletDataNotification {
-    // TODO: "...WalletData (check the models section)"?
-    pub address: String,
-    pub token: String,
+    pub address: Address,
+    pub token: Address,
+    #[serde(flatten)]
+    pub wallet_data: WalletState,
 }
 
-/// `WalletEvent` is the model of wallet event type data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+/// `WalletEvent` is the model of wallet event type data, with one variant per `WalletEventType`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum WalletEvent {
-    TransferAudit(TransferAudit),
-    WalletAdmissionData(WalletAdmissionData),
-    DepositNotification(DepositNotification),
-    WithdrawalRequestNotification(WithdrawalRequestNotification),
-    WithdrawalNotification(WithdrawalNotification),
-    WalletDataNotification(WalletDataNotification),
+    IncomingTransfer(Box<TransferAudit>),
+    IncomingReceipt(Box<TransferAudit>),
+    IncomingConfirmation(Box<TransferAudit>),
+    TimeoutTransfer(Box<TransferAudit>),
+    MatchedSwap(Box<TransferAudit>),
+    FinalizedSwap(Box<TransferAudit>),
+    CanceledSwap(Box<TransferAudit>),
+    RegisteredWallet(WalletAdmissionData),
+    ConfirmedDeposit(DepositNotification),
+    RequestWithdrawal(WithdrawalRequestNotification),
+    ConfirmedWithdrawal(WithdrawalNotification),
+    CheckpointCreated(Box<WalletDataNotification>),
 }
 
-/// `TokenEvent` is the model of token event type data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
-pub struct TokenEvent(pub TransferAudit);
+impl WalletEvent {
+    /// `decode` decodes the data of a wallet event of a type.
+    pub fn decode(r#type: &WalletEventType, data: json::Value) -> json::Result<WalletEvent> {
+        use WalletEventType::*;
+
+        let event = match r#type {
+            IncomingTransfer => WalletEvent::IncomingTransfer(json::from_value(data)?),
+            IncomingReceipt => WalletEvent::IncomingReceipt(json::from_value(data)?),
+            IncomingConfirmation => WalletEvent::IncomingConfirmation(json::from_value(data)?),
+            TimeoutTransfer => WalletEvent::TimeoutTransfer(json::from_value(data)?),
+            MatchedSwap => WalletEvent::MatchedSwap(json::from_value(data)?),
+            FinalizedSwap => WalletEvent::FinalizedSwap(json::from_value(data)?),
+            CanceledSwap => WalletEvent::CanceledSwap(json::from_value(data)?),
+            RegisteredWallet => WalletEvent::RegisteredWallet(json::from_value(data)?),
+            ConfirmedDeposit => WalletEvent::ConfirmedDeposit(json::from_value(data)?),
+            RequestWithdrawal => WalletEvent::RequestWithdrawal(json::from_value(data)?),
+            ConfirmedWithdrawal => WalletEvent::ConfirmedWithdrawal(json::from_value(data)?),
+            CheckpointCreated => WalletEvent::CheckpointCreated(json::from_value(data)?),
+        };
+
+        Ok(event)
+    }
+
+    /// `event_type` returns the type of the event.
+    pub fn event_type(&self) -> WalletEventType {
+        match self {
+            WalletEvent::IncomingTransfer(_) => WalletEventType::IncomingTransfer,
+            WalletEvent::IncomingReceipt(_) => WalletEventType::IncomingReceipt,
+            WalletEvent::IncomingConfirmation(_) => WalletEventType::IncomingConfirmation,
+            WalletEvent::TimeoutTransfer(_) => WalletEventType::TimeoutTransfer,
+            WalletEvent::MatchedSwap(_) => WalletEventType::MatchedSwap,
+            WalletEvent::FinalizedSwap(_) => WalletEventType::FinalizedSwap,
+            WalletEvent::CanceledSwap(_) => WalletEventType::CanceledSwap,
+            WalletEvent::RegisteredWallet(_) => WalletEventType::RegisteredWallet,
+            WalletEvent::ConfirmedDeposit(_) => WalletEventType::ConfirmedDeposit,
+            WalletEvent::RequestWithdrawal(_) => WalletEventType::RequestWithdrawal,
+            WalletEvent::ConfirmedWithdrawal(_) => WalletEventType::ConfirmedWithdrawal,
+            WalletEvent::CheckpointCreated(_) => WalletEventType::CheckpointCreated,
+        }
+    }
+
+    /// `transfer` returns the transfer of the event, if any.
+    pub fn transfer(&self) -> Option<&TransferAudit> {
+        match self {
+            WalletEvent::IncomingTransfer(transfer)
+            | WalletEvent::IncomingReceipt(transfer)
+            | WalletEvent::IncomingConfirmation(transfer)
+            | WalletEvent::TimeoutTransfer(transfer)
+            | WalletEvent::MatchedSwap(transfer)
+            | WalletEvent::FinalizedSwap(transfer)
+            | WalletEvent::CanceledSwap(transfer) => Some(transfer),
+            _ => None,
+        }
+    }
+}
+
+/// `TokenPairEvent` is the model of token pair event type data, with one variant per `TokenPairEventType`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TokenPairEvent {
+    IncomingSwap(Box<TransferAudit>),
+    MatchedSwap(Box<TransferAudit>),
+}
+
+impl TokenPairEvent {
+    /// `decode` decodes the data of a token pair event of a type.
+    pub fn decode(r#type: &TokenPairEventType, data: json::Value) -> json::Result<TokenPairEvent> {
+        let event = match r#type {
+            TokenPairEventType::IncomingSwap => {
+                TokenPairEvent::IncomingSwap(json::from_value(data)?)
+            }
+            TokenPairEventType::MatchedSwap => TokenPairEvent::MatchedSwap(json::from_value(data)?),
+        };
+
+        Ok(event)
+    }
+
+    /// `event_type` returns the type of the event.
+    pub fn event_type(&self) -> TokenPairEventType {
+        match self {
+            TokenPairEvent::IncomingSwap(_) => TokenPairEventType::IncomingSwap,
+            TokenPairEvent::MatchedSwap(_) => TokenPairEventType::MatchedSwap,
+        }
+    }
+
+    /// `transfer` returns the swap of the event.
+    pub fn transfer(&self) -> &TransferAudit {
+        match self {
+            TokenPairEvent::IncomingSwap(transfer) | TokenPairEvent::MatchedSwap(transfer) => {
+                transfer
+            }
+        }
+    }
+}
 
 /// `Event` is the model of event data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum Event {
     Wallet(WalletEvent),
-    Token(TokenEvent),
+    TokenPair(TokenPairEvent),
+}
+
+impl Event {
+    /// `decode` decodes the data of an event of a type.
+    pub fn decode(r#type: &EventType, data: json::Value) -> json::Result<Event> {
+        match r#type {
+            EventType::Wallet(r#type) => WalletEvent::decode(r#type, data).map(Event::Wallet),
+            EventType::TokenPair(r#type) => {
+                TokenPairEvent::decode(r#type, data).map(Event::TokenPair)
+            }
+        }
+    }
+
+    /// `event_type` returns the type of the event.
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Event::Wallet(event) => EventType::Wallet(event.event_type()),
+            Event::TokenPair(event) => EventType::TokenPair(event.event_type()),
+        }
+    }
+
+    /// `transfer` returns the transfer of the event, if any.
+    pub fn transfer(&self) -> Option<&TransferAudit> {
+        match self {
+            Event::Wallet(event) => event.transfer(),
+            Event::TokenPair(event) => Some(event.transfer()),
+        }
+    }
 }
 
 /// `NotificationData` is the data of a `NotificationResponse`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// The data is decoded according to the type string sent by the hub, so that the
+/// type and the data of a notification always agree. `MATCHED_SWAP` is both a wallet
+/// and a token pair event type with the same data, and is decoded as a wallet event;
+/// notifications don't name their stream, so the `WsClient` converts it with
+/// `into_token_pair` when only a token pair stream of the swap is subscribed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct NotificationData {
     pub r#type: EventType,
     pub data: Event,
 }
 
+impl NotificationData {
+    /// `new` creates a new `NotificationData` of an event.
+    pub fn new(data: Event) -> NotificationData {
+        NotificationData {
+            r#type: data.event_type(),
+            data,
+        }
+    }
+
+    /// `into_token_pair` converts a `MATCHED_SWAP` wallet event into a token pair event.
+    pub fn into_token_pair(self) -> NotificationData {
+        match self.data {
+            Event::Wallet(WalletEvent::MatchedSwap(transfer)) => {
+                NotificationData::new(Event::TokenPair(TokenPairEvent::MatchedSwap(transfer)))
+            }
+            data => NotificationData::new(data),
+        }
+    }
+}
+
+/// `RawNotificationData` is the data of a `NotificationResponse` before its decoding.
+#[derive(Deserialize)]
+struct RawNotificationData {
+    r#type: EventType,
+    data: json::Value,
+}
+
+impl<'de> Deserialize<'de> for NotificationData {
+    fn deserialize<D>(deserializer: D) -> Result<NotificationData, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RawNotificationData::deserialize(deserializer)?;
+        let data = Event::decode(&raw.r#type, raw.data).map_err(de::Error::custom)?;
+
+        Ok(NotificationData {
+            r#type: raw.r#type,
+            data,
+        })
+    }
+}
+
 /// `NotificationResponse` is a notification response in the WS Notification API.
 pub type NotificationResponse = GenericResponse<NotificationType, NotificationData>;
 
 /// `ResponseType` is the type of a `ResponseRequest`.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ResponseType;
 
+tag!(ResponseType, "response");
+
 /// `Response` is a response in the WS Notification API.
 pub type Response<Data> = GenericResponse<ResponseType, Data>;
 
 /// `StreamData` is response data containing a stream of textual data.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct StreamData<Op> {
     pub op: Op,
     pub stream: Vec<String>,
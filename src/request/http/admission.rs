@@ -1,15 +1,37 @@
 //! `admission` defines the functions for interacting with
 //! the HTTP Admission API.
 
-pub const ADMISSION_CREATE_ERROR_CODES: &'static [&'static str] = &[
-    "TOKEN_NOT_REGISTERED",
-    "WALLET_BLACKLISTED",
-    "WALLET_ALREADY_ADMITTED",
-    "INVALID_ADMISSION_SIGNATURE",
-];
-
-pub const ADMISSION_CREATE_BULK_ERROR_CODES: &'static [&'static str] = &[
-    "TOO_MANY_ADMISSION_REQUESTS",
-    "TOKEN_NOT_REGISTERED",
-    "INVALID_ADMISSION_SIGNATURE",
-];
+use crate::model::http::admission::Admission;
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+error_codes! {
+    /// `AdmissionCreateError` is an error code of the Admission create endpoint.
+    AdmissionCreateError {
+        TokenNotRegistered(Fatal) => "TOKEN_NOT_REGISTERED",
+        WalletBlacklisted(Fatal) => "WALLET_BLACKLISTED",
+        WalletAlreadyAdmitted(NeedsResync) => "WALLET_ALREADY_ADMITTED",
+        InvalidAdmissionSignature(Fatal) => "INVALID_ADMISSION_SIGNATURE",
+    }
+}
+
+error_codes! {
+    /// `AdmissionCreateBulkError` is an error code of the Admission bulk create endpoint.
+    AdmissionCreateBulkError {
+        TooManyAdmissionRequests(Retryable) => "TOO_MANY_ADMISSION_REQUESTS",
+        TokenNotRegistered(Fatal) => "TOKEN_NOT_REGISTERED",
+        InvalidAdmissionSignature(Fatal) => "INVALID_ADMISSION_SIGNATURE",
+    }
+}
+
+impl HubClient {
+    /// `create_admission` admits a wallet to the hub.
+    pub async fn create_admission(&self, admission: &Admission) -> Result<Admission> {
+        self.post("admission/", admission).await
+    }
+
+    /// `create_bulk_admission` admits a set of wallets to the hub.
+    pub async fn create_bulk_admission(&self, admissions: &[Admission]) -> Result<Vec<Admission>> {
+        self.post("admission/bulk/", &admissions).await
+    }
+}
@@ -0,0 +1,38 @@
+//! `analytics` defines the functions for interacting with
+//! the HTTP Analytics API.
+
+use crate::model::http::analytics::*;
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+impl HubClient {
+    /// `operator_status` returns the status of the hub operator.
+    pub async fn operator_status(&self) -> Result<OperatorStatus> {
+        self.get("analytics/status").await
+    }
+
+    /// `analytics_admissions` returns the aggregated admissions of the hub.
+    pub async fn analytics_admissions(&self) -> Result<Admissions> {
+        self.get("analytics/wallets").await
+    }
+
+    /// `analytics_challenges` returns the aggregated challenges of the hub.
+    pub async fn analytics_challenges(&self) -> Result<Challenges> {
+        self.get("analytics/challenges").await
+    }
+
+    /// `analytics_deposits` returns the aggregated deposits of the hub.
+    pub async fn analytics_deposits(&self) -> Result<Deposits> {
+        self.get("analytics/deposits").await
+    }
+
+    /// `analytics_transfers` returns the aggregated transfers of the hub.
+    pub async fn analytics_transfers(&self) -> Result<Transfers> {
+        self.get("analytics/transfers").await
+    }
+
+    /// `analytics_withdrawals` returns the aggregated withdrawals of the hub.
+    pub async fn analytics_withdrawals(&self) -> Result<Withdrawals> {
+        self.get("analytics/withdrawals").await
+    }
+}
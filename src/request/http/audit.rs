@@ -0,0 +1,84 @@
+//! `audit` defines the functions for interacting with
+//! the HTTP Audit API.
+
+use crate::model::common::{Address, Token, Wallet};
+use crate::model::http::audit::*;
+use crate::request::http::client::HubClient;
+use crate::request::http::pagination::{TransferFilter, TransferStream};
+use crate::result::Result;
+
+impl HubClient {
+    /// `blocks` returns the latest and confirmed blocks of the hub.
+    pub async fn blocks(&self) -> Result<Blocks> {
+        self.get("audit/").await
+    }
+
+    /// `tokens` returns the tokens registered in the hub.
+    pub async fn tokens(&self) -> Result<Vec<Token>> {
+        self.get("audit/tokens").await
+    }
+
+    /// `order_book` returns the order book of a token pair.
+    pub async fn order_book(
+        &self,
+        left_token: &Address,
+        right_token: &Address,
+    ) -> Result<TokenOrderBook> {
+        let path = format!("audit/swaps/{}/{}", left_token, right_token);
+        self.get(&path).await
+    }
+
+    /// `token_matchings` returns the matchings of a token pair.
+    pub async fn token_matchings(
+        &self,
+        left_token: &Address,
+        right_token: &Address,
+    ) -> Result<Vec<TokenMatching>> {
+        let path = format!("audit/swaps/{}/{}/matchings", left_token, right_token);
+        self.get(&path).await
+    }
+
+    /// `token_matchings_page` returns a page of the matchings of a token pair,
+    /// from the oldest, starting at an offset.
+    pub async fn token_matchings_page(
+        &self,
+        left_token: &Address,
+        right_token: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<TokenMatching>> {
+        let path = format!(
+            "audit/swaps/{}/{}/matchings?offset={}&limit={}",
+            left_token, right_token, offset, limit
+        );
+        self.get(&path).await
+    }
+
+    /// `transfers` returns the first page of the transfers of the hub.
+    pub async fn transfers(&self) -> Result<Transfers> {
+        self.get("audit/transactions").await
+    }
+
+    /// `transfer_stream` returns a stream of all the transfers of the hub selected by a filter.
+    pub fn transfer_stream(&self, filter: TransferFilter) -> Result<TransferStream> {
+        TransferStream::new(self, filter)
+    }
+
+    /// `transfer` returns a transfer of the hub by id.
+    pub async fn transfer(&self, id: i64) -> Result<Transfer> {
+        let path = format!("audit/transactions/{}", id);
+        self.get(&path).await
+    }
+
+    /// `wallet_state` returns the state of a wallet.
+    pub async fn wallet_state(&self, wallet: &Wallet) -> Result<WalletState> {
+        let path = format!("audit/{}/{}", wallet.token, wallet.address);
+        self.get(&path).await
+    }
+
+    /// `whois` returns the admission data of a wallet.
+    pub async fn whois(&self, wallet: &Wallet) -> Result<WhoIs> {
+        let path = format!("audit/{}/{}/whois", wallet.token, wallet.address);
+        self.get(&path).await
+    }
+}
@@ -0,0 +1,101 @@
+//! `client` defines the client of the HTTP API.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json as json;
+
+use crate::error::Error;
+use crate::request::http::error::ErrorResponse;
+use crate::result::Result;
+
+use std::future::Future;
+
+/// `HubClient` is an asynchronous client of a Liquidity Network hub HTTP API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HubClient {
+    base_url: String,
+}
+
+impl HubClient {
+    /// `DEFAULT_SCHEME` is the default scheme used to connect to a hub.
+    pub const DEFAULT_SCHEME: &'static str = "https";
+
+    /// `new` creates a new `HubClient` connecting to a host with the default scheme.
+    pub fn new(host: &str) -> HubClient {
+        HubClient::with_scheme(Self::DEFAULT_SCHEME, host)
+    }
+
+    /// `with_scheme` creates a new `HubClient` connecting to a host with a specific scheme.
+    pub fn with_scheme(scheme: &str, host: &str) -> HubClient {
+        let base_url = format!("{}://{}", scheme, host.trim_end_matches('/'));
+
+        HubClient { base_url }
+    }
+
+    /// `base_url` returns the base URL of the hub.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// `url` returns the URL of a path of the hub.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// `get` sends a GET request to a path of the hub.
+    pub(crate) async fn get<T>(&self, path: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        Self::send(surf::get(self.url(path))).await
+    }
+
+    /// `get_url` sends a GET request to a URL of the hub, such as a pagination link.
+    pub(crate) async fn get_url<T>(&self, url: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        Self::send(surf::get(url)).await
+    }
+
+    /// `post` sends a POST request with a JSON body to a path of the hub.
+    pub(crate) async fn post<B, T>(&self, path: &str, body: &B) -> Result<T>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        Self::send(surf::post(self.url(path)).body_json(body)?).await
+    }
+
+    /// `put` sends a PUT request with a JSON body to a path of the hub.
+    pub(crate) async fn put<B, T>(&self, path: &str, body: &B) -> Result<T>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        Self::send(surf::put(self.url(path)).body_json(body)?).await
+    }
+
+    /// `send` awaits a request and deserializes its JSON response.
+    async fn send<R, T>(req: R) -> Result<T>
+    where
+        R: Future<Output = std::result::Result<surf::Response, surf::Exception>>,
+        T: DeserializeOwned,
+    {
+        let mut res = req.await?;
+        let status = res.status().as_u16();
+        let body = res.body_bytes().await?;
+
+        if res.status().is_client_error() {
+            if let Ok(response) = json::from_slice::<ErrorResponse>(&body) {
+                return Err(Error::Hub { status, response });
+            }
+        }
+
+        if !res.status().is_success() {
+            return Err(Error::HTTPStatus { status });
+        }
+
+        json::from_slice(&body).map_err(|e| e.into())
+    }
+}
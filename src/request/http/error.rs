@@ -1,11 +1,188 @@
 //! `error` contains the HTTP API error types.
 
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// `ErrorData` is an error returned by the HTTP API.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ErrorData {
+    #[serde(deserialize_with = "one_or_many")]
     pub code: Vec<String>,
+    #[serde(default)]
     pub message: String,
 }
 
+/// `ErrorResponse` is the body of an error response of the HTTP API.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ErrorResponse {
+    #[serde(default)]
     pub non_field_errors: Vec<ErrorData>,
-    pub field_errors: Vec<ErrorData>,
+    #[serde(flatten)]
+    pub field_errors: BTreeMap<String, Vec<ErrorData>>,
+}
+
+impl ErrorResponse {
+    /// `codes` returns the error codes of the response, paired
+    /// with the name of the field they refer to, if any.
+    pub fn codes(&self) -> Vec<(Option<&str>, &str)> {
+        let non_field_codes = self
+            .non_field_errors
+            .iter()
+            .flat_map(|data| data.code.iter().map(|code| (None, code.as_str())));
+
+        let field_codes = self.field_errors.iter().flat_map(|(field, errors)| {
+            errors.iter().flat_map(move |data| {
+                data.code
+                    .iter()
+                    .map(move |code| (Some(field.as_str()), code.as_str()))
+            })
+        });
+
+        non_field_codes.chain(field_codes).collect()
+    }
+
+    /// `has_code` returns if the response contains an error code.
+    pub fn has_code(&self, code: &str) -> bool {
+        self.codes().iter().any(|(_, c)| *c == code)
+    }
+
+    /// `typed_codes` returns the error codes of the response as typed error codes.
+    pub fn typed_codes<'a, T>(&'a self) -> Vec<T>
+    where
+        T: From<&'a str>,
+    {
+        self.codes()
+            .into_iter()
+            .map(|(_, code)| T::from(code))
+            .collect()
+    }
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (field, code)) in self.codes().into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            match field {
+                Some(field) => write!(f, "{} on field {}", code, field)?,
+                None => f.write_str(code)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// `one_or_many` deserializes either a string or a sequence of strings.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    match OneOrMany::deserialize(deserializer) {
+        Ok(OneOrMany::One(code)) => Ok(vec![code]),
+        Ok(OneOrMany::Many(codes)) => Ok(codes),
+        Err(_) => Err(de::Error::custom("expected a code or a list of codes")),
+    }
+}
+
+/// `ErrorClass` is the class of an error code of the HTTP API,
+/// telling how a client should react to it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ErrorClass {
+    /// `Retryable` errors are transient, and the same request can be retried.
+    Retryable,
+    /// `NeedsResync` errors are caused by a stale client state, and the request
+    /// can be retried once the state is resynced with the hub.
+    NeedsResync,
+    /// `Fatal` errors are caused by an invalid request, and retrying does not help.
+    Fatal,
+    /// `Unknown` errors have a code not known by the client, so it cannot tell
+    /// how to react to them.
+    Unknown,
+}
+
+/// `error_codes` defines an enum of the error codes of an HTTP API endpoint,
+/// each with its `ErrorClass`. Codes not known by the client are kept in
+/// an `Unknown` variant, of class `ErrorClass::Unknown`.
+macro_rules! error_codes {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident($class:ident) => $code:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        impl $name {
+            /// `CODES` are the error codes known by the client.
+            pub const CODES: &'static [&'static str] = &[$($code,)*];
+
+            /// `code` returns the error code.
+            pub fn code(&self) -> &str {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Unknown(code) => code,
+                }
+            }
+
+            /// `class` returns the `ErrorClass` of the error code.
+            pub fn class(&self) -> $crate::request::http::error::ErrorClass {
+                match self {
+                    $($name::$variant => $crate::request::http::error::ErrorClass::$class,)*
+                    $name::Unknown(_) => $crate::request::http::error::ErrorClass::Unknown,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(code: &str) -> $name {
+                match code {
+                    $($code => $name::$variant,)*
+                    _ => $name::Unknown(code.to_owned()),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.write_str(self.code())
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serializer.serialize_str(self.code())
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<$name, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                let code = String::deserialize(deserializer)?;
+                Ok($name::from(code.as_str()))
+            }
+        }
+    };
 }
@@ -1,11 +1,27 @@
 //! `http` defines the models of the Liquidity Network hub HTTP API.
 
+/// `client` defines the HTTP API client.
+pub mod client;
+
 /// `error` defines the HTTP API error types.
+#[macro_use]
 pub mod error;
 
 /// `admission` defines the Admission HTTP request API.
 pub mod admission;
 
+/// `analytics` defines the Analytics HTTP request API.
+pub mod analytics;
+
+/// `audit` defines the Audit HTTP request API.
+pub mod audit;
+
+/// `pagination` defines the paginated iteration of the Audit HTTP request API.
+pub mod pagination;
+
+/// `sla` defines the SLA HTTP request API.
+pub mod sla;
+
 /// `swap` defines the Swap HTTP request API.
 pub mod swap;
 
@@ -0,0 +1,380 @@
+//! `pagination` defines the paginated iteration of the transfers
+//! of the HTTP Audit API.
+
+use async_std::task::{self, JoinHandle};
+use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::model::common::Address;
+use crate::model::http::audit::{ConciseTransfer, Transfers};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// `TransferKind` is the kind of a transfer of the hub.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferKind {
+    Transfer,
+    Swap,
+}
+
+/// `TransferFilter` selects the transfers of a `TransferStream`.
+///
+/// The filters are sent to the hub as query parameters, and applied again to
+/// every page received, so that a hub ignoring some of them still yields
+/// only the selected transfers.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransferFilter {
+    pub wallet: Option<Address>,
+    pub token: Option<Address>,
+    pub min_eon: Option<u64>,
+    pub max_eon: Option<u64>,
+    pub kind: Option<TransferKind>,
+    pub passive: Option<bool>,
+}
+
+impl TransferFilter {
+    /// `new` creates a new `TransferFilter` selecting every transfer.
+    pub fn new() -> TransferFilter {
+        TransferFilter::default()
+    }
+
+    /// `with_wallet` selects the transfers sent or received by an address.
+    pub fn with_wallet(mut self, wallet: Address) -> TransferFilter {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    /// `with_token` selects the transfers debiting or crediting a token.
+    pub fn with_token(mut self, token: Address) -> TransferFilter {
+        self.token = Some(token);
+        self
+    }
+
+    /// `with_eons` selects the transfers of a range of eons, inclusive.
+    pub fn with_eons(mut self, min_eon: u64, max_eon: u64) -> TransferFilter {
+        self.min_eon = Some(min_eon);
+        self.max_eon = Some(max_eon);
+        self
+    }
+
+    /// `with_kind` selects either the transfers or the swaps.
+    pub fn with_kind(mut self, kind: TransferKind) -> TransferFilter {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// `with_passive` selects either the passive or the active transfers.
+    pub fn with_passive(mut self, passive: bool) -> TransferFilter {
+        self.passive = Some(passive);
+        self
+    }
+
+    /// `query` returns the query parameters of the filter.
+    pub fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+
+        if let Some(wallet) = &self.wallet {
+            query.push(("wallet", wallet.to_string()));
+        }
+
+        if let Some(token) = &self.token {
+            query.push(("token", token.to_string()));
+        }
+
+        if let Some(min_eon) = self.min_eon {
+            query.push(("eon_number__gte", min_eon.to_string()));
+        }
+
+        if let Some(max_eon) = self.max_eon {
+            query.push(("eon_number__lte", max_eon.to_string()));
+        }
+
+        if let Some(kind) = self.kind {
+            query.push(("swap", (kind == TransferKind::Swap).to_string()));
+        }
+
+        if let Some(passive) = self.passive {
+            query.push(("passive", passive.to_string()));
+        }
+
+        query
+    }
+
+    /// `matches` returns if a transfer is selected by the filter.
+    pub fn matches(&self, transfer: &ConciseTransfer) -> bool {
+        let eon_number = transfer.eon_number as u64;
+        let kind = if transfer.swap {
+            TransferKind::Swap
+        } else {
+            TransferKind::Transfer
+        };
+
+        if let Some(wallet) = self.wallet {
+            if transfer.wallet.address != wallet && transfer.recipient.address != wallet {
+                return false;
+            }
+        }
+
+        if let Some(token) = self.token {
+            if transfer.wallet.token != token && transfer.recipient.token != token {
+                return false;
+            }
+        }
+
+        if self.min_eon.map(|min| eon_number < min).unwrap_or(false)
+            || self.max_eon.map(|max| eon_number > max).unwrap_or(false)
+        {
+            return false;
+        }
+
+        self.kind.unwrap_or(kind) == kind
+            && self.passive.unwrap_or(transfer.passive) == transfer.passive
+    }
+}
+
+/// `TransferCursor` is the position of a `TransferStream`, from which it can be resumed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransferCursor {
+    /// `page` is the URL of the page being read, or `None` once the stream is exhausted.
+    pub page: Option<String>,
+    /// `position` is the number of transfers of the page already read.
+    pub position: usize,
+    pub filter: TransferFilter,
+}
+
+impl TransferCursor {
+    /// `is_exhausted` returns if the stream of the cursor has no more transfers.
+    pub fn is_exhausted(&self) -> bool {
+        self.page.is_none()
+    }
+}
+
+/// `Page` is a page of transfers fetched by a `TransferStream`.
+#[derive(Clone, Debug)]
+struct Page {
+    url: String,
+    results: Vec<ConciseTransfer>,
+    position: usize,
+}
+
+/// `TransferStream` is a `Stream` of the transfers of the hub, walking the pages
+/// of the Audit API lazily by following their `next` links.
+///
+/// While a page is being read, the following pages are fetched in the background,
+/// one at a time: the prefetch depth bounds the number of pages buffered ahead of
+/// the page being read, not the number of pages fetched concurrently.
+///
+/// A page failing to be fetched is yielded as an error, and fetched again on the next
+/// poll up to the maximum number of retries in a row, after which the stream ends.
+/// The cursor of a stream ended this way still points at the failed page.
+#[derive(Debug)]
+pub struct TransferStream {
+    client: HubClient,
+    filter: TransferFilter,
+    prefetch: usize,
+    current: Option<Page>,
+    ready: VecDeque<Page>,
+    pending: Option<(String, JoinHandle<Result<Transfers>>)>,
+    next: Option<String>,
+    skip: usize,
+    max_retries: usize,
+    failures: usize,
+    failed: Option<String>,
+}
+
+impl TransferStream {
+    /// `DEFAULT_PREFETCH` is the default number of pages fetched ahead of the page being read.
+    pub const DEFAULT_PREFETCH: usize = 1;
+
+    /// `DEFAULT_MAX_RETRIES` is the default number of times a failed page is fetched again.
+    pub const DEFAULT_MAX_RETRIES: usize = 3;
+
+    /// `new` creates a new `TransferStream` of the transfers selected by a filter.
+    pub fn new(client: &HubClient, filter: TransferFilter) -> Result<TransferStream> {
+        let url = Url::parse_with_params(&client.url("audit/transactions"), filter.query())?;
+
+        Ok(TransferStream::with_page(
+            client,
+            filter,
+            Some(url.to_string()),
+            0,
+        ))
+    }
+
+    /// `resume` creates a new `TransferStream` from the position of a cursor.
+    pub fn resume(client: &HubClient, cursor: TransferCursor) -> TransferStream {
+        TransferStream::with_page(client, cursor.filter, cursor.page, cursor.position)
+    }
+
+    /// `with_page` creates a new `TransferStream` starting at a position of a page.
+    fn with_page(
+        client: &HubClient,
+        filter: TransferFilter,
+        page: Option<String>,
+        skip: usize,
+    ) -> TransferStream {
+        TransferStream {
+            client: client.clone(),
+            filter,
+            prefetch: Self::DEFAULT_PREFETCH,
+            current: None,
+            ready: VecDeque::new(),
+            pending: None,
+            next: page,
+            skip,
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            failures: 0,
+            failed: None,
+        }
+    }
+
+    /// `with_prefetch` sets the number of pages buffered ahead of the page being read.
+    pub fn with_prefetch(mut self, prefetch: usize) -> TransferStream {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// `with_max_retries` sets the number of times in a row a failed page is fetched again.
+    pub fn with_max_retries(mut self, max_retries: usize) -> TransferStream {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// `cursor` returns the position of the stream.
+    pub fn cursor(&self) -> TransferCursor {
+        let (page, position) = match &self.current {
+            Some(page) if page.position < page.results.len() => {
+                (Some(page.url.clone()), page.position)
+            }
+            _ => match self.ready.front() {
+                Some(page) => (Some(page.url.clone()), page.position),
+                None => match &self.pending {
+                    Some((url, _)) => (Some(url.clone()), self.skip),
+                    None => (self.next.clone().or_else(|| self.failed.clone()), self.skip),
+                },
+            },
+        };
+
+        TransferCursor {
+            page,
+            position,
+            filter: self.filter.clone(),
+        }
+    }
+
+    /// `fetch` starts fetching the next page, if the prefetch depth allows it.
+    fn fetch(&mut self) {
+        let reading = match &self.current {
+            Some(page) => page.position < page.results.len(),
+            None => false,
+        };
+        let buffered = self.ready.len() + reading as usize;
+
+        if self.pending.is_some() || buffered > self.prefetch {
+            return;
+        }
+
+        if let Some(url) = self.next.take() {
+            let client = self.client.clone();
+            let page = url.clone();
+            let handle = task::spawn(async move { client.get_url(&page).await });
+
+            self.pending = Some((url, handle));
+        }
+    }
+
+    /// `poll_fetch` polls the page being fetched, queueing it once received.
+    /// On error, the page is fetched again on the next poll, unless it already failed
+    /// the maximum number of retries in a row.
+    fn poll_fetch(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        let (url, handle) = match &mut self.pending {
+            Some(pending) => pending,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let res = match Pin::new(handle).poll(cx) {
+            Poll::Ready(res) => res,
+            Poll::Pending => return Poll::Pending,
+        };
+        let url = url.clone();
+        self.pending = None;
+
+        match res {
+            Ok(transfers) => {
+                self.failures = 0;
+                self.next = transfers.next.map(|next| next.to_string());
+                self.ready.push_back(Page {
+                    url,
+                    results: transfers.results,
+                    position: std::mem::take(&mut self.skip),
+                });
+
+                Poll::Ready(Ok(()))
+            }
+            Err(err) => {
+                if self.failures < self.max_retries {
+                    self.failures += 1;
+                    self.next = Some(url);
+                } else {
+                    self.failed = Some(url);
+                }
+
+                Poll::Ready(Err(err))
+            }
+        }
+    }
+
+    /// `take` returns the next selected transfer of the fetched pages.
+    fn take(&mut self) -> Option<ConciseTransfer> {
+        loop {
+            match &mut self.current {
+                Some(page) if page.position < page.results.len() => {
+                    let transfer = &page.results[page.position];
+                    page.position += 1;
+
+                    if self.filter.matches(transfer) {
+                        return Some(transfer.clone());
+                    }
+                }
+                _ => {
+                    self.current = Some(self.ready.pop_front()?);
+                }
+            }
+        }
+    }
+}
+
+impl Stream for TransferStream {
+    type Item = Result<ConciseTransfer>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            self.fetch();
+
+            let fetched = self.poll_fetch(cx);
+
+            if let Poll::Ready(Err(err)) = fetched {
+                return Poll::Ready(Some(Err(err)));
+            }
+
+            if let Some(transfer) = self.take() {
+                return Poll::Ready(Some(Ok(transfer)));
+            }
+
+            if fetched.is_pending() {
+                return Poll::Pending;
+            }
+
+            if self.pending.is_none() && self.next.is_none() {
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
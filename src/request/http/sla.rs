@@ -0,0 +1,20 @@
+//! `sla` defines the functions for interacting with
+//! the HTTP SLA (Service Level Agreement) API.
+
+use crate::model::common::Wallet;
+use crate::model::http::sla::{WalletSLA, SLA};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+impl HubClient {
+    /// `sla` returns the SLA offered by the hub.
+    pub async fn sla(&self) -> Result<SLA> {
+        self.get("sla/").await
+    }
+
+    /// `wallet_sla` returns the SLA of a wallet.
+    pub async fn wallet_sla(&self, wallet: &Wallet) -> Result<WalletSLA> {
+        let path = format!("sla/{}/{}", wallet.token, wallet.address);
+        self.get(&path).await
+    }
+}
@@ -1,61 +1,110 @@
 //! `swap` defines the functions for interacting with
 //! the HTTP Swap API.
 
-pub const SWAP_CREATE_ERROR_CODES: &'static [&'static str] = &[
-    "INVALID_DEBIT_AMOUNT",
-    "INVALID_CREDIT_AMOUNT",
-    "SWAPPING_DISABLED",
-    "TOO_MANY_FUTURE_SIGNATURES",
-    "WRONG_NUMBER_OF_SIGNATURES",
-    "DEBIT_WALLET_NOT_ADMITTED",
-    "CREDIT_WALLET_NOT_ADMITTED",
-    "DEBIT_CREDIT_WALLET_ADDRESS_MISMATCH",
-    "DEBIT_CREDIT_TOKEN_ADDRESS_MATCH",
-    "TOKEN_PAIR_BLOCKED",
-    "EON_NUMBER_OUT_OF_SYNC",
-    "DEBIT_WALLET_CANNOT_ADD_TRANSACTION",
-    "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
-    "DEBIT_WALLET_OVERSPENDING",
-    "DEBIT_WALLET_BALANCE_AMOUNT_MISMATCH",
-    "CREDIT_WALLET_BALANCE_NOT_ZERO",
-    "INVALID_DEBIT_BALANCE_SIGNATURE",
-    "INVALID_CREDIT_BALANCE_SIGNATURE",
-    "INVALID_FUTURE_CREDIT_SIGNATURE",
-    "INVALID_CREDIT_SIGNATURE",
-    "INVALID_FUTURE_DEBIT_SIGNATURE",
-    "INVALID_DEBIT_SIGNATURE",
-    "INVALID_FUTURE_CREDIT_FULFILLMENT_SIGNATURE",
-    "INVALID_CREDIT_FULFILLMENT_SIGNATURE",
-];
-
-pub const SWAP_CANCEL_UPDATE_ERROR_CODES: &'static [&'static str] = &[
-    "WRONG_NUMBER_OF_DEBIT_SIGNATURES",
-    "WRONG_NUMBER_OF_CREDIT_SIGNATURES",
-    "SWAP_NOT_FROZEN",
-    "SWAP_ALREADY_CLOSED",
-    "MISSING_FREEZING_SIGNATURE",
-    "SWAP_ALREADY_CANCELLED",
-    "INVALID_CREDIT_SIGNATURE",
-    "INVALID_FUTURE_CREDIT_SIGNATURE",
-    "INVALID_DEBIT_SIGNATURE",
-    "INVALID_FUTURE_DEBIT_SIGNATURE",
-];
-
-pub const SWAP_FINALIZE_UPDATE_ERROR_CODES: &'static [&'static str] = &[
-    "SWAP_NOT_FULFILLED",
-    "SWAP_ALREADY_FROZEN",
-    "SWAP_ALREADY_VOIDED",
-    "SWAP_ALREADY_CLOSED",
-    "SWAP_ALREADY_FINALIZED",
-    "WRONG_NUMBER_OF_CREDIT_SIGNATURES",
-    "INVALID_CREDIT_SIGNATURE",
-    "INVALID_FUTURE_CREDIT_SIGNATURE",
-];
-
-pub const SWAP_FREEZE_UPDATE_ERROR_CODES: &'static [&'static str] = &[
-    "SWAP_ALREADY_FULFILLED",
-    "SWAP_ALREADY_FROZEN",
-    "SWAP_ALREADY_VOIDED",
-    "SWAP_ALREADY_CLOSED",
-    "INVALID_FREEZING_SIGNATURE",
-];
+use crate::model::http::swap::*;
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+error_codes! {
+    /// `SwapCreateError` is an error code of the Swap create endpoint.
+    SwapCreateError {
+        InvalidDebitAmount(Fatal) => "INVALID_DEBIT_AMOUNT",
+        InvalidCreditAmount(Fatal) => "INVALID_CREDIT_AMOUNT",
+        SwappingDisabled(Retryable) => "SWAPPING_DISABLED",
+        TooManyFutureSignatures(NeedsResync) => "TOO_MANY_FUTURE_SIGNATURES",
+        WrongNumberOfSignatures(NeedsResync) => "WRONG_NUMBER_OF_SIGNATURES",
+        DebitWalletNotAdmitted(Fatal) => "DEBIT_WALLET_NOT_ADMITTED",
+        CreditWalletNotAdmitted(Fatal) => "CREDIT_WALLET_NOT_ADMITTED",
+        DebitCreditWalletAddressMismatch(Fatal) => "DEBIT_CREDIT_WALLET_ADDRESS_MISMATCH",
+        DebitCreditTokenAddressMatch(Fatal) => "DEBIT_CREDIT_TOKEN_ADDRESS_MATCH",
+        TokenPairBlocked(Fatal) => "TOKEN_PAIR_BLOCKED",
+        EonNumberOutOfSync(NeedsResync) => "EON_NUMBER_OUT_OF_SYNC",
+        DebitWalletCannotAddTransaction(Retryable) => "DEBIT_WALLET_CANNOT_ADD_TRANSACTION",
+        CreditWalletCannotAddTransaction(Retryable) => "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
+        DebitWalletOverspending(NeedsResync) => "DEBIT_WALLET_OVERSPENDING",
+        DebitWalletBalanceAmountMismatch(NeedsResync) => "DEBIT_WALLET_BALANCE_AMOUNT_MISMATCH",
+        CreditWalletBalanceNotZero(NeedsResync) => "CREDIT_WALLET_BALANCE_NOT_ZERO",
+        InvalidDebitBalanceSignature(NeedsResync) => "INVALID_DEBIT_BALANCE_SIGNATURE",
+        InvalidCreditBalanceSignature(NeedsResync) => "INVALID_CREDIT_BALANCE_SIGNATURE",
+        InvalidFutureCreditSignature(Fatal) => "INVALID_FUTURE_CREDIT_SIGNATURE",
+        InvalidCreditSignature(Fatal) => "INVALID_CREDIT_SIGNATURE",
+        InvalidFutureDebitSignature(Fatal) => "INVALID_FUTURE_DEBIT_SIGNATURE",
+        InvalidDebitSignature(Fatal) => "INVALID_DEBIT_SIGNATURE",
+        InvalidFutureCreditFulfillmentSignature(Fatal) => "INVALID_FUTURE_CREDIT_FULFILLMENT_SIGNATURE",
+        InvalidCreditFulfillmentSignature(Fatal) => "INVALID_CREDIT_FULFILLMENT_SIGNATURE",
+    }
+}
+
+error_codes! {
+    /// `SwapCancelError` is an error code of the Swap cancel endpoint.
+    SwapCancelError {
+        WrongNumberOfDebitSignatures(NeedsResync) => "WRONG_NUMBER_OF_DEBIT_SIGNATURES",
+        WrongNumberOfCreditSignatures(NeedsResync) => "WRONG_NUMBER_OF_CREDIT_SIGNATURES",
+        SwapNotFrozen(NeedsResync) => "SWAP_NOT_FROZEN",
+        SwapAlreadyClosed(NeedsResync) => "SWAP_ALREADY_CLOSED",
+        MissingFreezingSignature(Fatal) => "MISSING_FREEZING_SIGNATURE",
+        SwapAlreadyCancelled(NeedsResync) => "SWAP_ALREADY_CANCELLED",
+        InvalidCreditSignature(Fatal) => "INVALID_CREDIT_SIGNATURE",
+        InvalidFutureCreditSignature(Fatal) => "INVALID_FUTURE_CREDIT_SIGNATURE",
+        InvalidDebitSignature(Fatal) => "INVALID_DEBIT_SIGNATURE",
+        InvalidFutureDebitSignature(Fatal) => "INVALID_FUTURE_DEBIT_SIGNATURE",
+    }
+}
+
+error_codes! {
+    /// `SwapFinalizeError` is an error code of the Swap finalize endpoint.
+    SwapFinalizeError {
+        SwapNotFulfilled(NeedsResync) => "SWAP_NOT_FULFILLED",
+        SwapAlreadyFrozen(NeedsResync) => "SWAP_ALREADY_FROZEN",
+        SwapAlreadyVoided(NeedsResync) => "SWAP_ALREADY_VOIDED",
+        SwapAlreadyClosed(NeedsResync) => "SWAP_ALREADY_CLOSED",
+        SwapAlreadyFinalized(NeedsResync) => "SWAP_ALREADY_FINALIZED",
+        WrongNumberOfCreditSignatures(NeedsResync) => "WRONG_NUMBER_OF_CREDIT_SIGNATURES",
+        InvalidCreditSignature(Fatal) => "INVALID_CREDIT_SIGNATURE",
+        InvalidFutureCreditSignature(Fatal) => "INVALID_FUTURE_CREDIT_SIGNATURE",
+    }
+}
+
+error_codes! {
+    /// `SwapFreezeError` is an error code of the Swap freeze endpoint.
+    SwapFreezeError {
+        SwapAlreadyFulfilled(NeedsResync) => "SWAP_ALREADY_FULFILLED",
+        SwapAlreadyFrozen(NeedsResync) => "SWAP_ALREADY_FROZEN",
+        SwapAlreadyVoided(NeedsResync) => "SWAP_ALREADY_VOIDED",
+        SwapAlreadyClosed(NeedsResync) => "SWAP_ALREADY_CLOSED",
+        InvalidFreezingSignature(Fatal) => "INVALID_FREEZING_SIGNATURE",
+    }
+}
+
+impl HubClient {
+    /// `create_swap` creates a swap order in the hub.
+    pub async fn create_swap(&self, swap: &Swap) -> Result<Swap> {
+        self.post("swap/", swap).await
+    }
+
+    /// `freeze_swap` freezes a swap order in the hub.
+    pub async fn freeze_swap(&self, id: i64, freeze: &SwapFreeze) -> Result<SwapFreeze> {
+        let path = format!("swap/{}/freeze", id);
+        self.put(&path, freeze).await
+    }
+
+    /// `cancel_swap` cancels a frozen swap order in the hub.
+    pub async fn cancel_swap(
+        &self,
+        id: i64,
+        cancellation: &SwapCancellation,
+    ) -> Result<SwapCancellation> {
+        let path = format!("swap/{}/cancel", id);
+        self.put(&path, cancellation).await
+    }
+
+    /// `finalize_swap` finalizes a fulfilled swap order in the hub.
+    pub async fn finalize_swap(
+        &self,
+        id: i64,
+        finalization: &SwapFinalization,
+    ) -> Result<SwapFinalization> {
+        let path = format!("swap/{}/finalize", id);
+        self.put(&path, finalization).await
+    }
+}
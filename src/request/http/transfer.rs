@@ -1,26 +1,55 @@
 //! `transfer` defines the functions for interacting with
 //! the HTTP Transfer API.
 
-pub const TRANSFER_CREATE_ERROR_CODES: &'static [&'static str] = &[
-    "INVALID_DEBIT_AMOUNT",
-    "CREDIT_WALLET_NOT_ADMITTED",
-    "DEBIT_WALLET_NOT_ADMITTED",
-    "DEBIT_CREDIT_WALLET_ADDRESS_MATCH",
-    "EON_NUMBER_OUT_OF_SYNC",
-    "DEBIT_WALLET_EXCEEDED_SLA",
-    "CREDIT_WALLET_EXCEEDED_SLA",
-    "DEBIT_WALLET_CANNOT_ADD_TRANSACTION",
-    "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
-    "DEBIT_WALLET_OVERSPENDING",
-    "DEBIT_WALLET_BALANCE_MARKER_EXCEED_BALANCE",
-    "INVALID_DEBIT_BALANCE_SIGNATURE",
-    "INVALID_DEBIT_SIGNATURE",
-];
+use crate::model::http::transfer::{Transfer, TransferReceipt, TransferUpdate};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
 
-pub const TRANSFER_UPDATE_ERROR_CODES: &'static [&'static str] = &[
-    "TRANSFER_ALREADY_APPROVED",
-    "TRANSFER_TIMED_OUT",
-    "CREDIT_WALLET_EXCEEDED_SLA",
-    "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
-    "INVALID_CREDIT_SIGNATURE",
-];
+error_codes! {
+    /// `TransferCreateError` is an error code of the Transfer create endpoint.
+    TransferCreateError {
+        InvalidDebitAmount(Fatal) => "INVALID_DEBIT_AMOUNT",
+        CreditWalletNotAdmitted(Fatal) => "CREDIT_WALLET_NOT_ADMITTED",
+        DebitWalletNotAdmitted(Fatal) => "DEBIT_WALLET_NOT_ADMITTED",
+        DebitCreditWalletAddressMatch(Fatal) => "DEBIT_CREDIT_WALLET_ADDRESS_MATCH",
+        EonNumberOutOfSync(NeedsResync) => "EON_NUMBER_OUT_OF_SYNC",
+        DebitWalletExceededSLA(Fatal) => "DEBIT_WALLET_EXCEEDED_SLA",
+        CreditWalletExceededSLA(Fatal) => "CREDIT_WALLET_EXCEEDED_SLA",
+        DebitWalletCannotAddTransaction(Retryable) => "DEBIT_WALLET_CANNOT_ADD_TRANSACTION",
+        CreditWalletCannotAddTransaction(Retryable) => "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
+        DebitWalletOverspending(NeedsResync) => "DEBIT_WALLET_OVERSPENDING",
+        DebitWalletBalanceMarkerExceedBalance(NeedsResync) => "DEBIT_WALLET_BALANCE_MARKER_EXCEED_BALANCE",
+        InvalidDebitBalanceSignature(NeedsResync) => "INVALID_DEBIT_BALANCE_SIGNATURE",
+        InvalidDebitSignature(Fatal) => "INVALID_DEBIT_SIGNATURE",
+    }
+}
+
+error_codes! {
+    /// `TransferUpdateError` is an error code of the Transfer update endpoint.
+    TransferUpdateError {
+        TransferAlreadyApproved(NeedsResync) => "TRANSFER_ALREADY_APPROVED",
+        TransferTimedOut(Fatal) => "TRANSFER_TIMED_OUT",
+        CreditWalletExceededSLA(Fatal) => "CREDIT_WALLET_EXCEEDED_SLA",
+        CreditWalletCannotAddTransaction(Retryable) => "CREDIT_WALLET_CANNOT_ADD_TRANSACTION",
+        InvalidCreditSignature(Fatal) => "INVALID_CREDIT_SIGNATURE",
+    }
+}
+
+impl HubClient {
+    /// `create_transfer` submits a signed transfer to the hub.
+    pub async fn create_transfer(&self, receipt: &TransferReceipt) -> Result<Transfer> {
+        self.post("transfer/", receipt).await
+    }
+
+    /// `get_transfer` returns a transfer of the hub by id.
+    pub async fn get_transfer(&self, id: i64) -> Result<Transfer> {
+        let path = format!("transfer/{}", id);
+        self.get(&path).await
+    }
+
+    /// `update_transfer` countersigns a transfer of the hub by id.
+    pub async fn update_transfer(&self, id: i64, update: &TransferUpdate) -> Result<Transfer> {
+        let path = format!("transfer/{}", id);
+        self.put(&path, update).await
+    }
+}
@@ -1,3 +1,9 @@
+/// `constants` defines the constants used in the module.
+pub mod constants;
+
+/// `network` defines the profiles of the Liquidity Network hubs.
+pub mod network;
+
 /// `http` defines the types and functions for the HTTP API of the Liquidity Network hub.
 pub mod http;
 
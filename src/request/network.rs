@@ -0,0 +1,189 @@
+//! `network` defines the profiles of the Liquidity Network hubs a client can connect to.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::model::common::Address;
+use crate::model::traits::{FromJson, ToJson};
+use crate::request::constants::*;
+use crate::request::http::client::HubClient;
+use crate::request::ws::client::WsClient;
+use crate::result::Result;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// `Scheme` is the scheme used to connect to a hub.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    Http,
+    Https,
+}
+
+impl Scheme {
+    /// `as_str` returns the HTTP scheme.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        }
+    }
+
+    /// `ws_str` returns the WS scheme.
+    pub fn ws_str(self) -> &'static str {
+        match self {
+            Scheme::Http => "ws",
+            Scheme::Https => "wss",
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `Network` is the profile of a Liquidity Network hub.
+/// The fields unknown to the client are left empty. `blocks_per_eon` is filled
+/// from the hub by `Network::resolve`, while `hub_contract` is not exposed by
+/// the hub API and must be set in the profile when needed.
+///
+/// The presets only bundle the values published with the hubs, that is their host
+/// and the chain id of their public Ethereum network. The hub contracts and eon
+/// lengths are deployment parameters documented neither by the hubs nor by their
+/// API, so the presets leave them empty rather than hardcode values which could
+/// silently point a client at the wrong contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Network {
+    pub name: String,
+    pub scheme: Scheme,
+    pub host: String,
+    #[serde(default = "Network::default_ws_path")]
+    pub ws_path: String,
+    pub chain_id: Option<u64>,
+    pub hub_contract: Option<Address>,
+    pub blocks_per_eon: Option<u64>,
+}
+
+impl Network {
+    /// `DEFAULT_WS_PATH` is the default path of the WS Notification API.
+    pub const DEFAULT_WS_PATH: &'static str = "/ws/";
+
+    /// `MAINNET_CHAIN_ID` is the chain id of the Ethereum mainnet.
+    pub const MAINNET_CHAIN_ID: u64 = 1;
+
+    /// `RINKEBY_CHAIN_ID` is the chain id of the Ethereum Rinkeby testnet.
+    pub const RINKEBY_CHAIN_ID: u64 = 4;
+
+    /// `new` creates a new custom `Network`.
+    pub fn new(name: &str, scheme: Scheme, host: &str) -> Network {
+        Network {
+            name: name.to_owned(),
+            scheme,
+            host: host.to_owned(),
+            ws_path: Self::default_ws_path(),
+            chain_id: None,
+            hub_contract: None,
+            blocks_per_eon: None,
+        }
+    }
+
+    /// `local` creates a new `Network` for a hub running locally without TLS.
+    pub fn local(host: &str) -> Network {
+        Network::new("local", Scheme::Http, host)
+    }
+
+    /// `mainnet` returns the profile of the mainnet hub.
+    pub fn mainnet() -> Network {
+        let mut network = Network::new("mainnet", Scheme::Https, MAINNET_HUB_HOST);
+        network.chain_id = Some(Self::MAINNET_CHAIN_ID);
+        network
+    }
+
+    /// `rinkeby` returns the profile of the testnet hub.
+    pub fn rinkeby() -> Network {
+        let mut network = Network::new("rinkeby", Scheme::Https, TESTNET_HUB_HOST);
+        network.chain_id = Some(Self::RINKEBY_CHAIN_ID);
+        network
+    }
+
+    /// `limbo` returns the profile of the regtest hub. The regtest hub runs on a
+    /// private chain, whose chain id is not published.
+    pub fn limbo() -> Network {
+        Network::new("limbo", Scheme::Https, REGTEST_HUB_HOST)
+    }
+
+    /// `preset` returns the profile of a known hub by name.
+    pub fn preset(name: &str) -> Option<Network> {
+        match name {
+            "mainnet" => Some(Network::mainnet()),
+            "rinkeby" => Some(Network::rinkeby()),
+            "limbo" => Some(Network::limbo()),
+            _ => None,
+        }
+    }
+
+    /// `from_toml` deserializes a `Network` from a TOML string.
+    pub fn from_toml(s: &str) -> Result<Network> {
+        toml::from_str(s).map_err(|e| e.into())
+    }
+
+    /// `load` loads a `Network` from a TOML or JSON file,
+    /// depending on the file extension, regardless of its case.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Network> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match ext.as_str() {
+            "toml" => Network::from_toml(&fs::read_to_string(path)?),
+            "json" => Network::from_json(&fs::read_to_string(path)?),
+            _ => Err(Error::UnsupportedFormat { format: ext }),
+        }
+    }
+
+    /// `http_url` returns the base URL of the HTTP API.
+    pub fn http_url(&self) -> String {
+        format!("{}://{}", self.scheme.as_str(), self.host)
+    }
+
+    /// `ws_url` returns the URL of the WS Notification API.
+    pub fn ws_url(&self) -> String {
+        format!("{}://{}{}", self.scheme.ws_str(), self.host, self.ws_path)
+    }
+
+    /// `hub_client` returns a `HubClient` of the hub.
+    pub fn hub_client(&self) -> HubClient {
+        HubClient::with_scheme(self.scheme.as_str(), &self.host)
+    }
+
+    /// `resolve` fills the fields of the profile left empty which can be
+    /// retrieved from the hub, that is `blocks_per_eon`.
+    pub async fn resolve(&mut self) -> Result<()> {
+        if self.blocks_per_eon.is_none() {
+            let status = self.hub_client().operator_status().await?;
+            self.blocks_per_eon = Some(status.blocks_per_eon);
+        }
+
+        Ok(())
+    }
+
+    /// `ws_client` connects a `WsClient` to the hub.
+    pub async fn ws_client(&self) -> Result<WsClient> {
+        WsClient::connect(&self.ws_url()).await
+    }
+
+    fn default_ws_path() -> String {
+        Self::DEFAULT_WS_PATH.to_owned()
+    }
+}
+
+impl ToJson for Network {}
+
+impl<'a> FromJson<'a> for Network {}
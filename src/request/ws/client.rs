@@ -0,0 +1,681 @@
+//! `client` defines the client of the WS Notification API.
+
+use async_std::net::TcpStream;
+use async_std::task;
+use async_tls::client::TlsStream;
+use async_tungstenite::async_std::connect_async;
+use async_tungstenite::stream::Stream as MaybeTlsStream;
+use async_tungstenite::tungstenite::{self, Message};
+use async_tungstenite::WebSocketStream;
+use futures::future::Future;
+use futures::sink::{Sink, SinkExt};
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+use url::Url;
+
+use crate::error::Error;
+use crate::model::common::Uuid;
+use crate::model::ws::ws::*;
+use crate::request::ws::streams::{token_pair_stream, wallet_stream};
+use crate::result::Result;
+use crate::store::traits::{LastNotification, Store, StoreExt};
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `Connection` is a WebSocket connection to a hub.
+pub type Connection = WebSocketStream<MaybeTlsStream<TcpStream, TlsStream<TcpStream>>>;
+
+/// `Reconnection` is a pending reconnection to a hub.
+type Reconnection = Pin<Box<dyn Future<Output = Result<Connection>> + Send>>;
+
+/// `ReconnectPolicy` is the policy followed when reconnecting to a hub.
+/// The delay between attempts starts from `initial_delay` and doubles
+/// at each failed attempt, up to `max_delay`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub max_attempts: Option<u32>,
+}
+
+impl ReconnectPolicy {
+    /// `delay` returns the delay before a reconnection attempt.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+/// `AckMode` is the mode in which notifications are acknowledged to the hub.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum AckMode {
+    /// `Auto` acknowledges a notification when the consumer polls for the next event,
+    /// that is once the consumer is done handling it.
+    #[default]
+    Auto,
+    /// `Manual` acknowledges a notification only when the consumer calls `WsClient::ack`.
+    Manual,
+}
+
+/// `Deliveries` keeps track of the most recent notifications delivered to the consumer,
+/// and of whether they were acknowledged. Only acknowledged notifications are evicted,
+/// so the notifications waiting for an acknowledgement are all tracked.
+#[derive(Default)]
+struct Deliveries {
+    acked: HashMap<Uuid, bool>,
+    order: VecDeque<Uuid>,
+}
+
+impl Deliveries {
+    /// `CAPACITY` is the number of notifications tracked, unless more are unacknowledged.
+    const CAPACITY: usize = 4096;
+
+    /// `get` returns if a notification was delivered, and if it was acknowledged.
+    fn get(&self, uuid: &Uuid) -> Option<bool> {
+        self.acked.get(uuid).copied()
+    }
+
+    /// `deliver` marks a notification as delivered.
+    fn deliver(&mut self, uuid: Uuid) {
+        if self.acked.insert(uuid, false).is_none() {
+            self.order.push_back(uuid);
+        }
+
+        while self.order.len() > Self::CAPACITY {
+            let acked = &self.acked;
+            let oldest = self
+                .order
+                .iter()
+                .position(|uuid| acked.get(uuid).copied().unwrap_or(true));
+
+            match oldest.and_then(|index| self.order.remove(index)) {
+                Some(uuid) => {
+                    self.acked.remove(&uuid);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// `ack` marks a notification as acknowledged.
+    fn ack(&mut self, uuid: Uuid) {
+        if let Some(acked) = self.acked.get_mut(&uuid) {
+            *acked = true;
+        }
+    }
+}
+
+/// `WsEvent` is an event of the WS Notification API.
+pub enum WsEvent {
+    /// `Notification` is a notification of a subscribed stream.
+    Notification(NotificationResponse),
+    /// `Gap` signals that the connection was lost and re-established,
+    /// so notifications may have been missed and should be resynced
+    /// through the HTTP Audit API.
+    Gap,
+}
+
+/// `State` is the state of the connection of a `WsClient`.
+enum State {
+    Connected(Box<Connection>),
+    Reconnecting(Reconnection),
+    Closed,
+}
+
+/// `WsClient` is an asynchronous client of a Liquidity Network hub WS Notification API.
+/// The client is a `Stream` of the events of the subscribed streams. When the connection
+/// drops, the client reconnects, subscribes again to the confirmed streams and to those
+/// still waiting for a confirmation, and emits a `WsEvent::Gap`. The streams subscribed
+/// or unsubscribed while reconnecting are sent to the hub once reconnected.
+///
+/// Notifications are acknowledged following the `AckMode` of the client. Redelivered
+/// notifications are acknowledged again without being emitted when already acknowledged,
+/// and emitted again otherwise, so that each notification is handled at least once.
+/// When the client has a `Store`, the last acknowledged notification is saved to it,
+/// so that its redelivery to a later client is not emitted again.
+pub struct WsClient {
+    url: String,
+    policy: ReconnectPolicy,
+    subscriptions: BTreeSet<String>,
+    pending_subscriptions: BTreeSet<String>,
+    resubscriptions: BTreeSet<String>,
+    state: State,
+    ack_mode: AckMode,
+    deliveries: Deliveries,
+    unacked: Option<Uuid>,
+    pending_acks: VecDeque<Uuid>,
+    pending_requests: VecDeque<Message>,
+    store: Option<Arc<dyn Store>>,
+}
+
+impl WsClient {
+    /// `connect` connects to the WS Notification API endpoint of a hub
+    /// with the default `ReconnectPolicy`.
+    pub async fn connect(url: &str) -> Result<WsClient> {
+        WsClient::connect_with_policy(url, ReconnectPolicy::default()).await
+    }
+
+    /// `connect_with_policy` connects to the WS Notification API endpoint of a hub.
+    pub async fn connect_with_policy(url: &str, policy: ReconnectPolicy) -> Result<WsClient> {
+        let connection = open(url).await?;
+
+        let client = WsClient {
+            url: url.to_owned(),
+            policy,
+            subscriptions: BTreeSet::new(),
+            pending_subscriptions: BTreeSet::new(),
+            resubscriptions: BTreeSet::new(),
+            state: State::Connected(Box::new(connection)),
+            ack_mode: AckMode::default(),
+            deliveries: Deliveries::default(),
+            unacked: None,
+            pending_acks: VecDeque::new(),
+            pending_requests: VecDeque::new(),
+            store: None,
+        };
+
+        Ok(client)
+    }
+
+    /// `url` returns the URL of the WS Notification API endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// `subscriptions` returns the streams whose subscription was confirmed by the hub.
+    pub fn subscriptions(&self) -> &BTreeSet<String> {
+        &self.subscriptions
+    }
+
+    /// `pending_subscriptions` returns the streams whose subscription was requested
+    /// but not yet confirmed by the hub.
+    pub fn pending_subscriptions(&self) -> &BTreeSet<String> {
+        &self.pending_subscriptions
+    }
+
+    /// `requested_subscriptions` returns the streams subscribed or waiting for a confirmation.
+    fn requested_subscriptions(&self) -> BTreeSet<String> {
+        self.subscriptions
+            .union(&self.pending_subscriptions)
+            .cloned()
+            .collect()
+    }
+
+    /// `ack_mode` returns the `AckMode` of the client.
+    pub fn ack_mode(&self) -> AckMode {
+        self.ack_mode
+    }
+
+    /// `set_ack_mode` sets the `AckMode` of the client.
+    pub fn set_ack_mode(&mut self, mode: AckMode) {
+        self.ack_mode = mode;
+    }
+
+    /// `set_store` sets the `Store` the last acknowledged notification is saved to,
+    /// and loads the notification last acknowledged on the endpoint from it.
+    pub fn set_store(&mut self, store: Arc<dyn Store>) -> Result<()> {
+        if let Some(last) = store.load::<LastNotification>(&self.url)? {
+            self.deliveries.deliver(last.uuid);
+            self.deliveries.ack(last.uuid);
+        }
+
+        self.store = Some(store);
+        Ok(())
+    }
+
+    /// `acknowledged` marks a notification as acknowledged, and saves it to the store.
+    fn acknowledged(&mut self, uuid: Uuid) -> Result<()> {
+        self.deliveries.ack(uuid);
+
+        match &self.store {
+            Some(store) => store.save(&LastNotification {
+                url: self.url.clone(),
+                uuid,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// `is_connected` returns if the client is connected to the hub.
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, State::Connected(_))
+    }
+
+    /// `ping` sends a `PingRequest` to the hub.
+    pub async fn ping(&mut self) -> Result<()> {
+        let req = PingRequest {
+            op: PingOp,
+            args: (),
+        };
+
+        self.send(&req).await
+    }
+
+    /// `ack` sends an `AckRequest` for a notification to the hub.
+    /// If the client is not connected, the notification is acknowledged once reconnected.
+    pub async fn ack(&mut self, uuid: Uuid) -> Result<()> {
+        self.acknowledged(uuid)?;
+
+        if self.unacked == Some(uuid) {
+            self.unacked = None;
+        }
+
+        if !self.is_connected() {
+            self.pending_acks.push_back(uuid);
+            return Ok(());
+        }
+
+        let req = AckRequest {
+            op: AckOp,
+            args: uuid,
+        };
+
+        let res = self.send(&req).await;
+
+        if res.is_err() {
+            self.pending_acks.push_back(uuid);
+        }
+
+        res
+    }
+
+    /// `subscribe` sends a `SubscribeRequest` for a set of streams to the hub.
+    /// If the client is reconnecting, the streams are subscribed once reconnected.
+    pub async fn subscribe(&mut self, streams: &[String]) -> Result<()> {
+        if let State::Reconnecting(_) = self.state {
+            self.pending_subscriptions.extend(streams.iter().cloned());
+            return Ok(());
+        }
+
+        let req = SubscribeRequest {
+            op: SubscribeOp,
+            args: streams.to_vec(),
+        };
+
+        self.send(&req).await?;
+
+        for stream in streams {
+            if !self.subscriptions.contains(stream) {
+                self.pending_subscriptions.insert(stream.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `unsubscribe` sends an `UnsubscribeRequest` for a set of streams to the hub.
+    /// If the client is reconnecting, the streams are unsubscribed once reconnected.
+    pub async fn unsubscribe(&mut self, streams: &[String]) -> Result<()> {
+        if let State::Reconnecting(_) = self.state {
+            for stream in streams {
+                self.subscriptions.remove(stream);
+                self.pending_subscriptions.remove(stream);
+            }
+            return Ok(());
+        }
+
+        let req = UnsubscribeRequest {
+            op: UnsubscribeOp,
+            args: streams.to_vec(),
+        };
+
+        self.send(&req).await?;
+
+        for stream in streams {
+            self.pending_subscriptions.remove(stream);
+        }
+
+        Ok(())
+    }
+
+    /// `send` sends a generic request to the hub.
+    pub async fn send<Op, Args>(&mut self, req: &GenericRequest<Op, Args>) -> Result<()>
+    where
+        Op: Serialize,
+        Args: Serialize,
+    {
+        let msg = Message::text(json::to_string(req)?);
+
+        match self.state {
+            State::Connected(ref mut connection) => {
+                connection.send(msg).await.map_err(|e| e.into())
+            }
+            _ => Err(tungstenite::Error::AlreadyClosed.into()),
+        }
+    }
+
+    /// `close` closes the connection to the hub.
+    pub async fn close(mut self) -> Result<()> {
+        match std::mem::replace(&mut self.state, State::Closed) {
+            State::Connected(mut connection) => {
+                connection.as_mut().close(None).await.map_err(|e| e.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `reconnect` starts reconnecting to the hub.
+    fn reconnect(&mut self) {
+        let url = self.url.clone();
+        let policy = self.policy;
+        let requested = self.requested_subscriptions();
+        let streams: Vec<String> = requested.iter().cloned().collect();
+
+        self.resubscriptions = requested;
+        self.pending_requests.clear();
+        self.state = State::Reconnecting(Box::pin(reopen(url, policy, streams)));
+    }
+
+    /// `reconnected` queues the requests for the streams subscribed or unsubscribed
+    /// while reconnecting, which differ from those subscribed again by the reconnection.
+    fn reconnected(&mut self) -> Result<()> {
+        let resubscriptions = std::mem::take(&mut self.resubscriptions);
+        let requested = self.requested_subscriptions();
+
+        let subscribed: Vec<String> = requested.difference(&resubscriptions).cloned().collect();
+        let unsubscribed: Vec<String> = resubscriptions.difference(&requested).cloned().collect();
+
+        if !subscribed.is_empty() {
+            let req = SubscribeRequest {
+                op: SubscribeOp,
+                args: subscribed,
+            };
+            self.pending_requests
+                .push_back(Message::text(json::to_string(&req)?));
+        }
+
+        if !unsubscribed.is_empty() {
+            let req = UnsubscribeRequest {
+                op: UnsubscribeOp,
+                args: unsubscribed,
+            };
+            self.pending_requests
+                .push_back(Message::text(json::to_string(&req)?));
+        }
+
+        Ok(())
+    }
+
+    /// `handle` handles a message received from the hub.
+    fn handle(&mut self, msg: Message) -> Option<Result<WsEvent>> {
+        let text = match msg {
+            Message::Text(text) => text,
+            _ => return None,
+        };
+
+        match decode(&text) {
+            Ok(Incoming::Notification(notification)) => {
+                let uuid = notification.uuid;
+
+                match self.deliveries.get(&uuid) {
+                    Some(true) => {
+                        self.pending_acks.push_back(uuid);
+                        None
+                    }
+                    delivered => {
+                        if delivered.is_none() {
+                            self.deliveries.deliver(uuid);
+                        }
+
+                        if self.ack_mode == AckMode::Auto {
+                            self.unacked = Some(uuid);
+                        }
+
+                        Some(Ok(WsEvent::Notification(self.attribute(notification))))
+                    }
+                }
+            }
+            Ok(Incoming::Subscribed(streams)) => {
+                for stream in streams {
+                    self.pending_subscriptions.remove(&stream);
+                    self.subscriptions.insert(stream);
+                }
+                None
+            }
+            Ok(Incoming::Unsubscribed(streams)) => {
+                for stream in streams {
+                    self.subscriptions.remove(&stream);
+                    self.pending_subscriptions.remove(&stream);
+                }
+                None
+            }
+            Ok(Incoming::Other) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// `attribute` decodes a `MATCHED_SWAP` notification as a token pair event when
+    /// it can only have been sent on a subscribed token pair stream. Notifications
+    /// don't name their stream, so a swap matched while subscribed to both the
+    /// token pair stream and the stream of one of its wallets stays a wallet event.
+    fn attribute(&self, mut notification: NotificationResponse) -> NotificationResponse {
+        let transfer = match &notification.data.data {
+            Event::Wallet(WalletEvent::MatchedSwap(transfer)) => transfer,
+            _ => return notification,
+        };
+
+        let subscribed = |streams: &[String]| {
+            streams.iter().any(|stream| {
+                self.subscriptions
+                    .iter()
+                    .any(|subscription| subscription.eq_ignore_ascii_case(stream))
+            })
+        };
+
+        let wallet_streams = [
+            wallet_stream(&transfer.wallet),
+            wallet_stream(&transfer.recipient),
+        ];
+        let token_pair_streams = [
+            token_pair_stream(&transfer.wallet.token, &transfer.recipient.token),
+            token_pair_stream(&transfer.recipient.token, &transfer.wallet.token),
+        ];
+
+        if subscribed(&token_pair_streams) && !subscribed(&wallet_streams) {
+            notification.data = notification.data.into_token_pair();
+        }
+
+        notification
+    }
+}
+
+impl Stream for WsClient {
+    type Item = Result<WsEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(uuid) = this.unacked.take() {
+            this.pending_acks.push_back(uuid);
+
+            if let Err(e) = this.acknowledged(uuid) {
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+
+        loop {
+            match this.state {
+                State::Connected(ref mut connection) => {
+                    let pending = poll_pending(
+                        connection,
+                        &mut this.pending_requests,
+                        &mut this.pending_acks,
+                        cx,
+                    );
+
+                    if let Poll::Ready(Err(_)) = pending {
+                        this.reconnect();
+                        continue;
+                    }
+
+                    match Pin::new(connection.as_mut()).poll_next(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Some(Ok(Message::Close(_))))
+                        | Poll::Ready(Some(Err(_)))
+                        | Poll::Ready(None) => this.reconnect(),
+                        Poll::Ready(Some(Ok(msg))) => {
+                            if let Some(event) = this.handle(msg) {
+                                return Poll::Ready(Some(event));
+                            }
+                        }
+                    }
+                }
+                State::Reconnecting(ref mut reconnection) => match reconnection.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(connection)) => {
+                        this.state = State::Connected(Box::new(connection));
+
+                        if let Err(e) = this.reconnected() {
+                            return Poll::Ready(Some(Err(e)));
+                        }
+
+                        return Poll::Ready(Some(Ok(WsEvent::Gap)));
+                    }
+                    Poll::Ready(Err(e)) => {
+                        this.state = State::Closed;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                },
+                State::Closed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// `poll_pending` sends the pending requests and the pending acknowledgements
+/// of notifications to the hub.
+fn poll_pending(
+    connection: &mut Connection,
+    pending_requests: &mut VecDeque<Message>,
+    pending_acks: &mut VecDeque<Uuid>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<()>> {
+    while let Some(msg) = pending_requests.front() {
+        match Pin::new(&mut *connection).poll_ready(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+            Poll::Ready(Ok(())) => {}
+        }
+
+        Pin::new(&mut *connection).start_send(msg.clone())?;
+        pending_requests.pop_front();
+    }
+
+    while let Some(uuid) = pending_acks.front() {
+        match Pin::new(&mut *connection).poll_ready(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+            Poll::Ready(Ok(())) => {}
+        }
+
+        let req = AckRequest {
+            op: AckOp,
+            args: *uuid,
+        };
+        let msg = Message::text(json::to_string(&req)?);
+
+        Pin::new(&mut *connection).start_send(msg)?;
+        pending_acks.pop_front();
+    }
+
+    Pin::new(connection).poll_flush(cx).map_err(|e| e.into())
+}
+
+/// `open` opens a connection to a hub.
+async fn open(url: &str) -> Result<Connection> {
+    let (connection, _) = connect_async(Url::parse(url)?).await?;
+    Ok(connection)
+}
+
+/// `reopen` reopens a connection to a hub following a `ReconnectPolicy`,
+/// and subscribes again to a set of streams.
+async fn reopen(url: String, policy: ReconnectPolicy, streams: Vec<String>) -> Result<Connection> {
+    let mut attempt = 0;
+
+    loop {
+        task::sleep(policy.delay(attempt)).await;
+
+        let res = match open(&url).await {
+            Ok(mut connection) if !streams.is_empty() => {
+                let req = SubscribeRequest {
+                    op: SubscribeOp,
+                    args: streams.clone(),
+                };
+                let msg = Message::text(json::to_string(&req)?);
+
+                match connection.send(msg).await {
+                    Ok(()) => Ok(connection),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            res => res,
+        };
+
+        attempt += 1;
+
+        match res {
+            Ok(connection) => return Ok(connection),
+            Err(e) => match policy.max_attempts {
+                Some(max) if attempt >= max => return Err(e),
+                _ => {}
+            },
+        }
+    }
+}
+
+/// `Incoming` is a message sent by the hub.
+enum Incoming {
+    Notification(NotificationResponse),
+    Subscribed(Vec<String>),
+    Unsubscribed(Vec<String>),
+    Other,
+}
+
+/// `MessageType` is the type of a message sent by the hub.
+#[derive(Deserialize)]
+struct MessageType {
+    r#type: String,
+}
+
+/// `decode` decodes a textual message sent by the hub.
+fn decode(text: &str) -> Result<Incoming> {
+    let MessageType { r#type } = json::from_str(text)?;
+
+    match r#type.as_str() {
+        NotificationType::TAG => Ok(Incoming::Notification(json::from_str(text)?)),
+        ResponseType::TAG => {
+            let res: Response<StreamData<String>> = json::from_str(text)?;
+
+            match res.data.op.as_str() {
+                SubscribeOp::TAG => Ok(Incoming::Subscribed(res.data.stream)),
+                UnsubscribeOp::TAG => Ok(Incoming::Unsubscribed(res.data.stream)),
+                _ => Ok(Incoming::Other),
+            }
+        }
+        ErrorType::TAG => {
+            let res: ErrorResponse<json::Value> = json::from_str(text)?;
+            Err(Error::WSResponse {
+                message: res.data.message,
+            })
+        }
+        _ => Ok(Incoming::Other),
+    }
+}
@@ -0,0 +1,85 @@
+//! `handler` defines the handling of the events of the WS Notification API.
+
+use crate::model::ws::ws::{
+    DepositNotification, Event, NotificationData, TokenPairEvent, TransferAudit,
+    WalletAdmissionData, WalletDataNotification, WalletEvent, WithdrawalNotification,
+    WithdrawalRequestNotification,
+};
+
+/// `EventHandler` handles the events of the WS Notification API, with one method
+/// per event type. Every method does nothing by default.
+pub trait EventHandler {
+    /// `incoming_transfer` handles an `INCOMING_TRANSFER` wallet event.
+    fn incoming_transfer(&mut self, _transfer: &TransferAudit) {}
+
+    /// `incoming_receipt` handles an `INCOMING_RECEIPT` wallet event.
+    fn incoming_receipt(&mut self, _transfer: &TransferAudit) {}
+
+    /// `incoming_confirmation` handles an `INCOMING_CONFIRMATION` wallet event.
+    fn incoming_confirmation(&mut self, _transfer: &TransferAudit) {}
+
+    /// `timeout_transfer` handles a `TIMEOUT_TRANSFER` wallet event.
+    fn timeout_transfer(&mut self, _transfer: &TransferAudit) {}
+
+    /// `matched_swap` handles a `MATCHED_SWAP` wallet event.
+    fn matched_swap(&mut self, _swap: &TransferAudit) {}
+
+    /// `finalized_swap` handles a `FINALIZED_SWAP` wallet event.
+    fn finalized_swap(&mut self, _swap: &TransferAudit) {}
+
+    /// `canceled_swap` handles a `CANCELED_SWAP` wallet event.
+    fn canceled_swap(&mut self, _swap: &TransferAudit) {}
+
+    /// `registered_wallet` handles a `REGISTERED_WALLET` wallet event.
+    fn registered_wallet(&mut self, _admission: &WalletAdmissionData) {}
+
+    /// `confirmed_deposit` handles a `CONFIRMED_DEPOSIT` wallet event.
+    fn confirmed_deposit(&mut self, _deposit: &DepositNotification) {}
+
+    /// `request_withdrawal` handles a `REQUEST_WITHDRAWAL` wallet event.
+    fn request_withdrawal(&mut self, _request: &WithdrawalRequestNotification) {}
+
+    /// `confirmed_withdrawal` handles a `CONFIRMED_WITHDRAWAL` wallet event.
+    fn confirmed_withdrawal(&mut self, _withdrawal: &WithdrawalNotification) {}
+
+    /// `checkpoint_created` handles a `CHECKPOINT_CREATED` wallet event.
+    fn checkpoint_created(&mut self, _checkpoint: &WalletDataNotification) {}
+
+    /// `token_pair_incoming_swap` handles an `INCOMING_SWAP` token pair event.
+    fn token_pair_incoming_swap(&mut self, _swap: &TransferAudit) {}
+
+    /// `token_pair_matched_swap` handles a `MATCHED_SWAP` token pair event, received
+    /// by a `WsClient` subscribed to the token pair stream but not to the wallets of the swap.
+    fn token_pair_matched_swap(&mut self, _swap: &TransferAudit) {}
+
+    /// `handle` dispatches an event to the method of its type.
+    fn handle(&mut self, event: &Event) {
+        match event {
+            Event::Wallet(event) => match event {
+                WalletEvent::IncomingTransfer(transfer) => self.incoming_transfer(transfer),
+                WalletEvent::IncomingReceipt(transfer) => self.incoming_receipt(transfer),
+                WalletEvent::IncomingConfirmation(transfer) => self.incoming_confirmation(transfer),
+                WalletEvent::TimeoutTransfer(transfer) => self.timeout_transfer(transfer),
+                WalletEvent::MatchedSwap(swap) => self.matched_swap(swap),
+                WalletEvent::FinalizedSwap(swap) => self.finalized_swap(swap),
+                WalletEvent::CanceledSwap(swap) => self.canceled_swap(swap),
+                WalletEvent::RegisteredWallet(admission) => self.registered_wallet(admission),
+                WalletEvent::ConfirmedDeposit(deposit) => self.confirmed_deposit(deposit),
+                WalletEvent::RequestWithdrawal(request) => self.request_withdrawal(request),
+                WalletEvent::ConfirmedWithdrawal(withdrawal) => {
+                    self.confirmed_withdrawal(withdrawal)
+                }
+                WalletEvent::CheckpointCreated(checkpoint) => self.checkpoint_created(checkpoint),
+            },
+            Event::TokenPair(event) => match event {
+                TokenPairEvent::IncomingSwap(swap) => self.token_pair_incoming_swap(swap),
+                TokenPairEvent::MatchedSwap(swap) => self.token_pair_matched_swap(swap),
+            },
+        }
+    }
+
+    /// `handle_notification` dispatches the event of a notification to the method of its type.
+    fn handle_notification(&mut self, notification: &NotificationData) {
+        self.handle(&notification.data)
+    }
+}
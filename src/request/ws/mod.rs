@@ -1 +1,10 @@
 //! `ws` defines the models of the Liquidity Network hub WebSocket API.
+
+/// `client` defines the WS Notification API client.
+pub mod client;
+
+/// `streams` defines the names of the WS Notification API streams.
+pub mod streams;
+
+/// `handler` defines the WS Notification API event handler.
+pub mod handler;
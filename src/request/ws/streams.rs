@@ -0,0 +1,13 @@
+//! `streams` defines the names of the streams of the WS Notification API.
+
+use crate::model::common::{Address, Wallet};
+
+/// `wallet_stream` returns the name of the stream of the events of a wallet.
+pub fn wallet_stream(wallet: &Wallet) -> String {
+    format!("wallet/{}/{}", wallet.token, wallet.address)
+}
+
+/// `token_pair_stream` returns the name of the stream of the events of a token pair.
+pub fn token_pair_stream(left_token: &Address, right_token: &Address) -> String {
+    format!("tokenpair/{}/{}", left_token, right_token)
+}
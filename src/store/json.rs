@@ -0,0 +1,166 @@
+//! `json` defines a `Store` persisted in a JSON file.
+
+use serde_json as json;
+
+use crate::result::Result;
+use crate::store::traits::{Operation, Store};
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// `Collections` are the collections of a `JsonFileStore`.
+type Collections = BTreeMap<String, BTreeMap<String, json::Value>>;
+
+/// `JsonFileStore` is a `Store` kept in memory and persisted in a JSON file.
+/// The file is rewritten atomically and durably on every write.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    collections: Mutex<Collections>,
+}
+
+impl JsonFileStore {
+    /// `open` opens a `JsonFileStore`, creating it if the file doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<JsonFileStore> {
+        let path = path.as_ref().to_owned();
+
+        let collections = if path.exists() {
+            json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Collections::new()
+        };
+
+        Ok(JsonFileStore {
+            path,
+            collections: Mutex::new(collections),
+        })
+    }
+
+    /// `path` returns the path of the file of the store.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// `persist` writes the collections to the file of the store. The temporary
+    /// file is synced before replacing the file, and the directory after, so that
+    /// a crash leaves either the previous or the new content.
+    fn persist(&self, collections: &Collections) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(json::to_string_pretty(collections)?.as_bytes())?;
+        file.sync_all()?;
+
+        fs::rename(&tmp, &self.path)?;
+        sync_dir(&self.path)
+    }
+
+    /// `write` applies a function to a copy of the collections, and
+    /// persists and keeps the copy only if the function succeeds.
+    fn write<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Collections) -> Result<()>,
+    {
+        let mut collections = self.collections.lock().unwrap();
+        let mut updated = collections.clone();
+
+        f(&mut updated)?;
+        self.persist(&updated)?;
+        *collections = updated;
+
+        Ok(())
+    }
+}
+
+/// `sync_dir` syncs the directory of a file, persisting the renames into it.
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
+
+/// `sync_dir` is a no-op where directories can't be synced.
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+impl Store for JsonFileStore {
+    fn get_raw(&self, collection: &str, key: &str) -> Result<Option<String>> {
+        let collections = self.collections.lock().unwrap();
+
+        collections
+            .get(collection)
+            .and_then(|values| values.get(key))
+            .map(|value| json::to_string(value).map_err(|e| e.into()))
+            .transpose()
+    }
+
+    fn keys(&self, collection: &str) -> Result<Vec<String>> {
+        let collections = self.collections.lock().unwrap();
+
+        Ok(collections
+            .get(collection)
+            .map(|values| values.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn apply(&self, batch: Vec<Operation>) -> Result<()> {
+        self.write(|collections| {
+            for operation in batch {
+                match operation {
+                    Operation::Put {
+                        collection,
+                        key,
+                        value,
+                    } => {
+                        let value = json::from_str(&value)?;
+                        collections
+                            .entry(collection)
+                            .or_default()
+                            .insert(key, value);
+                    }
+                    Operation::Delete { collection, key } => {
+                        if let Some(values) = collections.get_mut(&collection) {
+                            values.remove(&key);
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn update_raw(
+        &self,
+        collection: &str,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<Option<String>>,
+    ) -> Result<()> {
+        self.write(|collections| {
+            let values = collections.entry(collection.to_owned()).or_default();
+            let current = values.get(key).map(json::to_string).transpose()?;
+
+            match f(current)? {
+                Some(value) => {
+                    values.insert(key.to_owned(), json::from_str(&value)?);
+                }
+                None => {
+                    values.remove(key);
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,11 @@
+//! `store` defines the persistent stores of the client state.
+
+/// `traits` defines the `Store` trait and the records it persists.
+pub mod traits;
+
+/// `json` defines a `Store` persisted in a JSON file.
+pub mod json;
+
+/// `sqlite` defines a `Store` persisted in an embedded SQLite database.
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
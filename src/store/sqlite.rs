@@ -0,0 +1,132 @@
+//! `sqlite` defines a `Store` persisted in an embedded SQLite database.
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::result::Result;
+use crate::store::traits::{Operation, Store};
+
+use std::path::Path;
+use std::sync::Mutex;
+
+/// `SCHEMA` is the schema of the database of a `SqliteStore`.
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS store (
+    collection TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (collection, key)
+)";
+
+/// `SqliteStore` is a `Store` persisted in an embedded SQLite database.
+/// Every write runs in its own transaction.
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// `open` opens a `SqliteStore`, creating its database if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore> {
+        SqliteStore::with_connection(Connection::open(path)?)
+    }
+
+    /// `open_in_memory` opens a `SqliteStore` in memory.
+    pub fn open_in_memory() -> Result<SqliteStore> {
+        SqliteStore::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// `with_connection` creates a `SqliteStore` from a database connection.
+    fn with_connection(connection: Connection) -> Result<SqliteStore> {
+        connection.execute(SCHEMA, params![])?;
+
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl Store for SqliteStore {
+    fn get_raw(&self, collection: &str, key: &str) -> Result<Option<String>> {
+        let connection = self.connection.lock().unwrap();
+
+        let value = connection
+            .query_row(
+                "SELECT value FROM store WHERE collection = ?1 AND key = ?2",
+                params![collection, key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(value)
+    }
+
+    fn keys(&self, collection: &str) -> Result<Vec<String>> {
+        let connection = self.connection.lock().unwrap();
+
+        let mut statement =
+            connection.prepare("SELECT key FROM store WHERE collection = ?1 ORDER BY key")?;
+        let keys = statement
+            .query_map(params![collection], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(keys)
+    }
+
+    fn apply(&self, batch: Vec<Operation>) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+
+        for operation in batch {
+            match operation {
+                Operation::Put {
+                    collection,
+                    key,
+                    value,
+                } => transaction.execute(
+                    "INSERT OR REPLACE INTO store (collection, key, value) VALUES (?1, ?2, ?3)",
+                    params![collection, key, value],
+                )?,
+                Operation::Delete { collection, key } => transaction.execute(
+                    "DELETE FROM store WHERE collection = ?1 AND key = ?2",
+                    params![collection, key],
+                )?,
+            };
+        }
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn update_raw(
+        &self,
+        collection: &str,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<Option<String>>,
+    ) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+
+        let current = transaction
+            .query_row(
+                "SELECT value FROM store WHERE collection = ?1 AND key = ?2",
+                params![collection, key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match f(current)? {
+            Some(value) => transaction.execute(
+                "INSERT OR REPLACE INTO store (collection, key, value) VALUES (?1, ?2, ?3)",
+                params![collection, key, value],
+            )?,
+            None => transaction.execute(
+                "DELETE FROM store WHERE collection = ?1 AND key = ?2",
+                params![collection, key],
+            )?,
+        };
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+//! `traits` defines the `Store` trait and the records it persists.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+
+use crate::model::common::{MerkleProof, SenderActiveState, TransferAudit, Uuid, Wallet};
+use crate::model::http::swap::Swap;
+use crate::model::http::transfer::TransferReceipt;
+use crate::result::Result;
+use crate::wallet::swap::SwapOrder;
+
+/// `Operation` is a write operation of a `Store`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Put {
+        collection: String,
+        key: String,
+        value: String,
+    },
+    Delete {
+        collection: String,
+        key: String,
+    },
+}
+
+/// `Store` is a persistent key-value store of JSON values, grouped in collections.
+/// Every write is atomic: a batch of operations is either fully applied or not at all.
+pub trait Store: Send + Sync {
+    /// `get_raw` returns the JSON value of a key of a collection.
+    fn get_raw(&self, collection: &str, key: &str) -> Result<Option<String>>;
+
+    /// `keys` returns the keys of a collection, in order.
+    fn keys(&self, collection: &str) -> Result<Vec<String>>;
+
+    /// `apply` applies a batch of operations atomically.
+    fn apply(&self, batch: Vec<Operation>) -> Result<()>;
+
+    /// `update_raw` atomically replaces the JSON value of a key of a collection
+    /// with the result of a function of its current value. A `None` result deletes the key.
+    fn update_raw(
+        &self,
+        collection: &str,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<Option<String>>,
+    ) -> Result<()>;
+
+    /// `put_raw` sets the JSON value of a key of a collection.
+    fn put_raw(&self, collection: &str, key: &str, value: String) -> Result<()> {
+        self.apply(vec![Operation::Put {
+            collection: collection.to_owned(),
+            key: key.to_owned(),
+            value,
+        }])
+    }
+
+    /// `delete` deletes a key of a collection.
+    fn delete(&self, collection: &str, key: &str) -> Result<()> {
+        self.apply(vec![Operation::Delete {
+            collection: collection.to_owned(),
+            key: key.to_owned(),
+        }])
+    }
+}
+
+/// `Record` is the trait implemented by the types persisted in their own collection.
+pub trait Record: Serialize + DeserializeOwned {
+    /// `COLLECTION` is the collection of the records.
+    const COLLECTION: &'static str;
+
+    /// `key` returns the key of the record in its collection.
+    fn key(&self) -> String;
+}
+
+/// `WalletProof` is a merkle proof of a wallet, recorded with the wallet it belongs to.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WalletProof {
+    pub wallet: Wallet,
+    pub proof: MerkleProof,
+}
+
+/// `WalletActiveState` is an active state of a wallet during an eon, recorded with
+/// the wallet it belongs to. A signed state replaces the unsigned state of the same transactions.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WalletActiveState {
+    pub wallet: Wallet,
+    pub eon_number: u64,
+    pub active_state: SenderActiveState,
+}
+
+/// `WalletNonce` is the nonce of the next transfer or swap sent by a wallet.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WalletNonce {
+    pub wallet: Wallet,
+    pub nonce: u64,
+}
+
+/// `LastNotification` is the last notification acknowledged on
+/// the WS Notification API endpoint of a hub.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LastNotification {
+    pub url: String,
+    pub uuid: Uuid,
+}
+
+impl Record for TransferAudit {
+    const COLLECTION: &'static str = "transfers";
+
+    fn key(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl Record for Swap {
+    const COLLECTION: &'static str = "swaps";
+
+    fn key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.wallet.token, self.wallet.address, self.nonce
+        )
+    }
+}
+
+impl Record for TransferReceipt {
+    const COLLECTION: &'static str = "transfer_receipts";
+
+    fn key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.wallet.token, self.wallet.address, self.nonce
+        )
+    }
+}
+
+impl Record for WalletNonce {
+    const COLLECTION: &'static str = "nonces";
+
+    fn key(&self) -> String {
+        format!("{}:{}", self.wallet.token, self.wallet.address)
+    }
+}
+
+impl Record for LastNotification {
+    const COLLECTION: &'static str = "last_notifications";
+
+    fn key(&self) -> String {
+        self.url.clone()
+    }
+}
+
+impl Record for SwapOrder {
+    const COLLECTION: &'static str = "swap_orders";
+
+    fn key(&self) -> String {
+        self.swap.key()
+    }
+}
+
+impl Record for WalletProof {
+    const COLLECTION: &'static str = "merkle_proofs";
+
+    fn key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.wallet.token, self.wallet.address, self.proof.eon_number
+        )
+    }
+}
+
+impl Record for WalletActiveState {
+    const COLLECTION: &'static str = "active_states";
+
+    fn key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.wallet.token, self.wallet.address, self.eon_number, self.active_state.tx_set_hash
+        )
+    }
+}
+
+/// `StoreExt` provides typed accessors to a `Store`.
+pub trait StoreExt: Store {
+    /// `get` returns the value of a key of a collection.
+    fn get<T: DeserializeOwned>(&self, collection: &str, key: &str) -> Result<Option<T>> {
+        match self.get_raw(collection, key)? {
+            Some(value) => Ok(Some(json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `put` sets the value of a key of a collection.
+    fn put<T: Serialize>(&self, collection: &str, key: &str, value: &T) -> Result<()> {
+        self.put_raw(collection, key, json::to_string(value)?)
+    }
+
+    /// `update` atomically replaces the value of a key of a collection
+    /// with the result of a function of its current value. A `None` result deletes the key.
+    fn update<T, F>(&self, collection: &str, key: &str, mut f: F) -> Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(Option<T>) -> Option<T>,
+    {
+        self.update_raw(collection, key, &mut |value| {
+            let value = match value {
+                Some(value) => Some(json::from_str(&value)?),
+                None => None,
+            };
+
+            match f(value) {
+                Some(value) => Ok(Some(json::to_string(&value)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// `load` returns a record by key.
+    fn load<R: Record>(&self, key: &str) -> Result<Option<R>> {
+        self.get(R::COLLECTION, key)
+    }
+
+    /// `load_all` returns the records of a collection, in key order.
+    fn load_all<R: Record>(&self) -> Result<Vec<R>> {
+        let mut records = Vec::new();
+
+        for key in self.keys(R::COLLECTION)? {
+            if let Some(record) = self.load(&key)? {
+                records.push(record);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// `save` persists a record.
+    fn save<R: Record>(&self, record: &R) -> Result<()> {
+        self.put(R::COLLECTION, &record.key(), record)
+    }
+
+    /// `save_all` persists a set of records atomically.
+    fn save_all<R: Record>(&self, records: &[R]) -> Result<()> {
+        let batch = records
+            .iter()
+            .map(|record| {
+                Ok(Operation::Put {
+                    collection: R::COLLECTION.to_owned(),
+                    key: record.key(),
+                    value: json::to_string(record)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.apply(batch)
+    }
+}
+
+impl<S: Store + ?Sized> StoreExt for S {}
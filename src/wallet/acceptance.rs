@@ -0,0 +1,253 @@
+//! `acceptance` defines the countersigning of the transfers received by a wallet.
+
+use crate::crypto::key::PrivateKey;
+use crate::crypto::merkle;
+use crate::crypto::proof::ProofFailure;
+use crate::crypto::protocol::{transfer_hash, ActiveStateUpdate};
+use crate::error::Error;
+use crate::model::common::{Address, Decimal, TransferAudit, Wallet};
+use crate::model::http::audit::WalletState;
+use crate::model::http::transfer::{Transfer, TransferReceipt, TransferUpdate};
+use crate::request::http::client::HubClient;
+use crate::request::http::transfer::TransferUpdateError;
+use crate::result::Result;
+use crate::wallet::transfer::TransactionSet;
+
+use std::fmt;
+
+/// `IncomingTransfer` is a transfer received by a wallet, waiting for its countersignature.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncomingTransfer {
+    pub id: i64,
+    pub wallet: Wallet,
+    pub recipient: Wallet,
+    pub amount: u64,
+    pub nonce: u64,
+    pub eon_number: Option<u64>,
+    pub recipient_starting_balance: Option<u64>,
+}
+
+impl IncomingTransfer {
+    /// `from_receipt` creates a new `IncomingTransfer` from the receipt of the transfer with an id.
+    /// The receipt carries no starting balance of the recipient, which is then
+    /// retrieved from the hub when accepting the transfer.
+    pub fn from_receipt(id: i64, receipt: &TransferReceipt) -> IncomingTransfer {
+        IncomingTransfer {
+            id,
+            wallet: receipt.wallet.clone(),
+            recipient: receipt.recipient.clone(),
+            amount: receipt.amount,
+            nonce: receipt.nonce,
+            eon_number: None,
+            recipient_starting_balance: None,
+        }
+    }
+}
+
+impl From<&TransferAudit> for IncomingTransfer {
+    fn from(transfer: &TransferAudit) -> IncomingTransfer {
+        IncomingTransfer {
+            id: transfer.id,
+            wallet: transfer.wallet.clone(),
+            recipient: transfer.recipient.clone(),
+            amount: transfer.amount,
+            nonce: transfer.nonce,
+            eon_number: Some(transfer.eon_number as u64),
+            recipient_starting_balance: transfer.recipient_starting_balance,
+        }
+    }
+}
+
+/// `AcceptanceStatus` is the outcome of the countersigning of an incoming transfer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AcceptanceStatus {
+    Accepted(Box<Transfer>),
+    AlreadyApproved,
+    Declined,
+    BalanceMismatch { expected: u64, reported: u64 },
+    Rejected(Vec<TransferUpdateError>),
+}
+
+/// `expected_balance` returns the balance of a wallet in an eon before
+/// its pending transfers, as computed from its wallet state, failing with
+/// an `Error::Underflow` if the wallet spent more than it was credited.
+pub fn expected_balance(set: &TransactionSet, state: &WalletState, eon_number: u64) -> Result<u64> {
+    let proof = state
+        .merkle_proofs
+        .iter()
+        .filter(|proof| proof.eon_number <= eon_number)
+        .max_by_key(|proof| proof.eon_number);
+
+    let allotment = match proof {
+        Some(proof) => proof
+            .right
+            .checked_sub(proof.left)
+            .ok_or(Error::InvalidProof {
+                failure: ProofFailure::Allotment,
+            })?,
+        None => 0,
+    };
+
+    let deposits = checked_sum(
+        state
+            .deposits
+            .iter()
+            .filter(|deposit| deposit.eon_number as u64 == eon_number)
+            .map(|deposit| deposit.amount),
+        "deposits",
+    )?;
+
+    let withdrawals = checked_sum(
+        state
+            .withdrawal_request
+            .iter()
+            .filter(|request| request.eon_number as u64 == eon_number)
+            .map(|request| request.amount),
+        "withdrawals",
+    )?;
+
+    let credits = checked_sum(vec![allotment, deposits, set.gains], "balance")?;
+    let debits = checked_sum(vec![set.spendings, withdrawals], "balance")?;
+
+    credits.checked_sub(debits).ok_or_else(|| Error::Underflow {
+        field: "balance".to_owned(),
+    })
+}
+
+/// `checked_sum` sums amounts, failing with an `Error::Overflow` of a field.
+fn checked_sum<I>(amounts: I, field: &str) -> Result<u64>
+where
+    I: IntoIterator<Item = u64>,
+{
+    amounts
+        .into_iter()
+        .try_fold(0u64, |sum, amount| sum.checked_add(amount))
+        .ok_or_else(|| Error::Overflow {
+            field: field.to_owned(),
+        })
+}
+
+/// `AcceptPolicy` decides which incoming transfers are countersigned.
+pub type AcceptPolicy<'a> = Box<dyn Fn(&IncomingTransfer) -> bool + Send + Sync + 'a>;
+
+/// `TransferReceiver` countersigns the transfers received by wallets on a hub.
+pub struct TransferReceiver<'a> {
+    client: &'a HubClient,
+    contract: Address,
+    policy: Option<AcceptPolicy<'a>>,
+}
+
+impl<'a> TransferReceiver<'a> {
+    /// `new` creates a new `TransferReceiver` for the hub contract, accepting every transfer.
+    pub fn new(client: &'a HubClient, contract: Address) -> TransferReceiver<'a> {
+        TransferReceiver {
+            client,
+            contract,
+            policy: None,
+        }
+    }
+
+    /// `with_policy` sets the policy deciding which incoming transfers are accepted.
+    pub fn with_policy<F>(mut self, policy: F) -> TransferReceiver<'a>
+    where
+        F: Fn(&IncomingTransfer) -> bool + Send + Sync + 'a,
+    {
+        self.policy = Some(Box::new(policy));
+        self
+    }
+
+    /// `accept` countersigns an incoming transfer with the key of its recipient.
+    pub async fn accept(
+        &self,
+        key: &PrivateKey,
+        incoming: &IncomingTransfer,
+    ) -> Result<AcceptanceStatus> {
+        if incoming.recipient.address != key.address() {
+            return Err(Error::RecipientMismatch {
+                recipient: incoming.recipient.address,
+                signer: key.address(),
+            });
+        }
+
+        if let Some(policy) = &self.policy {
+            if !policy(incoming) {
+                return Ok(AcceptanceStatus::Declined);
+            }
+        }
+
+        let eon_number = match incoming.eon_number {
+            Some(eon_number) => eon_number,
+            None => self.client.operator_status().await?.current_eon_number,
+        };
+        let state = self.client.wallet_state(&incoming.recipient).await?;
+
+        let mut set =
+            TransactionSet::from_state(&self.contract, &incoming.recipient, &state, eon_number)?;
+
+        let reported = match incoming.recipient_starting_balance {
+            Some(balance) => balance,
+            None => self
+                .client
+                .transfer(incoming.id)
+                .await?
+                .recipient_starting_balance
+                .ok_or_else(|| Error::MissingField {
+                    field: "recipient_starting_balance".to_owned(),
+                })?,
+        };
+
+        let expected = expected_balance(&set, &state, eon_number)?;
+
+        if expected != reported {
+            return Ok(AcceptanceStatus::BalanceMismatch { expected, reported });
+        }
+
+        let leaf = transfer_hash(
+            &self.contract,
+            &incoming.wallet,
+            &incoming.recipient,
+            incoming.amount,
+            incoming.nonce,
+        );
+        let index = set.index_of(Some(incoming.id), leaf);
+
+        let update = ActiveStateUpdate {
+            wallet: incoming.recipient.clone(),
+            trail_identifier: state.registration.trail_identifier,
+            eon_number,
+            tx_set_hash: merkle::root(&set.leaves),
+            updated_spendings: Decimal::from(set.spendings),
+            updated_gains: Decimal::from(checked_sum(vec![set.gains, incoming.amount], "gains")?),
+        };
+
+        let body = TransferUpdate {
+            recipient_active_state: set.active_state(&self.contract, key, &update, index)?,
+        };
+
+        let status = match self.client.update_transfer(incoming.id, &body).await {
+            Ok(transfer) => AcceptanceStatus::Accepted(Box::new(transfer)),
+            Err(Error::Hub { response, .. }) => {
+                let codes = response.typed_codes::<TransferUpdateError>();
+
+                if codes.contains(&TransferUpdateError::TransferAlreadyApproved) {
+                    AcceptanceStatus::AlreadyApproved
+                } else {
+                    AcceptanceStatus::Rejected(codes)
+                }
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(status)
+    }
+}
+
+impl<'a> fmt::Debug for TransferReceiver<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TransferReceiver")
+            .field("client", &self.client)
+            .field("contract", &self.contract)
+            .field("policy", &self.policy.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,181 @@
+//! `admission` defines the admission of wallets to a hub.
+
+use crate::crypto::key::PrivateKey;
+use crate::crypto::protocol::wallet_hash;
+use crate::error::Error;
+use crate::model::common::{Address, Wallet};
+use crate::model::http::admission::Admission;
+use crate::request::http::admission::{AdmissionCreateBulkError, AdmissionCreateError};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+/// `sign_admission` creates an `Admission` of a wallet to a token, authorized
+/// by signing the wallet hash in the hub contract.
+pub fn sign_admission(contract: &Address, key: &PrivateKey, token: &Address) -> Admission {
+    let wallet = Wallet {
+        address: key.address(),
+        token: *token,
+    };
+
+    Admission {
+        address: wallet.address,
+        authorization: key.sign_message(&wallet_hash(contract, &wallet)).value,
+        token: wallet.token,
+    }
+}
+
+/// `AdmissionStatus` is the outcome of the admission of a wallet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdmissionStatus {
+    Admitted,
+    AlreadyAdmitted,
+    Rejected(Vec<AdmissionCreateError>),
+}
+
+impl AdmissionStatus {
+    /// `is_admitted` returns if the wallet is admitted to the hub.
+    pub fn is_admitted(&self) -> bool {
+        match self {
+            AdmissionStatus::Admitted | AdmissionStatus::AlreadyAdmitted => true,
+            AdmissionStatus::Rejected(_) => false,
+        }
+    }
+}
+
+/// `AdmissionOutcome` is the outcome of the admission of a wallet to a token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdmissionOutcome {
+    pub wallet: Wallet,
+    pub status: AdmissionStatus,
+}
+
+/// `Admitter` admits wallets to a hub, using the bulk endpoint when
+/// more than one admission is requested. Bulk requests rejected with
+/// `TOO_MANY_ADMISSION_REQUESTS` are split in halves, while those rejected
+/// for another reason are retried once, one admission at a time. Wallets
+/// rejected with `WALLET_ALREADY_ADMITTED` are reported as `AlreadyAdmitted`.
+#[derive(Clone, Debug)]
+pub struct Admitter<'a> {
+    client: &'a HubClient,
+    contract: Address,
+    chunk_size: usize,
+}
+
+impl<'a> Admitter<'a> {
+    /// `DEFAULT_CHUNK_SIZE` is the default number of admissions sent in a bulk request.
+    pub const DEFAULT_CHUNK_SIZE: usize = 32;
+
+    /// `new` creates a new `Admitter` for the hub contract.
+    pub fn new(client: &'a HubClient, contract: Address) -> Admitter<'a> {
+        Admitter {
+            client,
+            contract,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// `with_chunk_size` sets the maximum number of admissions sent in a bulk request.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Admitter<'a> {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// `admit` admits the wallets of a key to a set of tokens.
+    pub async fn admit(
+        &self,
+        key: &PrivateKey,
+        tokens: &[Address],
+    ) -> Result<Vec<AdmissionOutcome>> {
+        self.admit_all(std::slice::from_ref(key), tokens).await
+    }
+
+    /// `admit_all` admits the wallets of a set of keys to a set of tokens.
+    /// Outcomes are returned in key-major order.
+    pub async fn admit_all(
+        &self,
+        keys: &[PrivateKey],
+        tokens: &[Address],
+    ) -> Result<Vec<AdmissionOutcome>> {
+        let admissions: Vec<Admission> = keys
+            .iter()
+            .flat_map(|key| {
+                tokens
+                    .iter()
+                    .map(move |token| sign_admission(&self.contract, key, token))
+            })
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(admissions.len());
+        let mut chunk_size = self.chunk_size;
+        let mut rest = &admissions[..];
+
+        while !rest.is_empty() {
+            let (chunk, next) = rest.split_at(chunk_size.min(rest.len()));
+
+            if chunk.len() == 1 {
+                outcomes.push(self.admit_one(&chunk[0]).await?);
+                rest = next;
+                continue;
+            }
+
+            match self.client.create_bulk_admission(chunk).await {
+                Ok(_) => {
+                    outcomes.extend(chunk.iter().map(|admission| AdmissionOutcome {
+                        wallet: wallet_of(admission),
+                        status: AdmissionStatus::Admitted,
+                    }));
+                }
+                Err(Error::Hub { ref response, .. })
+                    if response
+                        .typed_codes::<AdmissionCreateBulkError>()
+                        .contains(&AdmissionCreateBulkError::TooManyAdmissionRequests) =>
+                {
+                    chunk_size = (chunk.len() / 2).max(1);
+                    continue;
+                }
+                Err(Error::Hub { .. }) => {
+                    // The bulk endpoint doesn't tell which admissions failed,
+                    // so the chunk is retried one admission at a time.
+                    for admission in chunk {
+                        outcomes.push(self.admit_one(admission).await?);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+
+            rest = next;
+        }
+
+        Ok(outcomes)
+    }
+
+    /// `admit_one` sends a single admission to the hub.
+    async fn admit_one(&self, admission: &Admission) -> Result<AdmissionOutcome> {
+        let status = match self.client.create_admission(admission).await {
+            Ok(_) => AdmissionStatus::Admitted,
+            Err(Error::Hub { response, .. }) => {
+                let codes = response.typed_codes::<AdmissionCreateError>();
+
+                if codes.contains(&AdmissionCreateError::WalletAlreadyAdmitted) {
+                    AdmissionStatus::AlreadyAdmitted
+                } else {
+                    AdmissionStatus::Rejected(codes)
+                }
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(AdmissionOutcome {
+            wallet: wallet_of(admission),
+            status,
+        })
+    }
+}
+
+/// `wallet_of` returns the `Wallet` of an `Admission`.
+fn wallet_of(admission: &Admission) -> Wallet {
+    Wallet {
+        address: admission.address,
+        token: admission.token,
+    }
+}
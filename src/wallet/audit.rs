@@ -0,0 +1,658 @@
+//! `audit` defines the offline audit of the state of a wallet reported by a hub.
+
+use rug::Integer;
+
+use crate::crypto::hash::Hash;
+use crate::crypto::key::verify_message;
+use crate::crypto::protocol::{wallet_hash, ActiveStateUpdate};
+use crate::model::common::{
+    Address, Decimal, MerkleProof, SenderActiveState, Signature, TransferAudit, Wallet,
+};
+use crate::model::http::audit::WalletState;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// `Signer` is the party expected to have signed a message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Signer {
+    Wallet,
+    Operator,
+}
+
+/// `Field` is an accumulated field of an active state.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Field {
+    Spendings,
+    Gains,
+}
+
+/// `Subject` is the data a malformed discrepancy was found in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Subject {
+    Transfer,
+    MerkleProof,
+    Flows,
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Subject::Transfer => "transfer",
+            Subject::MerkleProof => "merkle proof",
+            Subject::Flows => "eon flows",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// `Discrepancy` is an inconsistency found in the state of a wallet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Discrepancy {
+    /// `InvalidSignature` is a signature not matching its expected signer.
+    InvalidSignature {
+        transfer_id: Option<i64>,
+        eon_number: u64,
+        signer: Signer,
+        expected: Address,
+    },
+    /// `MissingSignature` is a signature missing from an active state.
+    MissingSignature {
+        transfer_id: Option<i64>,
+        eon_number: u64,
+        signer: Signer,
+    },
+    /// `NonMonotonic` is an accumulated field decreasing between two transfers of an eon.
+    NonMonotonic {
+        transfer_id: i64,
+        eon_number: u64,
+        field: Field,
+        previous: Decimal,
+        current: Decimal,
+    },
+    /// `AmountMismatch` is an accumulated field not progressing by the amount of its transfer.
+    AmountMismatch {
+        transfer_id: i64,
+        eon_number: u64,
+        field: Field,
+        expected: Decimal,
+        actual: Decimal,
+    },
+    /// `BalanceMismatch` is a merkle proof allotment not matching the derived balance.
+    BalanceMismatch {
+        eon_number: u64,
+        expected: u64,
+        actual: u64,
+    },
+    /// `Overspent` is a wallet debited more than its allotment and credits in an eon.
+    Overspent {
+        eon_number: u64,
+        credits: u64,
+        debits: u64,
+    },
+    /// `Malformed` is data that could not be parsed.
+    Malformed {
+        subject: Subject,
+        transfer_id: Option<i64>,
+        eon_number: u64,
+        reason: String,
+    },
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Discrepancy::InvalidSignature {
+                transfer_id,
+                eon_number,
+                signer,
+                expected,
+            } => write!(
+                f,
+                "eon {}: invalid {:?} signature of transfer {:?}, expected signer {}",
+                eon_number, signer, transfer_id, expected
+            ),
+            Discrepancy::MissingSignature {
+                transfer_id,
+                eon_number,
+                signer,
+            } => write!(
+                f,
+                "eon {}: missing {:?} signature of transfer {:?}",
+                eon_number, signer, transfer_id
+            ),
+            Discrepancy::NonMonotonic {
+                transfer_id,
+                eon_number,
+                field,
+                previous,
+                current,
+            } => write!(
+                f,
+                "eon {}: {:?} of transfer {} decreased from {} to {}",
+                eon_number, field, transfer_id, previous, current
+            ),
+            Discrepancy::AmountMismatch {
+                transfer_id,
+                eon_number,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "eon {}: {:?} of transfer {} is {}, expected {}",
+                eon_number, field, transfer_id, actual, expected
+            ),
+            Discrepancy::BalanceMismatch {
+                eon_number,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "eon {}: allotment is {}, expected {}",
+                eon_number, actual, expected
+            ),
+            Discrepancy::Overspent {
+                eon_number,
+                credits,
+                debits,
+            } => write!(
+                f,
+                "eon {}: debits of {} exceed credits of {}",
+                eon_number, debits, credits
+            ),
+            Discrepancy::Malformed {
+                subject,
+                transfer_id: Some(transfer_id),
+                eon_number,
+                reason,
+            } => write!(
+                f,
+                "eon {}: malformed {} {}: {}",
+                eon_number, subject, transfer_id, reason
+            ),
+            Discrepancy::Malformed {
+                subject,
+                transfer_id: None,
+                eon_number,
+                reason,
+            } => write!(f, "eon {}: malformed {}: {}", eon_number, subject, reason),
+        }
+    }
+}
+
+/// `AuditReport` is the report of the audit of a wallet.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditReport {
+    pub transfers: usize,
+    pub signatures: usize,
+    pub proofs: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl AuditReport {
+    /// `is_clean` returns if the audit found no discrepancy.
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// `EonFlows` are the amounts moved in and out of a wallet during an eon.
+#[derive(Copy, Clone, Debug, Default)]
+struct EonFlows {
+    deposits: u64,
+    withdrawals: u64,
+    spendings: u64,
+    gains: u64,
+}
+
+/// `Auditor` audits the state of the wallets of a hub contract, offline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auditor {
+    contract: Address,
+    operator: Address,
+}
+
+impl Auditor {
+    /// `new` creates a new `Auditor` for the hub contract and its operator.
+    pub fn new(contract: Address, operator: Address) -> Auditor {
+        Auditor { contract, operator }
+    }
+
+    /// `audit_wallet` audits the state of a wallet, checking its signatures,
+    /// the progression of its active states and its balances.
+    pub fn audit_wallet(&self, wallet: &Wallet, state: &WalletState) -> AuditReport {
+        let mut report = AuditReport::default();
+
+        self.audit_registration(wallet, state, &mut report);
+        self.audit_transfers(wallet, state, &mut report);
+        self.audit_proofs(wallet, state, &mut report);
+        self.audit_balances(wallet, state, &mut report);
+
+        report
+    }
+
+    /// `check_signature` checks that a hash was signed by a signer.
+    #[allow(clippy::too_many_arguments)]
+    fn check_signature(
+        &self,
+        report: &mut AuditReport,
+        transfer_id: Option<i64>,
+        eon_number: u64,
+        hash: &Hash,
+        signature: &str,
+        signer: Signer,
+        wallet: &Wallet,
+    ) {
+        let expected = match signer {
+            Signer::Wallet => wallet.address,
+            Signer::Operator => self.operator,
+        };
+        let signature = Signature {
+            value: signature.to_owned(),
+        };
+
+        report.signatures += 1;
+
+        if signature.value.is_empty() {
+            report.discrepancies.push(Discrepancy::MissingSignature {
+                transfer_id,
+                eon_number,
+                signer,
+            });
+            return;
+        }
+
+        if !verify_message(hash, &signature, &expected) {
+            report.discrepancies.push(Discrepancy::InvalidSignature {
+                transfer_id,
+                eon_number,
+                signer,
+                expected,
+            });
+        }
+    }
+
+    /// `audit_registration` checks the signatures of the wallet registration.
+    fn audit_registration(&self, wallet: &Wallet, state: &WalletState, report: &mut AuditReport) {
+        let registration = &state.registration;
+        let hash = wallet_hash(&self.contract, wallet);
+
+        for (signature, signer) in &[
+            (&registration.wallet_signature, Signer::Wallet),
+            (&registration.operator_signature, Signer::Operator),
+        ] {
+            self.check_signature(
+                report,
+                None,
+                registration.eon_number,
+                &hash,
+                signature,
+                *signer,
+                wallet,
+            );
+        }
+    }
+
+    /// `audit_active_state` checks the signatures of an active state of a wallet,
+    /// and returns its parsed update. Active states not built yet are skipped, and
+    /// the operator signature is only expected once the hub processed the transfer.
+    fn audit_active_state(
+        &self,
+        report: &mut AuditReport,
+        transfer: &TransferAudit,
+        wallet: &Wallet,
+        trail_identifier: i64,
+        state: &SenderActiveState,
+    ) -> Option<ActiveStateUpdate> {
+        let eon_number = transfer.eon_number as u64;
+
+        if state.tx_set_hash.is_empty() {
+            return None;
+        }
+
+        let update =
+            match ActiveStateUpdate::from_state(wallet, trail_identifier as u64, eon_number, state)
+                .and_then(|update| update.hash(&self.contract).map(|hash| (update, hash)))
+            {
+                Ok(update) => update,
+                Err(err) => {
+                    report.discrepancies.push(Discrepancy::Malformed {
+                        subject: Subject::Transfer,
+                        transfer_id: Some(transfer.id),
+                        eon_number,
+                        reason: err.to_string(),
+                    });
+                    return None;
+                }
+            };
+
+        let (update, hash) = update;
+        let id = Some(transfer.id);
+
+        self.check_signature(
+            report,
+            id,
+            eon_number,
+            &hash,
+            &state.wallet_signature,
+            Signer::Wallet,
+            wallet,
+        );
+
+        if transfer.processed || !state.operator_signature.is_empty() {
+            self.check_signature(
+                report,
+                id,
+                eon_number,
+                &hash,
+                &state.operator_signature,
+                Signer::Operator,
+                wallet,
+            );
+        }
+
+        Some(update)
+    }
+
+    /// `audit_proof` checks the signatures of the active state of a merkle proof of a wallet.
+    fn audit_proof(&self, report: &mut AuditReport, wallet: &Wallet, proof: &MerkleProof) {
+        let state = &proof.active_state;
+
+        if state.tx_set_hash.is_empty() {
+            return;
+        }
+
+        let hash = match ActiveStateUpdate::from_active_state(
+            wallet,
+            proof.trail,
+            proof.eon_number,
+            state,
+        )
+        .and_then(|update| update.hash(&self.contract))
+        {
+            Ok(hash) => hash,
+            Err(err) => {
+                report.discrepancies.push(Discrepancy::Malformed {
+                    subject: Subject::MerkleProof,
+                    transfer_id: None,
+                    eon_number: proof.eon_number,
+                    reason: err.to_string(),
+                });
+                return;
+            }
+        };
+
+        for (signature, signer) in &[
+            (&state.wallet_signature, Signer::Wallet),
+            (&state.operator_signature, Signer::Operator),
+        ] {
+            self.check_signature(
+                report,
+                None,
+                proof.eon_number,
+                &hash,
+                signature,
+                *signer,
+                wallet,
+            );
+        }
+    }
+
+    /// `audit_proofs` checks the signatures of the active states of the merkle proofs of the wallet.
+    fn audit_proofs(&self, wallet: &Wallet, state: &WalletState, report: &mut AuditReport) {
+        for proof in &state.merkle_proofs {
+            self.audit_proof(report, wallet, proof);
+        }
+    }
+
+    /// `audit_transfers` checks the signatures of the transfers of the wallet,
+    /// and the progression of its active states within each eon.
+    fn audit_transfers(&self, wallet: &Wallet, state: &WalletState, report: &mut AuditReport) {
+        let mut transfers: Vec<_> = state.transfers.iter().collect();
+        transfers.sort_by_key(|transfer| (transfer.eon_number, transfer.id));
+
+        let mut previous: Option<(u64, ActiveStateUpdate)> = None;
+
+        for transfer in transfers {
+            report.transfers += 1;
+
+            let eon_number = transfer.eon_number as u64;
+            let outgoing = transfer.wallet == *wallet;
+
+            let sender = self.audit_active_state(
+                report,
+                transfer,
+                &transfer.wallet,
+                transfer.wallet_trail_identifier,
+                &transfer.sender_active_state,
+            );
+            let recipient = self.audit_active_state(
+                report,
+                transfer,
+                &transfer.recipient,
+                transfer.recipient_trail_identifier,
+                &transfer.recipient_active_state,
+            );
+
+            if transfer.voided || transfer.cancelled {
+                continue;
+            }
+
+            let current = match if outgoing { sender } else { recipient } {
+                Some(current) => current,
+                None => continue,
+            };
+
+            let (spendings, gains) = match &previous {
+                Some((eon, update)) if *eon == eon_number => (
+                    update.updated_spendings.clone(),
+                    update.updated_gains.clone(),
+                ),
+                _ => (Decimal::from(0), Decimal::from(0)),
+            };
+
+            let (spent, gained) = if outgoing {
+                (transfer.amount, 0)
+            } else {
+                (0, transfer.credited_amount())
+            };
+
+            for (field, previous, current, amount) in &[
+                (
+                    Field::Spendings,
+                    spendings,
+                    &current.updated_spendings,
+                    spent,
+                ),
+                (Field::Gains, gains, &current.updated_gains, gained),
+            ] {
+                if *current < previous {
+                    report.discrepancies.push(Discrepancy::NonMonotonic {
+                        transfer_id: transfer.id,
+                        eon_number,
+                        field: *field,
+                        previous: previous.clone(),
+                        current: (*current).clone(),
+                    });
+                    continue;
+                }
+
+                let expected = Decimal::new(Integer::from(previous.as_integer() + *amount));
+
+                if **current != expected {
+                    report.discrepancies.push(Discrepancy::AmountMismatch {
+                        transfer_id: transfer.id,
+                        eon_number,
+                        field: *field,
+                        expected,
+                        actual: (*current).clone(),
+                    });
+                }
+            }
+
+            previous = Some((eon_number, current));
+        }
+    }
+
+    /// `audit_balances` checks that the allotment of every merkle proof of the wallet
+    /// matches the balance derived from the previous proof and the flows of its eon.
+    fn audit_balances(&self, wallet: &Wallet, state: &WalletState, report: &mut AuditReport) {
+        let mut flows: BTreeMap<u64, EonFlows> = BTreeMap::new();
+        let mut overflows: BTreeMap<u64, &str> = BTreeMap::new();
+
+        let mut add = |eon_number: i64,
+                       reason: &'static str,
+                       amount: u64,
+                       pick: fn(&mut EonFlows) -> &mut u64| {
+            let eon_number = eon_number as u64;
+            let flow = pick(flows.entry(eon_number).or_default());
+
+            match flow.checked_add(amount) {
+                Some(sum) => *flow = sum,
+                None => {
+                    overflows.entry(eon_number).or_insert(reason);
+                }
+            }
+        };
+
+        for deposit in &state.deposits {
+            add(
+                deposit.eon_number,
+                "deposits overflow",
+                deposit.amount,
+                |flow| &mut flow.deposits,
+            );
+        }
+
+        for withdrawal in &state.withdrawal_request {
+            if !withdrawal.slashed {
+                add(
+                    withdrawal.eon_number,
+                    "withdrawals overflow",
+                    withdrawal.amount,
+                    |flow| &mut flow.withdrawals,
+                );
+            }
+        }
+
+        for transfer in &state.transfers {
+            if transfer.voided || transfer.cancelled {
+                continue;
+            }
+
+            if transfer.wallet == *wallet {
+                add(
+                    transfer.eon_number,
+                    "spendings overflow",
+                    transfer.amount,
+                    |flow| &mut flow.spendings,
+                );
+            } else if transfer.recipient == *wallet && transfer.complete {
+                add(
+                    transfer.eon_number,
+                    "gains overflow",
+                    transfer.credited_amount(),
+                    |flow| &mut flow.gains,
+                );
+            }
+        }
+
+        for (eon_number, reason) in overflows {
+            report.discrepancies.push(Discrepancy::Malformed {
+                subject: Subject::Flows,
+                transfer_id: None,
+                eon_number,
+                reason: reason.to_owned(),
+            });
+            flows.remove(&eon_number);
+        }
+
+        let mut proofs: Vec<_> = state.merkle_proofs.iter().collect();
+        proofs.sort_by_key(|proof| proof.eon_number);
+
+        for pair in proofs.windows(2) {
+            let (previous, current) = (pair[0], pair[1]);
+            report.proofs += 1;
+
+            if current.eon_number != previous.eon_number + 1 {
+                continue;
+            }
+
+            let flow = flows.get(&previous.eon_number).copied().unwrap_or_default();
+
+            let balances =
+                expected_allotment(previous, &flow, current.eon_number).and_then(|expected| {
+                    allotment(current)
+                        .map(|actual| (expected, actual))
+                        .map_err(|reason| malformed_proof(current.eon_number, reason))
+                });
+
+            let (expected, actual) = match balances {
+                Ok(balances) => balances,
+                Err(discrepancy) => {
+                    report.discrepancies.push(discrepancy);
+                    continue;
+                }
+            };
+
+            if expected != actual {
+                report.discrepancies.push(Discrepancy::BalanceMismatch {
+                    eon_number: current.eon_number,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+}
+
+/// `allotment` returns the size of the allotment of a merkle proof.
+fn allotment(proof: &MerkleProof) -> Result<u64, &'static str> {
+    proof
+        .right
+        .checked_sub(proof.left)
+        .ok_or("allotment right below left")
+}
+
+/// `malformed_proof` returns the discrepancy of a malformed merkle proof of an eon.
+fn malformed_proof(eon_number: u64, reason: &str) -> Discrepancy {
+    Discrepancy::Malformed {
+        subject: Subject::MerkleProof,
+        transfer_id: None,
+        eon_number,
+        reason: reason.to_owned(),
+    }
+}
+
+/// `expected_allotment` returns the size of the allotment of an eon following
+/// a merkle proof after the flows of its eon, or the discrepancy preventing it.
+fn expected_allotment(
+    previous: &MerkleProof,
+    flow: &EonFlows,
+    eon_number: u64,
+) -> Result<u64, Discrepancy> {
+    let malformed_flows = |reason: &str| Discrepancy::Malformed {
+        subject: Subject::Flows,
+        transfer_id: None,
+        eon_number,
+        reason: reason.to_owned(),
+    };
+
+    let credits = allotment(previous)
+        .map_err(|reason| malformed_proof(eon_number, reason))?
+        .checked_add(flow.deposits)
+        .and_then(|sum| sum.checked_add(flow.gains))
+        .ok_or_else(|| malformed_flows("credits overflow"))?;
+    let debits = flow
+        .withdrawals
+        .checked_add(flow.spendings)
+        .ok_or_else(|| malformed_flows("debits overflow"))?;
+
+    credits.checked_sub(debits).ok_or(Discrepancy::Overspent {
+        eon_number,
+        credits,
+        debits,
+    })
+}
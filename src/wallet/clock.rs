@@ -0,0 +1,265 @@
+//! `clock` defines the eon and block arithmetic of a hub.
+
+use chrono::{DateTime, Utc};
+
+use crate::model::common::Block;
+use crate::model::http::analytics::OperatorStatus;
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// `EonClock` maps the blocks of the chain of a hub to its eons, and estimates
+/// their wall-clock times from the block rate observed in the operator status.
+///
+/// Eon `n` spans the blocks `[origin + n * blocks_per_eon, origin + (n + 1) * blocks_per_eon)`.
+/// Unless set explicitly, the origin is the latest one consistent with every block
+/// observed, which assumes that the earliest observed block of an eon is its first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EonClock {
+    blocks_per_eon: u64,
+    origin: i128,
+    fixed_origin: bool,
+    latest: Block,
+    current_eon: u64,
+    challenge_window: u64,
+    observations: VecDeque<(u64, DateTime<Utc>)>,
+}
+
+impl EonClock {
+    /// `DEFAULT_BLOCK_TIME` is the block time assumed before the block rate can be observed.
+    pub const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(15);
+
+    /// `MAX_OBSERVATIONS` is the number of observations kept to estimate the block rate.
+    pub const MAX_OBSERVATIONS: usize = 64;
+
+    /// `MAX_ESTIMATE_MILLIS` bounds the distance in milliseconds between an estimated
+    /// time and the latest observation, keeping the estimates within the range of `DateTime`.
+    pub const MAX_ESTIMATE_MILLIS: i64 = 100_000 * 365 * 24 * 60 * 60 * 1000;
+
+    /// `new` creates a new `EonClock` from an operator status observed now.
+    pub fn new(status: &OperatorStatus) -> EonClock {
+        EonClock::observed_at(status, Utc::now())
+    }
+
+    /// `observed_at` creates a new `EonClock` from an operator status observed at a time.
+    /// The challenge window defaults to the last quarter of each eon.
+    pub fn observed_at(status: &OperatorStatus, time: DateTime<Utc>) -> EonClock {
+        let blocks_per_eon = status.blocks_per_eon.max(1);
+        let mut clock = EonClock {
+            blocks_per_eon,
+            origin: Self::upper_origin(&status.latest, blocks_per_eon),
+            fixed_origin: false,
+            latest: status.latest,
+            current_eon: status.current_eon_number,
+            challenge_window: blocks_per_eon / 4,
+            observations: VecDeque::new(),
+        };
+
+        clock.observe_at(status, time);
+        clock
+    }
+
+    /// `with_first_block` sets the first block of an eon, when known from the contract.
+    pub fn with_first_block(mut self, eon_number: u64, block: u64) -> EonClock {
+        self.origin = Self::origin_of(block, eon_number, self.blocks_per_eon);
+        self.fixed_origin = true;
+        self
+    }
+
+    /// `with_challenge_window` sets the number of blocks at the end of each eon
+    /// during which the operator answers challenges.
+    pub fn with_challenge_window(mut self, blocks: u64) -> EonClock {
+        self.challenge_window = blocks.min(self.blocks_per_eon);
+        self
+    }
+
+    /// `upper_origin` returns the latest origin consistent with a block.
+    fn upper_origin(block: &Block, blocks_per_eon: u64) -> i128 {
+        Self::origin_of(block.block, block.eon_number, blocks_per_eon)
+    }
+
+    /// `origin_of` returns the origin for which a block is the first of an eon.
+    fn origin_of(block: u64, eon_number: u64, blocks_per_eon: u64) -> i128 {
+        (block as i128).saturating_sub((eon_number as i128).saturating_mul(blocks_per_eon as i128))
+    }
+
+    /// `observe` records an operator status observed now.
+    pub fn observe(&mut self, status: &OperatorStatus) {
+        self.observe_at(status, Utc::now());
+    }
+
+    /// `observe_at` records an operator status observed at a time, refining the
+    /// eon boundaries with its blocks and the block rate with its latest block.
+    pub fn observe_at(&mut self, status: &OperatorStatus, time: DateTime<Utc>) {
+        if status.blocks_per_eon.max(1) != self.blocks_per_eon {
+            *self =
+                EonClock::observed_at(status, time).with_challenge_window(self.challenge_window);
+            return;
+        }
+
+        if !self.fixed_origin {
+            for block in status.confirmed.iter().chain(Some(&status.latest)) {
+                self.origin = self
+                    .origin
+                    .min(Self::upper_origin(block, self.blocks_per_eon));
+            }
+        }
+
+        if status.latest.block >= self.latest.block {
+            self.latest = status.latest;
+            self.current_eon = status.current_eon_number;
+        }
+
+        if self
+            .observations
+            .back()
+            .map(|(block, _)| status.latest.block > *block)
+            .unwrap_or(true)
+        {
+            self.observations.push_back((status.latest.block, time));
+
+            if self.observations.len() > Self::MAX_OBSERVATIONS {
+                self.observations.pop_front();
+            }
+        }
+    }
+
+    /// `blocks_per_eon` returns the number of blocks of an eon.
+    pub fn blocks_per_eon(&self) -> u64 {
+        self.blocks_per_eon
+    }
+
+    /// `current_block` returns the latest block observed.
+    pub fn current_block(&self) -> u64 {
+        self.latest.block
+    }
+
+    /// `current_eon` returns the current eon of the latest operator status observed.
+    pub fn current_eon(&self) -> u64 {
+        self.current_eon
+    }
+
+    /// `eon_of` returns the eon a block belongs to, if it is not before the first eon
+    /// nor beyond the range of `u64`.
+    pub fn eon_of(&self, block: u64) -> Option<u64> {
+        let offset = (block as i128).saturating_sub(self.origin);
+
+        if offset < 0 {
+            return None;
+        }
+
+        u64::try_from(offset / self.blocks_per_eon as i128).ok()
+    }
+
+    /// `first_block` returns the first block of an eon.
+    /// Blocks beyond the range of `u64` saturate.
+    pub fn first_block(&self, eon_number: u64) -> u64 {
+        let block = self
+            .origin
+            .saturating_add((eon_number as i128).saturating_mul(self.blocks_per_eon as i128));
+
+        u64::try_from(block.max(0)).unwrap_or(u64::MAX)
+    }
+
+    /// `last_block` returns the last block of an eon.
+    pub fn last_block(&self, eon_number: u64) -> u64 {
+        self.end_block(eon_number).saturating_sub(1)
+    }
+
+    /// `end_block` returns the first block after an eon.
+    fn end_block(&self, eon_number: u64) -> u64 {
+        match eon_number.checked_add(1) {
+            Some(next) => self.first_block(next),
+            None => u64::MAX,
+        }
+    }
+
+    /// `blocks_remaining` returns the number of blocks left in the current eon
+    /// after the latest block observed.
+    pub fn blocks_remaining(&self) -> u64 {
+        self.last_block(self.current_eon())
+            .saturating_sub(self.latest.block)
+    }
+
+    /// `is_in_challenge_window` returns if a block is in the challenge window of its eon.
+    pub fn is_in_challenge_window(&self, block: u64) -> bool {
+        match self.eon_of(block) {
+            Some(eon_number) => {
+                self.last_block(eon_number).saturating_sub(block) < self.challenge_window
+            }
+            None => false,
+        }
+    }
+
+    /// `block_time` returns the average block time observed, or the default block time
+    /// until two observations of different blocks were made.
+    pub fn block_time(&self) -> Duration {
+        match (self.observations.front(), self.observations.back()) {
+            (Some((first_block, first_time)), Some((last_block, last_time)))
+                if last_block > first_block && last_time > first_time =>
+            {
+                let elapsed = (*last_time - *first_time)
+                    .to_std()
+                    .unwrap_or(Self::DEFAULT_BLOCK_TIME);
+                let nanos = elapsed.as_nanos() / u128::from(last_block - first_block);
+
+                Duration::new(
+                    (nanos / 1_000_000_000) as u64,
+                    (nanos % 1_000_000_000) as u32,
+                )
+            }
+            _ => Self::DEFAULT_BLOCK_TIME,
+        }
+    }
+
+    /// `time_of` estimates the time of a block, from the latest observation.
+    pub fn time_of(&self, block: u64) -> DateTime<Utc> {
+        let (latest_block, latest_time) = match self.observations.back() {
+            Some((block, time)) => (*block, *time),
+            None => (self.latest.block, Utc::now()),
+        };
+        let block_time = self.block_time().as_millis() as i128;
+        let blocks = block as i128 - latest_block as i128;
+        let max = Self::MAX_ESTIMATE_MILLIS as i128;
+        let elapsed = blocks
+            .checked_mul(block_time)
+            .unwrap_or_else(|| blocks.signum() * max);
+
+        latest_time + chrono::Duration::milliseconds(elapsed.max(-max).min(max) as i64)
+    }
+
+    /// `block_at` estimates the block mined at a time, from the latest observation.
+    pub fn block_at(&self, time: DateTime<Utc>) -> u64 {
+        let (latest_block, latest_time) = match self.observations.back() {
+            Some((block, time)) => (*block, *time),
+            None => (self.latest.block, Utc::now()),
+        };
+        let block_time = self.block_time().as_millis().max(1) as i128;
+        let elapsed = (time - latest_time).num_milliseconds() as i128;
+
+        (latest_block as i128 + elapsed.div_euclid(block_time)).max(0) as u64
+    }
+
+    /// `eon_at` estimates the eon at a time, if it is not before the first eon.
+    pub fn eon_at(&self, time: DateTime<Utc>) -> Option<u64> {
+        self.eon_of(self.block_at(time))
+    }
+
+    /// `eon_start_time` estimates the time at which an eon starts.
+    pub fn eon_start_time(&self, eon_number: u64) -> DateTime<Utc> {
+        self.time_of(self.first_block(eon_number))
+    }
+
+    /// `eon_end_time` estimates the time at which an eon ends.
+    pub fn eon_end_time(&self, eon_number: u64) -> DateTime<Utc> {
+        self.time_of(self.end_block(eon_number))
+    }
+
+    /// `time_remaining` estimates the time left in the current eon, from now.
+    pub fn time_remaining(&self) -> Duration {
+        (self.eon_end_time(self.current_eon()) - Utc::now())
+            .to_std()
+            .unwrap_or_default()
+    }
+}
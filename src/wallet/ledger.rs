@@ -0,0 +1,359 @@
+//! `ledger` defines the local ledger of the balances of a wallet owner.
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::proof::ProofFailure;
+use crate::error::Error;
+use crate::model::common::{Address, TransferAudit, WithdrawalRequest};
+use crate::model::http::audit::WalletState;
+use crate::model::ws::ws::{Event, NotificationData, WalletEvent};
+use crate::result::Result;
+use crate::wallet::transfer::checked_add;
+
+use std::collections::BTreeMap;
+
+/// `Direction` is the direction of a transfer relative to the wallet owner.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+/// `Entry` is a transfer recorded in a `Ledger`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub eon_number: u64,
+    pub amount: u64,
+    pub direction: Direction,
+    pub complete: bool,
+}
+
+/// `Movement` is a deposit or withdrawal recorded in a `Ledger`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Movement {
+    pub eon_number: u64,
+    pub amount: u64,
+}
+
+/// `EonBalance` is the balance of a wallet during an eon.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EonBalance {
+    pub eon_number: u64,
+    pub opening: u64,
+    pub deposits: u64,
+    pub withdrawals: u64,
+    pub spent: u64,
+    pub received: u64,
+    pub pending_out: u64,
+    pub pending_in: u64,
+}
+
+impl EonBalance {
+    /// `closing` returns the balance at the end of the eon, once settled,
+    /// failing with an `Error::Underflow` if the wallet spent more than it held.
+    pub fn closing(&self) -> Result<u64> {
+        let credits = checked_add(self.opening, self.deposits, "credits")
+            .and_then(|sum| checked_add(sum, self.received, "credits"))?;
+        let debits = checked_add(self.spent, self.withdrawals, "debits")?;
+
+        credits.checked_sub(debits).ok_or_else(|| Error::Underflow {
+            field: "balance".to_owned(),
+        })
+    }
+
+    /// `spendable` returns the balance that can be spent during the eon.
+    pub fn spendable(&self) -> Result<u64> {
+        Ok(self.closing()?.saturating_sub(self.pending_out))
+    }
+}
+
+/// `TokenLedger` is the record of a wallet of a `Ledger` for a token.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenLedger {
+    pub allotments: BTreeMap<u64, u64>,
+    pub deposits: BTreeMap<String, Movement>,
+    pub withdrawals: BTreeMap<String, Movement>,
+    pub transfers: BTreeMap<i64, Entry>,
+}
+
+impl TokenLedger {
+    /// `eons` returns the eons of the records of the ledger.
+    fn eons<'a>(&'a self) -> impl Iterator<Item = u64> + 'a {
+        let movements = self.deposits.values().chain(self.withdrawals.values());
+
+        self.allotments
+            .keys()
+            .copied()
+            .chain(movements.map(|movement| movement.eon_number))
+            .chain(self.transfers.values().map(|entry| entry.eon_number))
+    }
+
+    /// `last_eon` returns the last eon recorded in the ledger.
+    pub fn last_eon(&self) -> Option<u64> {
+        self.eons().max()
+    }
+
+    /// `eon_balance` returns the balance of an eon opening with a balance.
+    fn eon_balance(&self, eon_number: u64, opening: u64) -> Result<EonBalance> {
+        let mut balance = EonBalance {
+            eon_number,
+            opening: self.allotments.get(&eon_number).copied().unwrap_or(opening),
+            ..EonBalance::default()
+        };
+
+        for movement in self.deposits.values() {
+            if movement.eon_number == eon_number {
+                balance.deposits = checked_add(balance.deposits, movement.amount, "deposits")?;
+            }
+        }
+
+        for movement in self.withdrawals.values() {
+            if movement.eon_number == eon_number {
+                balance.withdrawals =
+                    checked_add(balance.withdrawals, movement.amount, "withdrawals")?;
+            }
+        }
+
+        for entry in self.transfers.values() {
+            if entry.eon_number != eon_number {
+                continue;
+            }
+
+            let (sum, field) = match (entry.direction, entry.complete) {
+                (Direction::Outgoing, true) => (&mut balance.spent, "spent"),
+                (Direction::Outgoing, false) => (&mut balance.pending_out, "pending_out"),
+                (Direction::Incoming, true) => (&mut balance.received, "received"),
+                (Direction::Incoming, false) => (&mut balance.pending_in, "pending_in"),
+            };
+            *sum = checked_add(*sum, entry.amount, field)?;
+        }
+
+        Ok(balance)
+    }
+
+    /// `balance_at` returns the balance during an eon, carrying over the
+    /// closing balances of the previous eons without a merkle proof.
+    pub fn balance_at(&self, eon_number: u64) -> Result<EonBalance> {
+        let first = self.eons().min().unwrap_or(eon_number).min(eon_number);
+        let mut balance = self.eon_balance(first, 0)?;
+
+        for eon in (first..=eon_number).skip(1) {
+            balance = self.eon_balance(eon, balance.closing()?)?;
+        }
+
+        Ok(balance)
+    }
+}
+
+/// `Ledger` keeps the balances of the wallets of an owner, per token and per eon,
+/// from the audit data and the notifications of a hub. Recording the same data
+/// twice has no effect, so that overlapping audits and redelivered notifications
+/// can be applied safely.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    address: Address,
+    tokens: BTreeMap<Address, TokenLedger>,
+}
+
+impl Ledger {
+    /// `new` creates a new empty `Ledger` of the wallets of an address.
+    pub fn new(address: Address) -> Ledger {
+        Ledger {
+            address,
+            tokens: BTreeMap::new(),
+        }
+    }
+
+    /// `address` returns the address of the owner of the ledger.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// `tokens` returns the tokens recorded in the ledger.
+    pub fn tokens(&self) -> impl Iterator<Item = &Address> {
+        self.tokens.keys()
+    }
+
+    /// `token` returns the record of a token.
+    pub fn token(&self, token: &Address) -> Option<&TokenLedger> {
+        self.tokens.get(token)
+    }
+
+    /// `apply_wallet_state` records the audit state of the wallet of a token.
+    /// Nothing is recorded if a merkle proof allots an interval ending before it starts.
+    pub fn apply_wallet_state(&mut self, token: &Address, state: &WalletState) -> Result<()> {
+        let allotments = state
+            .merkle_proofs
+            .iter()
+            .map(|proof| {
+                proof
+                    .right
+                    .checked_sub(proof.left)
+                    .map(|allotment| (proof.eon_number, allotment))
+                    .ok_or(Error::InvalidProof {
+                        failure: ProofFailure::Allotment,
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let ledger = self.tokens.entry(*token).or_default();
+        ledger.allotments.extend(allotments);
+
+        for deposit in &state.deposits {
+            ledger.deposits.insert(
+                deposit.txid.clone(),
+                Movement {
+                    eon_number: deposit.eon_number as u64,
+                    amount: deposit.amount,
+                },
+            );
+        }
+
+        for request in &state.withdrawal_request {
+            self.apply_withdrawal_request(token, request);
+        }
+
+        for transfer in &state.transfers {
+            self.apply_transfer(transfer);
+        }
+
+        Ok(())
+    }
+
+    /// `apply_transfer` records a transfer to or from the wallets of the owner.
+    /// Swaps are recorded as outgoing from their debit token and incoming to their credit token.
+    pub fn apply_transfer(&mut self, transfer: &TransferAudit) {
+        let swap = transfer.wallet.token != transfer.recipient.token;
+        let sides = [
+            (&transfer.wallet, Direction::Outgoing, transfer.amount),
+            (
+                &transfer.recipient,
+                Direction::Incoming,
+                if swap {
+                    transfer.amount_swapped.unwrap_or_default()
+                } else {
+                    transfer.amount
+                },
+            ),
+        ];
+
+        for (wallet, direction, amount) in &sides {
+            if wallet.address != self.address {
+                continue;
+            }
+
+            let transfers = &mut self.tokens.entry(wallet.token).or_default().transfers;
+
+            if transfer.voided || transfer.cancelled {
+                transfers.remove(&transfer.id);
+                continue;
+            }
+
+            transfers.insert(
+                transfer.id,
+                Entry {
+                    eon_number: transfer.eon_number as u64,
+                    amount: *amount,
+                    direction: *direction,
+                    complete: transfer.complete,
+                },
+            );
+        }
+    }
+
+    /// `apply_event` records the data of a notification.
+    pub fn apply_event(&mut self, notification: &NotificationData) -> Result<()> {
+        if let Some(transfer) = notification.data.transfer() {
+            self.apply_transfer(transfer);
+        }
+
+        if let Event::Wallet(event) = &notification.data {
+            self.apply_wallet_event(event)?;
+        }
+
+        Ok(())
+    }
+
+    /// `apply_wallet_event` records the deposits, withdrawals and checkpoints
+    /// of a wallet event of the owner.
+    fn apply_wallet_event(&mut self, event: &WalletEvent) -> Result<()> {
+        let (address, token) = match event {
+            WalletEvent::ConfirmedDeposit(n) => (n.address, n.token),
+            WalletEvent::RequestWithdrawal(n) => (n.address, n.token),
+            WalletEvent::ConfirmedWithdrawal(n) => (n.address, n.token),
+            WalletEvent::CheckpointCreated(n) => (n.address, n.token),
+            _ => return Ok(()),
+        };
+
+        if address != self.address {
+            return Ok(());
+        }
+
+        match event {
+            WalletEvent::ConfirmedDeposit(notification) => {
+                let deposit = &notification.deposit;
+
+                self.tokens.entry(token).or_default().deposits.insert(
+                    deposit.txid.clone(),
+                    Movement {
+                        eon_number: deposit.eon_number as u64,
+                        amount: deposit.amount,
+                    },
+                );
+            }
+            WalletEvent::RequestWithdrawal(notification) => {
+                self.apply_withdrawal_request(&token, &notification.withdrawal_request);
+            }
+            WalletEvent::ConfirmedWithdrawal(notification) => {
+                self.apply_withdrawal_request(&token, &notification.withdrawal.request);
+            }
+            WalletEvent::CheckpointCreated(notification) => {
+                self.apply_wallet_state(&token, &notification.wallet_data)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// `apply_withdrawal_request` records a withdrawal request of the wallet of a token.
+    fn apply_withdrawal_request(&mut self, token: &Address, request: &WithdrawalRequest) {
+        let withdrawals = &mut self.tokens.entry(*token).or_default().withdrawals;
+
+        if request.slashed {
+            withdrawals.remove(&request.txid);
+        } else {
+            withdrawals.insert(
+                request.txid.clone(),
+                Movement {
+                    eon_number: request.eon_number as u64,
+                    amount: request.amount,
+                },
+            );
+        }
+    }
+
+    /// `last_eon` returns the last eon recorded for a token.
+    pub fn last_eon(&self, token: &Address) -> Option<u64> {
+        self.tokens.get(token).and_then(TokenLedger::last_eon)
+    }
+
+    /// `balance_at` returns the balance of the wallet of a token during an eon.
+    pub fn balance_at(&self, token: &Address, eon_number: u64) -> Result<EonBalance> {
+        match self.tokens.get(token) {
+            Some(ledger) => ledger.balance_at(eon_number),
+            None => Ok(EonBalance {
+                eon_number,
+                ..EonBalance::default()
+            }),
+        }
+    }
+
+    /// `spendable` returns the balance of the wallet of a token that can be spent
+    /// during the current eon of the hub, as given by an `EonClock`. The eons since
+    /// the last record carry over its closing balance.
+    pub fn spendable(&self, token: &Address, current_eon: u64) -> Result<u64> {
+        self.balance_at(token, current_eon)?.spendable()
+    }
+}
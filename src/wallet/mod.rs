@@ -0,0 +1,28 @@
+//! `wallet` defines the high-level operations of wallets on a hub.
+
+/// `admission` defines the admission of wallets to a hub.
+pub mod admission;
+
+/// `transfer` defines the lifecycle of the transfers sent by a wallet.
+pub mod transfer;
+
+/// `acceptance` defines the countersigning of the transfers received by a wallet.
+pub mod acceptance;
+
+/// `swap` defines the lifecycle of the swap orders of a wallet.
+pub mod swap;
+
+/// `signatures` defines the builder of the multi-eon signature vectors of swaps.
+pub mod signatures;
+
+/// `audit` defines the offline audit of the state of a wallet reported by a hub.
+pub mod audit;
+
+/// `ledger` defines the local ledger of the balances of a wallet owner.
+pub mod ledger;
+
+/// `clock` defines the eon and block arithmetic of a hub.
+pub mod clock;
+
+/// `sla` defines the purchase and the quota tracking of the SLAs of wallets.
+pub mod sla;
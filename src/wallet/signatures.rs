@@ -0,0 +1,324 @@
+//! `signatures` defines the builder of the multi-eon signature vectors of swaps.
+
+use crate::crypto::hash::Hash;
+use crate::crypto::key::PrivateKey;
+use crate::crypto::merkle;
+use crate::crypto::protocol::{
+    balance_marker_hash, swap_freeze_hash, swap_hash, ActiveStateUpdate,
+};
+use crate::error::Error;
+use crate::model::common::{Address, Decimal, SenderActiveState, Signature, Wallet};
+use crate::model::http::swap::{Swap, SwapCancellation, SwapFinalization, SwapFreeze};
+use crate::result::Result;
+use crate::wallet::transfer::{checked_add, TransactionSet};
+
+use std::ops::RangeInclusive;
+
+/// `Side` is one of the two wallets of a swap.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Side {
+    Debit,
+    Credit,
+}
+
+/// `SwapSignatures` builds the signature vectors of a swap, with one signature
+/// per eon in which the swap remains open.
+///
+/// In the current eon, the swap is appended to the transaction set of each wallet,
+/// on top of its other transfers of the eon. In the following eons, the swap is
+/// carried over as the only transaction of each wallet. An existing swap already
+/// in a transaction set keeps its leaf, and its amounts are projected in place of
+/// the amounts the set holds for it.
+#[derive(Clone, Debug)]
+pub struct SwapSignatures<'a> {
+    contract: &'a Address,
+    key: &'a PrivateKey,
+    swap_id: Option<i64>,
+    wallet: Wallet,
+    recipient: Wallet,
+    amount: u64,
+    amount_swapped: u64,
+    nonce: u64,
+    eon_number: Option<u64>,
+    current_eon_number: u64,
+    lifetime: u64,
+    max_lifetime: Option<u64>,
+    debit_trail: u64,
+    credit_trail: u64,
+    debit_set: TransactionSet,
+    credit_set: TransactionSet,
+}
+
+impl<'a> SwapSignatures<'a> {
+    /// `DEFAULT_MAX_LIFETIME` is the maximum number of eons a swap can be open for,
+    /// when the signatures are not configured with the limit of the hub.
+    pub const DEFAULT_MAX_LIFETIME: u64 = 10;
+
+    /// `new` creates a new `SwapSignatures` for a swap of an amount of a token
+    /// from the wallet of a key, for an amount of another token.
+    pub fn new(
+        contract: &'a Address,
+        key: &'a PrivateKey,
+        debit_token: &Address,
+        credit_token: &Address,
+        amount: u64,
+        amount_swapped: u64,
+        nonce: u64,
+    ) -> SwapSignatures<'a> {
+        SwapSignatures {
+            contract,
+            key,
+            swap_id: None,
+            wallet: Wallet {
+                address: key.address(),
+                token: *debit_token,
+            },
+            recipient: Wallet {
+                address: key.address(),
+                token: *credit_token,
+            },
+            amount,
+            amount_swapped,
+            nonce,
+            eon_number: None,
+            current_eon_number: 0,
+            lifetime: 1,
+            max_lifetime: None,
+            debit_trail: 0,
+            credit_trail: 0,
+            debit_set: TransactionSet::default(),
+            credit_set: TransactionSet::default(),
+        }
+    }
+
+    /// `from_swap` creates a new `SwapSignatures` for an existing swap.
+    pub fn from_swap(
+        contract: &'a Address,
+        key: &'a PrivateKey,
+        swap: &Swap,
+    ) -> SwapSignatures<'a> {
+        let mut signatures = SwapSignatures::new(
+            contract,
+            key,
+            &swap.wallet.token,
+            &swap.recipient.token,
+            swap.amount,
+            swap.amount_swapped.unwrap_or_default(),
+            swap.nonce,
+        );
+        signatures.swap_id = swap.id;
+        signatures.eon_number = Some(swap.eon_number);
+        signatures.current_eon_number = swap.eon_number;
+        signatures
+    }
+
+    /// `with_lifetime` sets the current eon and the number of eons the swap remains open.
+    /// A new swap starts in the current eon.
+    pub fn with_lifetime(mut self, current_eon_number: u64, lifetime: u64) -> SwapSignatures<'a> {
+        self.current_eon_number = current_eon_number;
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// `with_max_lifetime` sets the maximum number of eons the hub accepts future signatures for.
+    pub fn with_max_lifetime(mut self, max_lifetime: u64) -> SwapSignatures<'a> {
+        self.max_lifetime = Some(max_lifetime);
+        self
+    }
+
+    /// `with_debit_state` sets the trail and current eon transactions of the debit wallet.
+    pub fn with_debit_state(mut self, trail: u64, set: TransactionSet) -> SwapSignatures<'a> {
+        self.debit_trail = trail;
+        self.debit_set = set;
+        self
+    }
+
+    /// `with_credit_state` sets the trail and current eon transactions of the credit wallet.
+    pub fn with_credit_state(mut self, trail: u64, set: TransactionSet) -> SwapSignatures<'a> {
+        self.credit_trail = trail;
+        self.credit_set = set;
+        self
+    }
+
+    /// `debit_wallet` returns the debit wallet of the swap.
+    pub fn debit_wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    /// `credit_wallet` returns the credit wallet of the swap.
+    pub fn credit_wallet(&self) -> &Wallet {
+        &self.recipient
+    }
+
+    /// `hash` returns the hash of the swap.
+    pub fn hash(&self) -> Hash {
+        swap_hash(
+            self.contract,
+            &self.wallet,
+            &self.recipient,
+            self.amount,
+            self.amount_swapped,
+            self.nonce,
+        )
+    }
+
+    /// `eon_number` returns the eon in which the swap starts.
+    pub fn eon_number(&self) -> u64 {
+        self.eon_number.unwrap_or(self.current_eon_number)
+    }
+
+    /// `eons` returns the eons in which the swap remains open, from the current eon.
+    /// The lifetime is bounded by `DEFAULT_MAX_LIFETIME` unless a maximum is set.
+    pub fn eons(&self) -> Result<RangeInclusive<u64>> {
+        let max = self.max_lifetime.unwrap_or(Self::DEFAULT_MAX_LIFETIME);
+
+        if self.lifetime == 0 || self.lifetime > max {
+            return Err(Error::OutOfRange {
+                value: self.lifetime as i64,
+                min: Some(1),
+                max: Some(max as i64),
+            });
+        }
+
+        let last = checked_add(self.eon_number(), self.lifetime - 1, "eon_number")?;
+        let first = self.current_eon_number.max(self.eon_number());
+
+        if first > last {
+            return Err(Error::OutOfRange {
+                value: first as i64,
+                min: Some(self.eon_number() as i64),
+                max: Some(last as i64),
+            });
+        }
+
+        Ok(first..=last)
+    }
+
+    /// `project` returns the active state of a side of the swap in an eon, with the
+    /// spendings and gains of the swap, and the index of the swap in its transaction set.
+    fn project(
+        &self,
+        side: Side,
+        eon_number: u64,
+        spendings: u64,
+        gains: u64,
+    ) -> Result<(ActiveStateUpdate, TransactionSet, usize)> {
+        let (wallet, trail_identifier, current) = match side {
+            Side::Debit => (&self.wallet, self.debit_trail, &self.debit_set),
+            Side::Credit => (&self.recipient, self.credit_trail, &self.credit_set),
+        };
+
+        let mut set = if eon_number == self.current_eon_number {
+            current.clone()
+        } else {
+            TransactionSet::default()
+        };
+        let index = set.index_of(self.swap_id, self.hash());
+        let (other_spendings, other_gains) = set.totals_without(index);
+
+        let update = ActiveStateUpdate {
+            wallet: wallet.clone(),
+            trail_identifier,
+            eon_number,
+            tx_set_hash: merkle::root(&set.leaves),
+            updated_spendings: Decimal::from(checked_add(other_spendings, spendings, "spendings")?),
+            updated_gains: Decimal::from(checked_add(other_gains, gains, "gains")?),
+        };
+
+        Ok((update, set, index))
+    }
+
+    /// `sign_projections` signs the active states of a side of the swap in every open eon.
+    fn sign_projections(&self, side: Side, spendings: u64, gains: u64) -> Result<Vec<Signature>> {
+        self.eons()?
+            .map(|eon_number| {
+                let (update, _, _) = self.project(side, eon_number, spendings, gains)?;
+                update.sign(self.contract, self.key)
+            })
+            .collect()
+    }
+
+    /// `sign_balance_markers` signs the balance of a side of the swap in every open eon.
+    fn sign_balance_markers(&self, side: Side, balance: u64) -> Result<Vec<Signature>> {
+        let wallet = match side {
+            Side::Debit => &self.wallet,
+            Side::Credit => &self.recipient,
+        };
+
+        let signatures = self
+            .eons()?
+            .map(|eon_number| {
+                let hash = balance_marker_hash(self.contract, wallet, eon_number, balance);
+                self.key.sign_message(&hash)
+            })
+            .collect();
+
+        Ok(signatures)
+    }
+
+    /// `active_state` builds the signed active state of a side of the swap in the current eon.
+    fn active_state(&self, side: Side, spendings: u64, gains: u64) -> Result<SenderActiveState> {
+        let (update, set, index) = self.project(side, self.current_eon_number, spendings, gains)?;
+        set.active_state(self.contract, self.key, &update, index)
+    }
+
+    /// `build` builds the signed `Swap` to be created in the hub.
+    /// The debit wallet must hold exactly the swapped amount and the credit wallet must be empty.
+    pub fn build(&self) -> Result<Swap> {
+        let swap = Swap {
+            id: self.swap_id,
+            amount: self.amount,
+            amount_swapped: Some(self.amount_swapped),
+            credit_balance_signature: self.sign_balance_markers(Side::Credit, 0)?,
+            credit_signature: self.sign_projections(Side::Credit, 0, 0)?,
+            debit_balance_signature: self.sign_balance_markers(Side::Debit, self.amount)?,
+            debit_signature: self.sign_projections(Side::Debit, self.amount, 0)?,
+            eon_number: self.eon_number(),
+            final_receipt_hashes: None,
+            final_receipt_index: 0,
+            fulfillment_signature: self.sign_projections(Side::Credit, 0, self.amount_swapped)?,
+            nonce: self.nonce,
+            recipient: self.recipient.clone(),
+            recipient_active_state: self.active_state(Side::Credit, 0, 0)?,
+            sender_active_state: self.active_state(Side::Debit, self.amount, 0)?,
+            wallet: self.wallet.clone(),
+        };
+
+        Ok(swap)
+    }
+
+    /// `freeze` builds the freezing signatures of the swap.
+    pub fn freeze(&self) -> Result<SwapFreeze> {
+        let hash = self.hash();
+
+        let freeze = SwapFreeze {
+            freezing_signature: self
+                .eons()?
+                .map(|eon_number| self.key.sign_message(&swap_freeze_hash(&hash, eon_number)))
+                .collect(),
+        };
+
+        Ok(freeze)
+    }
+
+    /// `cancellation` builds the cancellation signatures of the swap,
+    /// reverting its debit and credit.
+    pub fn cancellation(&self) -> Result<SwapCancellation> {
+        let cancellation = SwapCancellation {
+            recipient_cancellation_signature: self.sign_projections(Side::Credit, 0, 0)?,
+            sender_cancellation_signature: self.sign_projections(Side::Debit, 0, 0)?,
+        };
+
+        Ok(cancellation)
+    }
+
+    /// `finalization` builds the finalization signatures of the swap,
+    /// crediting the swapped amount.
+    pub fn finalization(&self) -> Result<SwapFinalization> {
+        let finalization = SwapFinalization {
+            finalization_signature: self.sign_projections(Side::Credit, 0, self.amount_swapped)?,
+        };
+
+        Ok(finalization)
+    }
+}
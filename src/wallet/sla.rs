@@ -0,0 +1,243 @@
+//! `sla` defines the purchase and the quota tracking of the SLAs of wallets.
+
+use chrono::Utc;
+
+use crate::crypto::key::PrivateKey;
+use crate::error::Error;
+use crate::model::common::{Address, Wallet};
+use crate::model::http::audit::WalletState;
+use crate::model::http::sla::{WalletSLA, SLA};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+use crate::wallet::transfer::{TransferOutcome, TransferSender, TransferStatus};
+
+use std::time::Duration;
+
+/// `SLAWarning` is a warning that the SLA of a wallet is about to stop covering its transfers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SLAWarning {
+    /// `Expiring` is an SLA expiring within the warning delay.
+    Expiring { remaining: Duration },
+    /// `Expired` is an SLA past its expiry.
+    Expired,
+    /// `Exhausting` is an SLA whose usage of the current eon reached the warning threshold.
+    Exhausting { used: u64, limit: u64 },
+    /// `Exhausted` is an SLA whose usage of the current eon reached its limit.
+    Exhausted { limit: u64 },
+}
+
+/// `SLAQuota` tracks the transfers of a wallet against the limit of its SLA.
+///
+/// The limit of an SLA is the number of transfers a wallet can take part in
+/// during an eon, as sender or recipient, until the SLA expires.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SLAQuota {
+    wallet: Wallet,
+    limit: u64,
+    expiry: chrono::DateTime<Utc>,
+    eon_number: u64,
+    used: u64,
+    expiry_warning: Duration,
+    usage_warning: u64,
+}
+
+impl SLAQuota {
+    /// `DEFAULT_EXPIRY_WARNING` is the default delay before expiry from which an SLA is expiring.
+    pub const DEFAULT_EXPIRY_WARNING: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// `DEFAULT_USAGE_WARNING` is the default percentage of the limit from which an SLA is exhausting.
+    pub const DEFAULT_USAGE_WARNING: u64 = 90;
+
+    /// `new` creates a new `SLAQuota` of a wallet, covered by an SLA until its expiry.
+    pub fn new(wallet: Wallet, sla: &SLA, wallet_sla: &WalletSLA) -> SLAQuota {
+        SLAQuota {
+            wallet,
+            limit: sla.limit,
+            expiry: *wallet_sla.expiry.as_datetime(),
+            eon_number: 0,
+            used: 0,
+            expiry_warning: Self::DEFAULT_EXPIRY_WARNING,
+            usage_warning: Self::DEFAULT_USAGE_WARNING,
+        }
+    }
+
+    /// `with_expiry_warning` sets the delay before expiry from which the SLA is expiring.
+    pub fn with_expiry_warning(mut self, expiry_warning: Duration) -> SLAQuota {
+        self.expiry_warning = expiry_warning;
+        self
+    }
+
+    /// `with_usage_warning` sets the percentage of the limit from which the SLA is exhausting.
+    pub fn with_usage_warning(mut self, percentage: u64) -> SLAQuota {
+        self.usage_warning = percentage.min(100);
+        self
+    }
+
+    /// `wallet` returns the wallet covered by the SLA.
+    pub fn wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    /// `limit` returns the number of transfers covered by the SLA per eon.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// `expiry` returns the time at which the SLA expires.
+    pub fn expiry(&self) -> &chrono::DateTime<Utc> {
+        &self.expiry
+    }
+
+    /// `used` returns the number of transfers of the wallet in the current eon.
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// `remaining` returns the number of transfers left to the wallet in the current eon.
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// `renew` records the renewal of the SLA of the wallet.
+    pub fn renew(&mut self, sla: &SLA, wallet_sla: &WalletSLA) {
+        self.limit = sla.limit;
+        self.expiry = *wallet_sla.expiry.as_datetime();
+    }
+
+    /// `record_transfer` records a transfer of the wallet in an eon.
+    /// A transfer of a later eon resets the usage.
+    pub fn record_transfer(&mut self, eon_number: u64) {
+        if eon_number > self.eon_number {
+            self.eon_number = eon_number;
+            self.used = 0;
+        }
+
+        if eon_number == self.eon_number {
+            self.used = self.used.saturating_add(1);
+        }
+    }
+
+    /// `apply_wallet_state` sets the usage of an eon from the audit state of the wallet.
+    pub fn apply_wallet_state(&mut self, state: &WalletState, eon_number: u64) {
+        if eon_number < self.eon_number {
+            return;
+        }
+
+        self.eon_number = eon_number;
+        self.used = state
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.eon_number as u64 == eon_number)
+            .filter(|transfer| !transfer.voided && !transfer.cancelled)
+            .filter(|transfer| transfer.wallet == self.wallet || transfer.recipient == self.wallet)
+            .count() as u64;
+    }
+
+    /// `warnings` returns the warnings of the SLA at a time.
+    pub fn warnings(&self, now: chrono::DateTime<Utc>) -> Vec<SLAWarning> {
+        let mut warnings = Vec::new();
+
+        match (self.expiry - now).to_std() {
+            Ok(remaining) if remaining > Duration::from_secs(0) => {
+                if remaining <= self.expiry_warning {
+                    warnings.push(SLAWarning::Expiring { remaining });
+                }
+            }
+            _ => warnings.push(SLAWarning::Expired),
+        }
+
+        if self.used >= self.limit {
+            warnings.push(SLAWarning::Exhausted { limit: self.limit });
+        } else if u128::from(self.used) * 100
+            >= u128::from(self.limit) * u128::from(self.usage_warning)
+        {
+            warnings.push(SLAWarning::Exhausting {
+                used: self.used,
+                limit: self.limit,
+            });
+        }
+
+        warnings
+    }
+
+    /// `is_covered` returns if the SLA covers another transfer of the wallet at a time.
+    pub fn is_covered(&self, now: chrono::DateTime<Utc>) -> bool {
+        self.expiry > now && self.used < self.limit
+    }
+}
+
+/// `SLAPurchase` is the outcome of the purchase of an SLA.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SLAPurchase {
+    pub payment: TransferOutcome,
+    pub quota: Option<SLAQuota>,
+}
+
+/// `SLAPurchaser` buys the SLA offered by a hub for wallets.
+#[derive(Clone, Debug)]
+pub struct SLAPurchaser<'a> {
+    client: &'a HubClient,
+    sender: TransferSender<'a>,
+}
+
+impl<'a> SLAPurchaser<'a> {
+    /// `new` creates a new `SLAPurchaser` for the hub contract.
+    pub fn new(client: &'a HubClient, contract: Address) -> SLAPurchaser<'a> {
+        SLAPurchaser {
+            client,
+            sender: TransferSender::new(client, contract),
+        }
+    }
+
+    /// `with_sender` sets the sender of the SLA payments.
+    pub fn with_sender(mut self, sender: TransferSender<'a>) -> SLAPurchaser<'a> {
+        self.sender = sender;
+        self
+    }
+
+    /// `offer` returns the SLA offered by the hub.
+    pub async fn offer(&self) -> Result<SLA> {
+        let sla = self.client.sla().await?;
+        sla.validate()?;
+
+        Ok(sla)
+    }
+
+    /// `purchase` pays the SLA offered by the hub from the wallet of a key in the
+    /// SLA token, and returns the quota of the wallet once the payment completes.
+    pub async fn purchase(&self, key: &PrivateKey) -> Result<SLAPurchase> {
+        let sla = self.offer().await?;
+        self.purchase_offer(key, &sla).await
+    }
+
+    /// `purchase_offer` pays an SLA from the wallet of a key in the SLA token, and
+    /// returns the quota of the wallet once the payment completes.
+    pub async fn purchase_offer(&self, key: &PrivateKey, sla: &SLA) -> Result<SLAPurchase> {
+        let cost = sla.cost.as_integer();
+        let amount = cost.to_u64().ok_or_else(|| Error::OutOfRange {
+            value: cost.to_i64().unwrap_or(i64::MAX),
+            min: Some(0),
+            max: None,
+        })?;
+
+        let recipient = Wallet {
+            address: sla.recipient,
+            token: sla.token,
+        };
+        let payment = self.sender.send_transfer(key, &recipient, amount).await?;
+
+        let quota = if payment.status == TransferStatus::Complete {
+            let wallet = Wallet {
+                address: key.address(),
+                token: sla.token,
+            };
+            let wallet_sla = self.client.wallet_sla(&wallet).await?;
+
+            Some(SLAQuota::new(wallet, sla, &wallet_sla))
+        } else {
+            None
+        };
+
+        Ok(SLAPurchase { payment, quota })
+    }
+}
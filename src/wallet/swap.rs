@@ -0,0 +1,432 @@
+//! `swap` defines the lifecycle of the swap orders of a wallet.
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::hash::Hash;
+use crate::crypto::key::PrivateKey;
+use crate::crypto::protocol::swap_hash;
+use crate::error::Error;
+use crate::model::common::{Address, Wallet};
+use crate::model::http::swap::{Swap, SwapCancellation, SwapFinalization, SwapFreeze};
+use crate::model::traits::{FromJson, ToJson};
+use crate::request::http::client::HubClient;
+use crate::request::http::swap::{SwapCancelError, SwapFinalizeError, SwapFreezeError};
+use crate::result::Result;
+use crate::store::traits::{Store, StoreExt};
+use crate::wallet::signatures::SwapSignatures;
+use crate::wallet::transfer::{next_nonce, TransactionSet};
+
+use std::fmt;
+
+/// `SwapState` is the state of a swap order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapState {
+    Created,
+    Matched,
+    Fulfilled,
+    Finalized,
+    Frozen,
+    Cancelled,
+    Voided,
+}
+
+impl SwapState {
+    /// `is_closed` returns if no further transition is possible from the state.
+    pub fn is_closed(self) -> bool {
+        matches!(
+            self,
+            SwapState::Finalized | SwapState::Cancelled | SwapState::Voided
+        )
+    }
+
+    /// `check_transition` returns the reason the hub would reject
+    /// a transition of the state to another state, as the error code of the
+    /// request causing the transition. Transitions to the other states are
+    /// driven by the hub, and unsupported unless part of the lifecycle.
+    pub fn check_transition(self, to: SwapState) -> std::result::Result<(), SwapTransitionError> {
+        let reason = match (self, to) {
+            (SwapState::Created, SwapState::Matched)
+            | (SwapState::Created, SwapState::Fulfilled)
+            | (SwapState::Matched, SwapState::Matched)
+            | (SwapState::Matched, SwapState::Fulfilled)
+            | (SwapState::Created, SwapState::Frozen)
+            | (SwapState::Matched, SwapState::Frozen)
+            | (SwapState::Created, SwapState::Voided)
+            | (SwapState::Matched, SwapState::Voided)
+            | (SwapState::Fulfilled, SwapState::Finalized)
+            | (SwapState::Frozen, SwapState::Cancelled) => return Ok(()),
+            (SwapState::Fulfilled, SwapState::Frozen) => {
+                SwapTransitionError::Freeze(SwapFreezeError::SwapAlreadyFulfilled)
+            }
+            (SwapState::Frozen, SwapState::Frozen) => {
+                SwapTransitionError::Freeze(SwapFreezeError::SwapAlreadyFrozen)
+            }
+            (SwapState::Voided, SwapState::Frozen) => {
+                SwapTransitionError::Freeze(SwapFreezeError::SwapAlreadyVoided)
+            }
+            (from, SwapState::Frozen) if from.is_closed() => {
+                SwapTransitionError::Freeze(SwapFreezeError::SwapAlreadyClosed)
+            }
+            (SwapState::Frozen, SwapState::Finalized) => {
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapAlreadyFrozen)
+            }
+            (SwapState::Voided, SwapState::Finalized) => {
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapAlreadyVoided)
+            }
+            (SwapState::Finalized, SwapState::Finalized) => {
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapAlreadyFinalized)
+            }
+            (from, SwapState::Finalized) if from.is_closed() => {
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapAlreadyClosed)
+            }
+            (_, SwapState::Finalized) => {
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapNotFulfilled)
+            }
+            (SwapState::Cancelled, SwapState::Cancelled) => {
+                SwapTransitionError::Cancel(SwapCancelError::SwapAlreadyCancelled)
+            }
+            (from, SwapState::Cancelled) if from.is_closed() => {
+                SwapTransitionError::Cancel(SwapCancelError::SwapAlreadyClosed)
+            }
+            (_, SwapState::Cancelled) => {
+                SwapTransitionError::Cancel(SwapCancelError::SwapNotFrozen)
+            }
+            _ => SwapTransitionError::Unsupported,
+        };
+
+        Err(reason)
+    }
+}
+
+impl fmt::Display for SwapState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            SwapState::Created => "created",
+            SwapState::Matched => "matched",
+            SwapState::Fulfilled => "fulfilled",
+            SwapState::Finalized => "finalized",
+            SwapState::Frozen => "frozen",
+            SwapState::Cancelled => "cancelled",
+            SwapState::Voided => "voided",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// `SwapTransitionError` is the reason a transition between two swap states is rejected.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SwapTransitionError {
+    /// `Freeze` is the error code the hub replies with when freezing the swap.
+    Freeze(SwapFreezeError),
+    /// `Cancel` is the error code the hub replies with when cancelling the swap.
+    Cancel(SwapCancelError),
+    /// `Finalize` is the error code the hub replies with when finalizing the swap.
+    Finalize(SwapFinalizeError),
+    /// `Unsupported` is a transition the swap lifecycle doesn't have,
+    /// and which no request to the hub can cause.
+    Unsupported,
+}
+
+impl fmt::Display for SwapTransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SwapTransitionError::Freeze(code) => code.fmt(f),
+            SwapTransitionError::Cancel(code) => code.fmt(f),
+            SwapTransitionError::Finalize(code) => code.fmt(f),
+            SwapTransitionError::Unsupported => f.write_str("unsupported transition"),
+        }
+    }
+}
+
+/// `SwapOrder` is a swap order of a wallet, tracking its state through its lifecycle.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SwapOrder {
+    pub swap: Swap,
+    pub state: SwapState,
+    pub lifetime: u64,
+}
+
+impl ToJson for SwapOrder {}
+
+impl<'a> FromJson<'a> for SwapOrder {}
+
+impl SwapOrder {
+    /// `new` creates a new `SwapOrder` from a created swap open for a number of eons.
+    pub fn new(swap: Swap, lifetime: u64) -> SwapOrder {
+        SwapOrder {
+            swap,
+            state: SwapState::Created,
+            lifetime,
+        }
+    }
+
+    /// `id` returns the id of the swap in the hub.
+    pub fn id(&self) -> Option<i64> {
+        self.swap.id
+    }
+
+    /// `last_eon` returns the last eon in which the swap is open.
+    pub fn last_eon(&self) -> Result<u64> {
+        self.swap
+            .eon_number
+            .checked_add(self.lifetime.max(1) - 1)
+            .ok_or_else(|| Error::Overflow {
+                field: "last_eon".to_owned(),
+            })
+    }
+
+    /// `hash` returns the hash of the swap.
+    pub fn hash(&self, contract: &Address) -> Hash {
+        swap_hash(
+            contract,
+            &self.swap.wallet,
+            &self.swap.recipient,
+            self.swap.amount,
+            self.swap.amount_swapped.unwrap_or_default(),
+            self.swap.nonce,
+        )
+    }
+
+    /// `check_transition` checks that the `SwapOrder` can transition to a state.
+    pub fn check_transition(&self, to: SwapState) -> Result<()> {
+        self.state
+            .check_transition(to)
+            .map_err(|reason| Error::InvalidSwapTransition {
+                from: self.state,
+                to,
+                reason,
+            })
+    }
+
+    /// `transition` transitions the `SwapOrder` to a state.
+    pub fn transition(&mut self, to: SwapState) -> Result<()> {
+        self.check_transition(to)?;
+        self.state = to;
+
+        Ok(())
+    }
+}
+
+/// `SwapTrader` sequences the swap orders of wallets on a hub.
+/// When the trader has a `Store`, the orders are saved to it on creation
+/// and after every transition.
+#[derive(Clone)]
+pub struct SwapTrader<'a> {
+    client: &'a HubClient,
+    contract: Address,
+    max_lifetime: u64,
+    store: Option<&'a dyn Store>,
+}
+
+impl<'a> SwapTrader<'a> {
+    /// `DEFAULT_MAX_LIFETIME` is the maximum number of eons a swap order can be
+    /// open for, when the trader is not configured with the limit of its hub.
+    pub const DEFAULT_MAX_LIFETIME: u64 = SwapSignatures::DEFAULT_MAX_LIFETIME;
+
+    /// `new` creates a new `SwapTrader` for the hub contract.
+    pub fn new(client: &'a HubClient, contract: Address) -> SwapTrader<'a> {
+        SwapTrader {
+            client,
+            contract,
+            max_lifetime: Self::DEFAULT_MAX_LIFETIME,
+            store: None,
+        }
+    }
+
+    /// `with_max_lifetime` sets the maximum number of eons the hub accepts
+    /// future signatures for, beyond which it replies `TOO_MANY_FUTURE_SIGNATURES`.
+    pub fn with_max_lifetime(mut self, max_lifetime: u64) -> SwapTrader<'a> {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// `with_store` sets the `Store` the swap orders are saved to.
+    pub fn with_store(mut self, store: &'a dyn Store) -> SwapTrader<'a> {
+        self.store = Some(store);
+        self
+    }
+
+    /// `save` saves a swap order to the store of the trader, if any.
+    fn save(&self, order: &SwapOrder) -> Result<()> {
+        match self.store {
+            Some(store) => store.save(order),
+            None => Ok(()),
+        }
+    }
+
+    /// `transition` transitions a swap order to a state, and saves it.
+    fn transition(&self, order: &mut SwapOrder, to: SwapState) -> Result<()> {
+        order.transition(to)?;
+        self.save(order)
+    }
+
+    /// `signatures` returns the `SwapSignatures` of an existing swap in the
+    /// current eon, on top of the current transactions of its wallets.
+    async fn signatures<'b>(
+        &'b self,
+        key: &'b PrivateKey,
+        order: &SwapOrder,
+    ) -> Result<SwapSignatures<'b>> {
+        let swap = &order.swap;
+        let eon_number = self.client.operator_status().await?.current_eon_number;
+        let debit = self.client.wallet_state(&swap.wallet).await?;
+        let credit = self.client.wallet_state(&swap.recipient).await?;
+
+        let signatures = SwapSignatures::from_swap(&self.contract, key, swap)
+            .with_lifetime(eon_number, order.lifetime)
+            .with_max_lifetime(self.max_lifetime)
+            .with_debit_state(
+                debit.registration.trail_identifier,
+                TransactionSet::from_state(&self.contract, &swap.wallet, &debit, eon_number)?,
+            )
+            .with_credit_state(
+                credit.registration.trail_identifier,
+                TransactionSet::from_state(&self.contract, &swap.recipient, &credit, eon_number)?,
+            );
+
+        Ok(signatures)
+    }
+
+    /// `create_order` creates a swap order of an amount of a token, from the
+    /// wallet of a key, for an amount of another token, open for a number of eons.
+    /// The debit wallet must hold exactly the amount and the credit wallet must be empty.
+    pub async fn create_order(
+        &self,
+        key: &PrivateKey,
+        debit_token: &Address,
+        credit_token: &Address,
+        amount: u64,
+        amount_swapped: u64,
+        lifetime: u64,
+    ) -> Result<SwapOrder> {
+        let wallet = Wallet {
+            address: key.address(),
+            token: *debit_token,
+        };
+        let recipient = Wallet {
+            address: key.address(),
+            token: *credit_token,
+        };
+
+        let eon_number = self.client.operator_status().await?.current_eon_number;
+        let debit = self.client.wallet_state(&wallet).await?;
+        let credit = self.client.wallet_state(&recipient).await?;
+
+        let nonce = next_nonce(&wallet, &debit)?;
+
+        let swap = SwapSignatures::new(
+            &self.contract,
+            key,
+            debit_token,
+            credit_token,
+            amount,
+            amount_swapped,
+            nonce,
+        )
+        .with_lifetime(eon_number, lifetime)
+        .with_max_lifetime(self.max_lifetime)
+        .with_debit_state(
+            debit.registration.trail_identifier,
+            TransactionSet::from_state(&self.contract, &wallet, &debit, eon_number)?,
+        )
+        .with_credit_state(
+            credit.registration.trail_identifier,
+            TransactionSet::from_state(&self.contract, &recipient, &credit, eon_number)?,
+        )
+        .build()?;
+
+        let swap = self.client.create_swap(&swap).await?;
+        let order = SwapOrder::new(swap, lifetime);
+        self.save(&order)?;
+
+        Ok(order)
+    }
+
+    /// `swap_id` returns the id of a swap order, which the hub assigns on creation.
+    fn swap_id(order: &SwapOrder) -> Result<i64> {
+        order.id().ok_or_else(|| Error::MissingField {
+            field: "id".to_owned(),
+        })
+    }
+
+    /// `freeze` freezes a swap order, so that it can be cancelled.
+    pub async fn freeze(&self, key: &PrivateKey, order: &mut SwapOrder) -> Result<SwapFreeze> {
+        order.check_transition(SwapState::Frozen)?;
+        let id = Self::swap_id(order)?;
+
+        let freeze = self.signatures(key, order).await?.freeze()?;
+        let freeze = self.client.freeze_swap(id, &freeze).await?;
+        self.transition(order, SwapState::Frozen)?;
+
+        Ok(freeze)
+    }
+
+    /// `cancel` cancels a frozen swap order, refunding the debit wallet.
+    pub async fn cancel(
+        &self,
+        key: &PrivateKey,
+        order: &mut SwapOrder,
+    ) -> Result<SwapCancellation> {
+        order.check_transition(SwapState::Cancelled)?;
+        let id = Self::swap_id(order)?;
+
+        let cancellation = self.signatures(key, order).await?.cancellation()?;
+        let cancellation = self.client.cancel_swap(id, &cancellation).await?;
+        self.transition(order, SwapState::Cancelled)?;
+
+        Ok(cancellation)
+    }
+
+    /// `finalize` finalizes a fulfilled swap order, crediting the credit wallet.
+    pub async fn finalize(
+        &self,
+        key: &PrivateKey,
+        order: &mut SwapOrder,
+    ) -> Result<SwapFinalization> {
+        order.check_transition(SwapState::Finalized)?;
+        let id = Self::swap_id(order)?;
+
+        let finalization = self.signatures(key, order).await?.finalization()?;
+        let finalization = self.client.finalize_swap(id, &finalization).await?;
+        self.transition(order, SwapState::Finalized)?;
+
+        Ok(finalization)
+    }
+
+    /// `refresh` updates the state of a swap order from its audit in the hub.
+    pub async fn refresh(&self, order: &mut SwapOrder) -> Result<SwapState> {
+        let id = Self::swap_id(order)?;
+        let audit = self.client.transfer(id).await?;
+
+        let state = if audit.cancelled {
+            SwapState::Cancelled
+        } else if audit.voided {
+            SwapState::Voided
+        } else if audit.complete {
+            SwapState::Fulfilled
+        } else if audit.matched_amounts.matched_out > 0 {
+            SwapState::Matched
+        } else {
+            order.state
+        };
+
+        if state != order.state && order.state.check_transition(state).is_ok() {
+            self.transition(order, state)?;
+        }
+
+        Ok(order.state)
+    }
+}
+
+impl<'a> fmt::Debug for SwapTrader<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SwapTrader")
+            .field("client", &self.client)
+            .field("contract", &self.contract)
+            .field("max_lifetime", &self.max_lifetime)
+            .field("store", &self.store.is_some())
+            .finish()
+    }
+}
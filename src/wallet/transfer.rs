@@ -0,0 +1,321 @@
+//! `transfer` defines the lifecycle of the transfers sent by a wallet.
+
+use async_std::task;
+
+use crate::crypto::hash::{format_hash, Hash};
+use crate::crypto::key::PrivateKey;
+use crate::crypto::merkle;
+use crate::crypto::protocol::{swap_hash, transfer_hash, ActiveStateUpdate};
+use crate::error::Error;
+use crate::model::common::{Address, Decimal, SenderActiveState, TransferAudit, Wallet};
+use crate::model::http::audit::WalletState;
+use crate::model::http::transfer::{Transfer, TransferReceipt};
+use crate::request::http::client::HubClient;
+use crate::result::Result;
+
+use std::time::{Duration, Instant};
+
+/// `TransferStatus` is the status in which a sent transfer resolved.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TransferStatus {
+    Complete,
+    Processed,
+    TimedOut,
+}
+
+/// `TransferOutcome` is the outcome of a sent transfer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferOutcome {
+    pub transfer: Transfer,
+    pub status: TransferStatus,
+}
+
+/// `TransactionSet` is the set of transfers of a wallet in an eon, committed to
+/// by the transaction set hash of its active state.
+///
+/// The spendings and gains are accumulated from the amounts of the eon transfers,
+/// swaps crediting their amount swapped, and the transaction set commits to their
+/// hashes in id order. The amounts spent and gained by each transfer are kept
+/// alongside its leaf.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionSet {
+    pub ids: Vec<Option<i64>>,
+    pub leaves: Vec<Hash>,
+    pub amounts: Vec<(u64, u64)>,
+    pub spendings: u64,
+    pub gains: u64,
+}
+
+impl TransactionSet {
+    /// `from_state` creates the `TransactionSet` of a wallet in an eon from its wallet state.
+    pub fn from_state(
+        contract: &Address,
+        wallet: &Wallet,
+        state: &WalletState,
+        eon_number: u64,
+    ) -> Result<TransactionSet> {
+        let mut eon_transfers: Vec<_> = state
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.eon_number as u64 == eon_number)
+            .filter(|transfer| !transfer.voided && !transfer.cancelled)
+            .collect();
+        eon_transfers.sort_by_key(|transfer| transfer.id);
+
+        let mut set = TransactionSet::default();
+
+        for transfer in eon_transfers {
+            let amounts = if transfer.wallet == *wallet {
+                (transfer.amount, 0)
+            } else if transfer.recipient == *wallet && transfer.complete {
+                (0, transfer.credited_amount())
+            } else {
+                (0, 0)
+            };
+
+            set.spendings = checked_add(set.spendings, amounts.0, "spendings")?;
+            set.gains = checked_add(set.gains, amounts.1, "gains")?;
+            set.ids.push(Some(transfer.id));
+            set.leaves.push(leaf_hash(contract, transfer));
+            set.amounts.push(amounts);
+        }
+
+        Ok(set)
+    }
+
+    /// `index_of` returns the index of a transfer in the set, appending it if missing.
+    pub fn index_of(&mut self, id: Option<i64>, leaf: Hash) -> usize {
+        if id.is_some() {
+            if let Some(index) = self.ids.iter().position(|other| *other == id) {
+                return index;
+            }
+        }
+
+        self.ids.push(id);
+        self.leaves.push(leaf);
+        self.amounts.push((0, 0));
+        self.leaves.len() - 1
+    }
+
+    /// `totals_without` returns the spendings and gains of the set
+    /// without the amounts of the transfer at an index.
+    pub fn totals_without(&self, index: usize) -> (u64, u64) {
+        let (spent, gained) = self.amounts.get(index).copied().unwrap_or_default();
+        (self.spendings - spent, self.gains - gained)
+    }
+
+    /// `active_state` builds and signs the active state of a wallet
+    /// committing to the set, proving the membership of a transfer.
+    pub fn active_state(
+        &self,
+        contract: &Address,
+        key: &PrivateKey,
+        update: &ActiveStateUpdate,
+        index: usize,
+    ) -> Result<SenderActiveState> {
+        let state = SenderActiveState {
+            operator_signature: String::new(),
+            tx_set_hash: format_hash(&update.tx_set_hash),
+            tx_set_index: index.to_string(),
+            tx_set_proof: merkle::proof(&self.leaves, index)
+                .iter()
+                .map(format_hash)
+                .collect(),
+            updated_gains: update.updated_gains.to_string(),
+            updated_spendings: update.updated_spendings.to_string(),
+            wallet_signature: update.sign(contract, key)?.value,
+        };
+
+        Ok(state)
+    }
+}
+
+/// `build_transfer` builds and signs the receipt of a transfer from the wallet of
+/// a key in an eon, on top of the transfers of the eon in the wallet state.
+pub fn build_transfer(
+    contract: &Address,
+    key: &PrivateKey,
+    state: &WalletState,
+    eon_number: u64,
+    recipient: &Wallet,
+    amount: u64,
+) -> Result<TransferReceipt> {
+    let wallet = Wallet {
+        address: key.address(),
+        token: recipient.token,
+    };
+
+    let nonce = next_nonce(&wallet, state)?;
+
+    let mut set = TransactionSet::from_state(contract, &wallet, state, eon_number)?;
+    let leaf = transfer_hash(contract, &wallet, recipient, amount, nonce);
+    let index = set.index_of(None, leaf);
+
+    let update = ActiveStateUpdate {
+        wallet: wallet.clone(),
+        trail_identifier: state.registration.trail_identifier,
+        eon_number,
+        tx_set_hash: merkle::root(&set.leaves),
+        updated_spendings: Decimal::from(checked_add(set.spendings, amount, "spendings")?),
+        updated_gains: Decimal::from(set.gains),
+    };
+
+    let receipt = TransferReceipt {
+        amount,
+        nonce,
+        recipient: recipient.clone(),
+        recipient_active_state: None,
+        sender_active_state: set.active_state(contract, key, &update, index)?,
+        wallet_signature: key.sign_message(&leaf),
+        wallet,
+    };
+
+    Ok(receipt)
+}
+
+/// `next_nonce` returns the nonce of the next transfer or swap sent by a wallet,
+/// following the nonces of the transfers in its wallet state.
+pub fn next_nonce(wallet: &Wallet, state: &WalletState) -> Result<u64> {
+    let last = state
+        .transfers
+        .iter()
+        .filter(|transfer| transfer.wallet == *wallet)
+        .map(|transfer| transfer.nonce)
+        .max();
+
+    match last {
+        Some(nonce) => checked_add(nonce, 1, "nonce"),
+        None => Ok(0),
+    }
+}
+
+/// `leaf_hash` returns the hash of a transfer in a transaction set,
+/// which is its swap hash for a swap.
+fn leaf_hash(contract: &Address, transfer: &TransferAudit) -> Hash {
+    match transfer.amount_swapped {
+        Some(amount_swapped) => swap_hash(
+            contract,
+            &transfer.wallet,
+            &transfer.recipient,
+            transfer.amount,
+            amount_swapped,
+            transfer.nonce,
+        ),
+        None => transfer_hash(
+            contract,
+            &transfer.wallet,
+            &transfer.recipient,
+            transfer.amount,
+            transfer.nonce,
+        ),
+    }
+}
+
+/// `checked_add` adds two amounts, failing with an `Error::Overflow` of a field.
+pub(crate) fn checked_add(a: u64, b: u64, field: &str) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| Error::Overflow {
+        field: field.to_owned(),
+    })
+}
+
+/// `TransferSender` sends transfers to a hub and follows them until they resolve.
+#[derive(Clone, Debug)]
+pub struct TransferSender<'a> {
+    client: &'a HubClient,
+    contract: Address,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl<'a> TransferSender<'a> {
+    /// `DEFAULT_POLL_INTERVAL` is the default interval between transfer polls.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    /// `DEFAULT_TIMEOUT` is the default time after which a transfer is considered timed out.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+    /// `new` creates a new `TransferSender` for the hub contract.
+    pub fn new(client: &'a HubClient, contract: Address) -> TransferSender<'a> {
+        TransferSender {
+            client,
+            contract,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// `with_poll_interval` sets the interval between transfer polls.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> TransferSender<'a> {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// `with_timeout` sets the time after which a transfer is considered timed out.
+    pub fn with_timeout(mut self, timeout: Duration) -> TransferSender<'a> {
+        self.timeout = timeout;
+        self
+    }
+
+    /// `send_transfer` sends an amount from the wallet of a key to a recipient
+    /// wallet of the same token, and waits until the transfer resolves.
+    pub async fn send_transfer(
+        &self,
+        from: &PrivateKey,
+        to: &Wallet,
+        amount: u64,
+    ) -> Result<TransferOutcome> {
+        let wallet = Wallet {
+            address: from.address(),
+            token: to.token,
+        };
+
+        let status = self.client.operator_status().await?;
+        let state = self.client.wallet_state(&wallet).await?;
+        let receipt = build_transfer(
+            &self.contract,
+            from,
+            &state,
+            status.current_eon_number,
+            to,
+            amount,
+        )?;
+
+        let transfer = match self.client.create_transfer(&receipt).await {
+            Ok(transfer) => transfer,
+            Err(Error::Hub { response, .. }) => {
+                return Err(Error::TransferRejected {
+                    codes: response.typed_codes(),
+                })
+            }
+            Err(err) => return Err(err),
+        };
+
+        self.wait_transfer(transfer).await
+    }
+
+    /// `wait_transfer` polls a transfer until it is complete, processed or timed out.
+    pub async fn wait_transfer(&self, transfer: Transfer) -> Result<TransferOutcome> {
+        let deadline = Instant::now() + self.timeout;
+        let mut transfer = transfer;
+
+        loop {
+            let status = if transfer.complete {
+                Some(TransferStatus::Complete)
+            } else if transfer.processed {
+                Some(TransferStatus::Processed)
+            } else if Instant::now() >= deadline {
+                Some(TransferStatus::TimedOut)
+            } else {
+                None
+            };
+
+            if let Some(status) = status {
+                return Ok(TransferOutcome { transfer, status });
+            }
+
+            task::sleep(self.poll_interval).await;
+
+            transfer = self.client.get_transfer(transfer.id).await?;
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use async_std::task;
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::error::Error;
+use libliquefy::model::common::{Address, Wallet};
+use libliquefy::model::http::transfer::TransferUpdate;
+use libliquefy::request::http::client::HubClient;
+use libliquefy::wallet::acceptance::{AcceptanceStatus, IncomingTransfer, TransferReceiver};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{
+    serve_http_responses, wallet_state, HttpServer, Response, ADDRESS, CONTRACT, CREDIT_TOKEN,
+    DEBIT_TOKEN, OPERATOR_KEY, PRIVATE_KEY,
+};
+
+const SENDER: &str = "0x0000000000000000000000000000000000000001";
+
+fn wallet(address: &str, token: &str) -> Wallet {
+    Wallet {
+        address: Address::from_str(address).unwrap(),
+        token: Address::from_str(token).unwrap(),
+    }
+}
+
+/// `incoming` returns a transfer of 40 credit tokens to the wallet of `PRIVATE_KEY`,
+/// reporting a starting balance of its recipient.
+fn incoming(starting_balance: u64) -> IncomingTransfer {
+    IncomingTransfer {
+        id: 42,
+        wallet: wallet(SENDER, CREDIT_TOKEN),
+        recipient: wallet(ADDRESS, CREDIT_TOKEN),
+        amount: 40,
+        nonce: 0,
+        eon_number: Some(3),
+        recipient_starting_balance: Some(starting_balance),
+    }
+}
+
+fn state_path() -> String {
+    let recipient = wallet(ADDRESS, CREDIT_TOKEN);
+    format!("/audit/{}/{}", recipient.token, recipient.address)
+}
+
+/// `serve` serves the state of the recipient, without transfers, and responds to
+/// the update of the transfer.
+async fn serve(update: Response) -> HttpServer {
+    serve_http_responses(vec![
+        (
+            state_path(),
+            vec![(200, wallet_state(Vec::new()).to_string())],
+        ),
+        ("/transfer/42".to_owned(), vec![update]),
+    ])
+    .await
+}
+
+fn receiver(client: &HubClient) -> TransferReceiver<'_> {
+    TransferReceiver::new(client, Address::from_str(CONTRACT).unwrap())
+}
+
+#[test]
+fn declines_transfers_refused_by_the_policy() {
+    task::block_on(async {
+        let server = serve((200, "{}".to_owned())).await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let receiver = receiver(&client).with_policy(|incoming| incoming.amount < 40);
+        let status = receiver.accept(&key, &incoming(0)).await.unwrap();
+        assert_eq!(status, AcceptanceStatus::Declined);
+        assert!(server.requests.lock().unwrap().is_empty());
+    });
+}
+
+#[test]
+fn refuses_keys_of_other_wallets() {
+    task::block_on(async {
+        let server = serve((200, "{}".to_owned())).await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(OPERATOR_KEY).unwrap();
+
+        let status = receiver(&client).accept(&key, &incoming(0)).await;
+        match status {
+            Err(Error::RecipientMismatch { recipient, signer }) => {
+                assert_eq!(recipient, Address::from_str(ADDRESS).unwrap());
+                assert_eq!(signer, key.address());
+            }
+            other => panic!("unexpected acceptance {:?}", other),
+        }
+        assert!(server.requests.lock().unwrap().is_empty());
+    });
+}
+
+#[test]
+fn reports_starting_balances_differing_from_the_wallet_state() {
+    task::block_on(async {
+        let server = serve((200, "{}".to_owned())).await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let status = receiver(&client).accept(&key, &incoming(5)).await.unwrap();
+        assert_eq!(
+            status,
+            AcceptanceStatus::BalanceMismatch {
+                expected: 0,
+                reported: 5,
+            }
+        );
+        assert_eq!(*server.requests.lock().unwrap(), vec![state_path()]);
+    });
+}
+
+#[test]
+fn reports_transfers_already_approved() {
+    task::block_on(async {
+        let body = json::json!({
+            "non_field_errors": [{ "code": "TRANSFER_ALREADY_APPROVED", "message": "" }]
+        });
+        let server = serve((400, body.to_string())).await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let status = receiver(&client).accept(&key, &incoming(0)).await.unwrap();
+        assert_eq!(status, AcceptanceStatus::AlreadyApproved);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![state_path(), "/transfer/42".to_owned()]
+        );
+
+        let bodies = server.bodies.lock().unwrap();
+        let update: TransferUpdate = json::from_str(bodies.last().unwrap()).unwrap();
+        let active_state = update.recipient_active_state;
+        assert_eq!(active_state.tx_set_index, "0");
+        assert_eq!(active_state.updated_spendings, "0");
+        assert_eq!(active_state.updated_gains, "40");
+    });
+}
+
+#[test]
+fn fails_on_wallets_spending_more_than_credited() {
+    task::block_on(async {
+        let mut spent = common::transfer();
+        spent["wallet"] = json::to_value(wallet(ADDRESS, CREDIT_TOKEN)).unwrap();
+        spent["recipient"] = json::to_value(wallet(SENDER, DEBIT_TOKEN)).unwrap();
+        spent["amount_swapped"] = json::Value::Null;
+
+        let server = serve_http_responses(vec![(
+            state_path(),
+            vec![(200, wallet_state(vec![spent]).to_string())],
+        )])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let status = receiver(&client).accept(&key, &incoming(0)).await;
+        match status {
+            Err(Error::Underflow { field }) => assert_eq!(field, "balance"),
+            other => panic!("unexpected acceptance {:?}", other),
+        }
+    });
+}
@@ -0,0 +1,145 @@
+use async_std::task;
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::model::common::Address;
+use libliquefy::model::http::admission::Admission;
+use libliquefy::request::http::admission::AdmissionCreateError;
+use libliquefy::request::http::client::HubClient;
+use libliquefy::request::http::error::ErrorClass;
+use libliquefy::wallet::admission::{sign_admission, AdmissionStatus, Admitter};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{serve_http_responses, Response, CONTRACT, CREDIT_TOKEN, DEBIT_TOKEN, PRIVATE_KEY};
+
+const OTHER_TOKENS: [&str; 2] = [
+    "0x0000000000000000000000000000000000000001",
+    "0x0000000000000000000000000000000000000002",
+];
+
+fn tokens() -> Vec<Address> {
+    [DEBIT_TOKEN, CREDIT_TOKEN, OTHER_TOKENS[0], OTHER_TOKENS[1]]
+        .iter()
+        .map(|token| Address::from_str(token).unwrap())
+        .collect()
+}
+
+/// `error` returns a response of the hub rejecting a request with an error code.
+fn error(code: &str) -> Response {
+    let body = json::json!({ "non_field_errors": [{ "code": code, "message": "" }] });
+    (400, body.to_string())
+}
+
+fn admitted(token: &Address) -> Response {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    (
+        201,
+        json::to_string(&sign_admission(&contract, &key, token)).unwrap(),
+    )
+}
+
+/// `sent` returns the tokens of the admissions of the requests received by the server.
+fn sent(bodies: &[String]) -> Vec<Vec<Address>> {
+    bodies
+        .iter()
+        .map(|body| match json::from_str::<Vec<Admission>>(body) {
+            Ok(admissions) => admissions.iter().map(|admission| admission.token).collect(),
+            Err(_) => vec![json::from_str::<Admission>(body).unwrap().token],
+        })
+        .collect()
+}
+
+#[test]
+fn halves_chunks_and_retries_rejected_chunks_once() {
+    task::block_on(async {
+        let tokens = tokens();
+        let server = serve_http_responses(vec![
+            (
+                "/admission/bulk/".to_owned(),
+                vec![
+                    error("TOO_MANY_ADMISSION_REQUESTS"),
+                    (201, "[]".to_owned()),
+                    error("TOKEN_NOT_REGISTERED"),
+                ],
+            ),
+            (
+                "/admission/".to_owned(),
+                vec![admitted(&tokens[2]), error("TOKEN_NOT_REGISTERED")],
+            ),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let admitter = Admitter::new(&client, Address::from_str(CONTRACT).unwrap());
+        let outcomes = admitter.admit(&key, &tokens).await.unwrap();
+
+        let statuses: Vec<_> = outcomes.iter().map(|outcome| &outcome.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                &AdmissionStatus::Admitted,
+                &AdmissionStatus::Admitted,
+                &AdmissionStatus::Admitted,
+                &AdmissionStatus::Rejected(vec![AdmissionCreateError::TokenNotRegistered]),
+            ]
+        );
+        assert!(outcomes
+            .iter()
+            .zip(tokens.iter())
+            .all(|(outcome, token)| outcome.wallet.token == *token
+                && outcome.wallet.address == key.address()));
+
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![
+                "/admission/bulk/",
+                "/admission/bulk/",
+                "/admission/bulk/",
+                "/admission/",
+                "/admission/",
+            ]
+        );
+        assert_eq!(
+            sent(&server.bodies.lock().unwrap()),
+            vec![
+                tokens.clone(),
+                tokens[..2].to_vec(),
+                tokens[2..].to_vec(),
+                vec![tokens[2]],
+                vec![tokens[3]],
+            ]
+        );
+    });
+}
+
+#[test]
+fn reports_wallets_already_admitted() {
+    task::block_on(async {
+        let server = serve_http_responses(vec![(
+            "/admission/".to_owned(),
+            vec![error("WALLET_ALREADY_ADMITTED")],
+        )])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+        let token = Address::from_str(DEBIT_TOKEN).unwrap();
+
+        let admitter = Admitter::new(&client, Address::from_str(CONTRACT).unwrap());
+        let outcomes = admitter.admit(&key, &[token]).await.unwrap();
+
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].status, AdmissionStatus::AlreadyAdmitted);
+        assert!(outcomes[0].status.is_admitted());
+        assert_eq!(
+            AdmissionCreateError::WalletAlreadyAdmitted.class(),
+            ErrorClass::NeedsResync
+        );
+        assert_eq!(*server.requests.lock().unwrap(), vec!["/admission/"]);
+    });
+}
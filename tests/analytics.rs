@@ -0,0 +1,160 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+
+use libliquefy::analytics::series::{DaySeries, EonSeries, Series};
+use libliquefy::analytics::view::AnalyticsView;
+use libliquefy::model::common::{Block, DateTime};
+use libliquefy::model::http::analytics::{
+    Admissions, Challenges, DaySnapshot, Deposits, EonSnapshot, OperatorStatus, Transfers,
+    Withdrawals,
+};
+use libliquefy::wallet::clock::EonClock;
+
+fn eons(points: &[(u64, u64)]) -> Vec<EonSnapshot> {
+    points
+        .iter()
+        .map(|(eon_number, count)| EonSnapshot {
+            count: *count,
+            eon_number: *eon_number,
+        })
+        .collect()
+}
+
+fn series(points: &[(u64, u64)]) -> EonSeries {
+    EonSeries::from_eons(&eons(points))
+}
+
+fn points<K: Ord + Copy>(series: &Series<K>) -> Vec<(K, f64)> {
+    series.points().collect()
+}
+
+fn day(month: u32, day: u32) -> NaiveDate {
+    let year = if month == 12 { 2019 } else { 2020 };
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// `view` returns a view of the admissions and the transfers per eon.
+fn view(admissions: &[(u64, u64)], transfers: &[(u64, u64)]) -> AnalyticsView {
+    AnalyticsView {
+        admissions: Admissions {
+            eon_number: eons(admissions),
+            total: 0,
+        },
+        challenges: Challenges {
+            eon_number: Vec::new(),
+            rebuted: 0,
+            time: Vec::new(),
+        },
+        deposits: Deposits {
+            eon_number: Vec::new(),
+            time: Vec::new(),
+            total: 0,
+        },
+        transfers: Transfers {
+            eon_number: eons(transfers),
+            time: Vec::new(),
+            total: 0,
+        },
+        withdrawals: Withdrawals {
+            eon_number: Vec::new(),
+            time: Vec::new(),
+            total: 0,
+        },
+    }
+}
+
+#[test]
+fn accumulates_and_differentiates_series() {
+    let counts = series(&[(1, 2), (2, 3), (2, 1), (4, 5), (5, 1)]);
+    assert_eq!(
+        points(&counts),
+        vec![(1, 2.0), (2, 4.0), (4, 5.0), (5, 1.0)]
+    );
+    assert_eq!(counts.total(), 12.0);
+
+    let cumulative = counts.cumulative();
+    assert_eq!(
+        points(&cumulative),
+        vec![(1, 2.0), (2, 6.0), (4, 11.0), (5, 12.0)]
+    );
+
+    assert_eq!(points(&counts.deltas()), vec![(2, 2.0), (5, -4.0)]);
+    assert!(series(&[(u64::MAX, 1)]).deltas().is_empty());
+
+    let days: DaySeries = [(day(1, 1), 1.0), (day(1, 2), 3.0), (day(1, 4), 4.0)]
+        .iter()
+        .fold(DaySeries::new(), |mut series, (day, value)| {
+            series.insert(*day, *value);
+            series
+        });
+    assert_eq!(points(&days.deltas()), vec![(day(1, 2), 2.0)]);
+}
+
+#[test]
+fn joins_series_on_their_keys() {
+    let values = series(&[(1, 6), (2, 4), (3, 9), (5, 1)]);
+    let divisors = series(&[(1, 3), (2, 0), (3, 3), (4, 2)]);
+
+    assert_eq!(points(&values.ratio(&divisors)), vec![(1, 2.0), (3, 3.0)]);
+    assert_eq!(
+        points(&values.join(&divisors, |value, other| Some(value - other))),
+        vec![(1, 3.0), (2, 4.0), (3, 6.0)]
+    );
+    assert_eq!(
+        points(&values.merge(&divisors, |value, other| value + other)),
+        vec![(1, 9.0), (2, 4.0), (3, 12.0), (4, 2.0), (5, 1.0)]
+    );
+
+    let filled = series(&[(2, 1), (4, 3)]).fill_forward(&values);
+    assert_eq!(
+        points(&filled),
+        vec![(2, 1.0), (3, 1.0), (4, 3.0), (5, 3.0)]
+    );
+}
+
+#[test]
+fn divides_transfers_by_the_wallets_admitted_up_to_each_eon() {
+    let view = view(
+        &[(1, 2), (3, 2)],
+        &[(0, 5), (1, 4), (2, 6), (3, 8), (5, 12)],
+    );
+
+    assert_eq!(
+        points(&view.transfers_per_wallet()),
+        vec![(1, 2.0), (2, 3.0), (3, 2.0), (5, 3.0)]
+    );
+}
+
+#[test]
+fn aligns_day_and_eon_series() {
+    // Eons last a day of 15 second blocks, and eon 10 starts on 2020-01-01.
+    let status = OperatorStatus {
+        blocks_per_eon: 5760,
+        confirmed: Vec::new(),
+        current_eon_number: 10,
+        latest: Block {
+            block: 100_000,
+            eon_number: 10,
+        },
+    };
+    let midnight = Utc.timestamp_opt(1_577_836_800, 0).unwrap();
+    let clock = EonClock::observed_at(&status, midnight).with_first_block(10, 100_000);
+
+    let eon_series = series(&[(9, 1), (10, 2), (11, 3)]);
+    assert_eq!(
+        points(&eon_series.to_days(&clock)),
+        vec![(day(12, 31), 1.0), (day(1, 1), 2.0), (day(1, 2), 3.0)]
+    );
+
+    let snapshots: Vec<_> = [(day(12, 1), 7), (day(12, 31), 1), (day(1, 1), 2)]
+        .iter()
+        .map(|(day, count)| DaySnapshot {
+            count: *count,
+            day: DateTime::new(Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap())),
+        })
+        .collect();
+    let day_series = DaySeries::from_days(&snapshots);
+    assert_eq!(
+        points(&day_series.to_eons(&clock)),
+        vec![(9, 1.0), (10, 2.0)]
+    );
+}
@@ -0,0 +1,188 @@
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::crypto::protocol::wallet_hash;
+use libliquefy::model::common::{Address, MerkleProof, Wallet};
+use libliquefy::model::http::audit::{Registration, WalletState};
+use libliquefy::wallet::audit::{Auditor, Discrepancy, Signer, Subject};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{OPERATOR_KEY, PRIVATE_KEY};
+
+struct Fixture {
+    auditor: Auditor,
+    wallet: Wallet,
+    state: WalletState,
+}
+
+fn fixture() -> Fixture {
+    let fixture = common::fixture();
+    let contract = Address::from_str(fixture["contract"].as_str().unwrap()).unwrap();
+    let operator = PrivateKey::from_str(OPERATOR_KEY).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let wallet = Wallet {
+        address: Address::from_str(fixture["passive"]["wallet"].as_str().unwrap()).unwrap(),
+        token: Address::from_str(fixture["token"].as_str().unwrap()).unwrap(),
+    };
+    assert_eq!(wallet.address, key.address());
+
+    let proof: MerkleProof =
+        json::from_value(fixture["passive"]["delivery_proof"]["merkle_proof"].clone()).unwrap();
+    let hash = wallet_hash(&contract, &wallet);
+
+    let state = WalletState {
+        deposits: Vec::new(),
+        merkle_proofs: vec![proof],
+        registration: Registration {
+            eon_number: 0,
+            operator_signature: operator.sign_message(&hash).value,
+            trail_identifier: 0,
+            wallet_signature: key.sign_message(&hash).value,
+        },
+        transfers: Vec::new(),
+        withdrawal_request: Vec::new(),
+        withdrawals: Vec::new(),
+    };
+
+    Fixture {
+        auditor: Auditor::new(contract, operator.address()),
+        wallet,
+        state,
+    }
+}
+
+#[test]
+fn checks_the_signatures_of_merkle_proofs() {
+    let f = fixture();
+
+    let report = f.auditor.audit_wallet(&f.wallet, &f.state);
+    assert!(report.is_clean(), "{:?}", report.discrepancies);
+    assert_eq!(report.signatures, 4);
+
+    let mut state = f.state.clone();
+    state.merkle_proofs[0].active_state.updated_gains = "5".to_owned();
+
+    let report = f.auditor.audit_wallet(&f.wallet, &state);
+    assert_eq!(report.discrepancies.len(), 2);
+    assert!(report
+        .discrepancies
+        .iter()
+        .all(|discrepancy| matches!(discrepancy, Discrepancy::InvalidSignature { .. })));
+}
+
+#[test]
+fn reports_missing_signatures() {
+    let f = fixture();
+
+    let mut state = f.state.clone();
+    state.registration.operator_signature.clear();
+    state.merkle_proofs[0].active_state.wallet_signature.clear();
+
+    let report = f.auditor.audit_wallet(&f.wallet, &state);
+    let missing: Vec<_> = report
+        .discrepancies
+        .iter()
+        .map(|discrepancy| match discrepancy {
+            Discrepancy::MissingSignature {
+                eon_number, signer, ..
+            } => (*eon_number, *signer),
+            _ => panic!("unexpected discrepancy {}", discrepancy),
+        })
+        .collect();
+
+    assert_eq!(missing, vec![(0, Signer::Operator), (3, Signer::Wallet)]);
+}
+
+#[test]
+fn reports_malformed_allotments() {
+    let f = fixture();
+
+    let mut state = f.state.clone();
+    let mut next = state.merkle_proofs[0].clone();
+    next.eon_number += 1;
+    next.left = 200;
+    next.right = 150;
+    next.active_state.tx_set_hash.clear();
+    state.merkle_proofs.push(next);
+
+    let report = f.auditor.audit_wallet(&f.wallet, &state);
+    assert_eq!(report.proofs, 1);
+
+    match &report.discrepancies[..] {
+        [discrepancy @ Discrepancy::Malformed {
+            subject: Subject::MerkleProof,
+            transfer_id: None,
+            eon_number: 4,
+            ..
+        }] => assert_eq!(
+            discrepancy.to_string(),
+            "eon 4: malformed merkle proof: allotment right below left"
+        ),
+        discrepancies => panic!("unexpected discrepancies {:?}", discrepancies),
+    }
+
+    let mut state = f.state.clone();
+    state.merkle_proofs[0].right = u64::MAX;
+    state.merkle_proofs[0].left = 0;
+    let mut next = state.merkle_proofs[0].clone();
+    next.eon_number += 1;
+    next.active_state.tx_set_hash.clear();
+    state.merkle_proofs.push(next);
+    state.deposits = json::from_value(json::json!([{
+        "amount": 1,
+        "block": 10,
+        "eon_number": 3,
+        "time": "2020-01-01T00:00:00Z",
+        "txid": "0x00",
+    }]))
+    .unwrap();
+
+    let report = f.auditor.audit_wallet(&f.wallet, &state);
+    assert!(report.discrepancies.iter().any(|discrepancy| matches!(
+        discrepancy,
+        Discrepancy::Malformed {
+            subject: Subject::Flows,
+            eon_number: 4,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn reports_wallets_debited_beyond_their_allotment() {
+    let f = fixture();
+
+    let mut state = f.state.clone();
+    state.merkle_proofs[0].left = 0;
+    state.merkle_proofs[0].right = 50;
+    let mut next = state.merkle_proofs[0].clone();
+    next.eon_number += 1;
+    next.right = 0;
+    next.active_state.tx_set_hash.clear();
+    state.merkle_proofs.push(next);
+    state.withdrawal_request = json::from_value(json::json!([{
+        "amount": 80,
+        "block": 10,
+        "eon_number": 3,
+        "slashed": false,
+        "time": "2020-01-01T00:00:00Z",
+        "txid": "0x00",
+    }]))
+    .unwrap();
+
+    let report = f.auditor.audit_wallet(&f.wallet, &state);
+    let overspent = Discrepancy::Overspent {
+        eon_number: 4,
+        credits: 50,
+        debits: 80,
+    };
+    assert!(report.discrepancies.contains(&overspent));
+    assert_eq!(
+        overspent.to_string(),
+        "eon 4: debits of 80 exceed credits of 50"
+    );
+}
@@ -0,0 +1,300 @@
+use async_std::task;
+use chrono::{TimeZone, Utc};
+use serde_json as json;
+
+use libliquefy::market::candles::{Candle, CandleSeries, ExportFormat, Interval};
+use libliquefy::model::common::{Address, Block};
+use libliquefy::model::http::analytics::OperatorStatus;
+use libliquefy::model::http::audit::TokenMatching;
+use libliquefy::model::ws::ws::{Event, NotificationData, TokenPairEvent, WalletEvent};
+use libliquefy::request::http::client::HubClient;
+use libliquefy::wallet::clock::EonClock;
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{serve_http, transfer, CREDIT_TOKEN, DEBIT_TOKEN};
+
+const TIME: u64 = 1_599_999_960;
+
+fn trade(time: u64, price: u64, volume: u64) -> TokenMatching {
+    TokenMatching {
+        price,
+        time,
+        volume,
+    }
+}
+
+fn series(interval: Interval) -> CandleSeries {
+    CandleSeries::new(
+        Address::from_str(DEBIT_TOKEN).unwrap(),
+        Address::from_str(CREDIT_TOKEN).unwrap(),
+        interval,
+    )
+}
+
+/// `ohlcv` returns the start, open, high, low, close, volume and trades of the candles.
+fn ohlcv(series: &CandleSeries) -> Vec<(u64, u64, u64, u64, u64, u64, u64)> {
+    series
+        .candles()
+        .map(|c| (c.start, c.open, c.high, c.low, c.close, c.volume, c.trades))
+        .collect()
+}
+
+/// `matchings_path` returns the path of a page of the matchings of the pair.
+fn matchings_path(offset: usize) -> String {
+    format!(
+        "/audit/swaps/{}/{}/matchings?offset={}&limit={}",
+        Address::from_str(DEBIT_TOKEN).unwrap(),
+        Address::from_str(CREDIT_TOKEN).unwrap(),
+        offset,
+        CandleSeries::PAGE_SIZE
+    )
+}
+
+fn page(trades: &[TokenMatching]) -> String {
+    json::to_string(trades).unwrap()
+}
+
+#[test]
+fn buckets_trades_by_interval() {
+    let history = vec![
+        trade(TIME + 59, 3, 10),
+        trade(TIME, 4, 20),
+        trade(TIME + 30, 6, 5),
+        trade(TIME + 60, 2, 1),
+        trade(TIME + 3600, 7, 2),
+    ];
+
+    let mut minutes = series(Interval::Minute);
+    minutes.load(&history);
+
+    let start = TIME - TIME % 60;
+    assert_eq!(
+        ohlcv(&minutes),
+        vec![
+            (start, 4, 6, 3, 3, 35, 3),
+            (start + 60, 2, 2, 2, 2, 1, 1),
+            (start + 3600, 7, 7, 7, 7, 2, 1),
+        ]
+    );
+    let first = minutes.candles().next().unwrap();
+    assert_eq!((first.end, first.eon_number), (start + 60, None));
+
+    let mut hours = series(Interval::Hour);
+    hours.load(&history);
+
+    let start = TIME - TIME % 3600;
+    assert_eq!(
+        ohlcv(&hours),
+        vec![(start, 4, 6, 2, 2, 36, 4), (start + 3600, 7, 7, 7, 7, 2, 1)]
+    );
+
+    let mut custom = series(Interval::Seconds(0));
+    custom.load(&history[..2]);
+    assert_eq!(custom.candles().count(), 2);
+}
+
+#[test]
+fn closes_candles_with_their_latest_trade() {
+    let mut minutes = series(Interval::Minute);
+    for trade in &[
+        trade(TIME + 30, 6, 1),
+        trade(TIME + 10, 3, 1),
+        trade(TIME + 59, 5, 1),
+        trade(TIME, 4, 1),
+        trade(TIME + 20, 7, 1),
+    ] {
+        assert!(minutes.apply_matching(trade));
+    }
+
+    let start = TIME - TIME % 60;
+    assert_eq!(ohlcv(&minutes), vec![(start, 4, 7, 3, 5, 5, 5)]);
+
+    let candle = minutes.candles().next().unwrap();
+    assert_eq!((candle.open_time, candle.close_time), (TIME, TIME + 59));
+}
+
+#[test]
+fn saturates_the_volume_of_candles() {
+    let mut minutes = series(Interval::Minute);
+    minutes.load(&[trade(TIME, 4, u64::MAX), trade(TIME, 5, 1)]);
+    assert_eq!(minutes.candles().next().unwrap().volume, u64::MAX);
+
+    assert!(!minutes.apply_matching(&trade(u64::MAX, 4, 1)));
+    assert_eq!(minutes.candles().count(), 1);
+}
+
+#[test]
+fn buckets_trades_by_eon() {
+    let status = OperatorStatus {
+        blocks_per_eon: 100,
+        confirmed: Vec::new(),
+        current_eon_number: 10,
+        latest: Block {
+            block: 1050,
+            eon_number: 10,
+        },
+    };
+    let observed = Utc.timestamp_opt(TIME as i64, 0).unwrap();
+    let clock = EonClock::observed_at(&status, observed).with_first_block(10, 1000);
+
+    let mut without_clock = series(Interval::Eon);
+    assert!(!without_clock.apply_matching(&trade(TIME, 1, 1)));
+    assert_eq!(without_clock.candles().count(), 0);
+
+    let mut eons = series(Interval::Eon).with_clock(clock);
+    eons.load(&[
+        trade(TIME - 750, 5, 1),
+        trade(TIME + 749, 3, 1),
+        trade(TIME + 750, 4, 1),
+    ]);
+
+    let candles: Vec<_> = eons
+        .candles()
+        .map(|c| (c.eon_number, c.start, c.end, c.trades))
+        .collect();
+    assert_eq!(
+        candles,
+        vec![
+            (Some(10), TIME - 750, TIME + 750, 2),
+            (Some(11), TIME + 750, TIME + 2250, 1),
+        ]
+    );
+}
+
+#[test]
+fn exports_candles_as_csv_and_json() {
+    let mut minutes = series(Interval::Minute);
+    minutes.load(&[trade(TIME, 4, 20), trade(TIME + 60, 2, 1)]);
+
+    let start = TIME - TIME % 60;
+    let csv = minutes.export(ExportFormat::CSV).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            Candle::CSV_HEADER.to_owned(),
+            format!("{},{},,4,4,4,4,20,1", start, start + 60),
+            format!("{},{},,2,2,2,2,1,1", start + 60, start + 120),
+        ]
+    );
+
+    let exported = minutes.export(ExportFormat::JSON).unwrap();
+    let candles: Vec<Candle> = json::from_str(&exported).unwrap();
+    assert_eq!(candles, minutes.candles().cloned().collect::<Vec<_>>());
+
+    assert_eq!(ExportFormat::from_str("csv").unwrap(), ExportFormat::CSV);
+    assert_eq!(ExportFormat::from_str("json").unwrap(), ExportFormat::JSON);
+    assert!(ExportFormat::from_str("xml").is_err());
+}
+
+#[test]
+fn backfills_from_the_last_matching_read() {
+    task::block_on(async {
+        let first: Vec<_> = (0..CandleSeries::PAGE_SIZE as u64)
+            .map(|i| trade(TIME + i, 4, 1))
+            .collect();
+        let last = TIME + CandleSeries::PAGE_SIZE as u64;
+        let second = vec![trade(last, 5, 2)];
+
+        let server = serve_http(vec![
+            (matchings_path(0), page(&first)),
+            (matchings_path(CandleSeries::PAGE_SIZE), page(&second)),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let mut series = series(Interval::Hour);
+        let recorded = series.backfill(&client).await.unwrap();
+        assert_eq!(recorded, CandleSeries::PAGE_SIZE + 1);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![matchings_path(0), matchings_path(CandleSeries::PAGE_SIZE)]
+        );
+
+        // The hub recorded an identical trade, and a different one, at the time of the
+        // last trade of the series, then a later trade.
+        let offset = CandleSeries::PAGE_SIZE + 1;
+        let third = vec![trade(last, 5, 2), trade(last, 6, 2), trade(last + 1, 5, 2)];
+        let server = serve_http(vec![
+            (matchings_path(offset), page(&third)),
+            (matchings_path(offset + 3), page(&[])),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        assert_eq!(series.backfill(&client).await.unwrap(), 3);
+        assert_eq!(series.backfill(&client).await.unwrap(), 0);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![matchings_path(offset), matchings_path(offset + 3)]
+        );
+
+        let trades: u64 = series.candles().map(|candle| candle.trades).sum();
+        assert_eq!(trades, CandleSeries::PAGE_SIZE as u64 + 4);
+    });
+}
+
+#[test]
+fn stops_backfilling_when_the_hub_ignores_paging() {
+    task::block_on(async {
+        let full: Vec<_> = (0..CandleSeries::PAGE_SIZE as u64)
+            .map(|i| trade(TIME + i, 4, 1))
+            .collect();
+
+        let server = serve_http(vec![
+            (matchings_path(0), page(&full)),
+            (matchings_path(CandleSeries::PAGE_SIZE), page(&full)),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let mut series = series(Interval::Hour);
+        assert_eq!(
+            series.backfill(&client).await.unwrap(),
+            CandleSeries::PAGE_SIZE
+        );
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+    });
+}
+
+#[test]
+fn syncs_on_matched_swaps_of_the_pair() {
+    task::block_on(async {
+        let server = serve_http(vec![
+            (matchings_path(0), page(&[trade(TIME, 4, 1)])),
+            (matchings_path(1), page(&[])),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+        let mut series = series(Interval::Minute);
+
+        let swap = json::from_value(transfer()).unwrap();
+        let other = NotificationData::new(Event::Wallet(WalletEvent::FinalizedSwap(swap)));
+        assert_eq!(series.sync(&client, &other).await.unwrap(), 0);
+        assert!(server.requests.lock().unwrap().is_empty());
+
+        let mut reversed = transfer();
+        reversed["wallet"]["token"] = json::json!(CREDIT_TOKEN);
+        reversed["recipient"]["token"] = json::json!(DEBIT_TOKEN);
+        let matched = NotificationData::new(Event::TokenPair(TokenPairEvent::MatchedSwap(
+            json::from_value(reversed).unwrap(),
+        )));
+        assert_eq!(series.sync(&client, &matched).await.unwrap(), 1);
+        assert_eq!(series.sync(&client, &matched).await.unwrap(), 0);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![matchings_path(0), matchings_path(1)]
+        );
+
+        let mut unrelated = transfer();
+        unrelated["recipient"]["token"] = json::json!(common::ADDRESS);
+        let unrelated = NotificationData::new(Event::TokenPair(TokenPairEvent::MatchedSwap(
+            json::from_value(unrelated).unwrap(),
+        )));
+        assert_eq!(series.sync(&client, &unrelated).await.unwrap(), 0);
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+    });
+}
@@ -0,0 +1,76 @@
+use chrono::{TimeZone, Utc};
+
+use libliquefy::model::common::Block;
+use libliquefy::model::http::analytics::OperatorStatus;
+use libliquefy::wallet::clock::EonClock;
+
+use std::time::Duration;
+
+fn status(block: u64, eon_number: u64, current_eon_number: u64) -> OperatorStatus {
+    OperatorStatus {
+        blocks_per_eon: 100,
+        confirmed: Vec::new(),
+        current_eon_number,
+        latest: Block { block, eon_number },
+    }
+}
+
+#[test]
+fn reports_the_current_eon_of_the_operator() {
+    let time = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    let mut clock = EonClock::observed_at(&status(1050, 10, 10), time);
+    assert_eq!(clock.current_eon(), 10);
+
+    clock.observe_at(&status(1120, 11, 11), time + chrono::Duration::seconds(60));
+    assert_eq!(clock.current_eon(), 11);
+    assert_eq!(clock.eon_of(1120), Some(11));
+
+    clock.observe_at(&status(1110, 10, 10), time + chrono::Duration::seconds(90));
+    assert_eq!(clock.current_eon(), 11);
+}
+
+#[test]
+fn estimates_block_times_over_long_spans() {
+    let time = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    let blocks = 10_000_000_000;
+
+    let mut clock = EonClock::observed_at(&status(1000, 10, 10), time);
+    clock.observe_at(
+        &status(1000 + blocks, 10, 10),
+        time + chrono::Duration::seconds(blocks as i64 / 2),
+    );
+    assert_eq!(clock.block_time(), Duration::from_millis(500));
+
+    let latest = time + chrono::Duration::seconds(blocks as i64 / 2);
+    assert_eq!(
+        clock.time_of(1000 + blocks + 4_000_000_000),
+        latest + chrono::Duration::seconds(2_000_000_000)
+    );
+    assert_eq!(
+        clock.time_of(0),
+        latest - chrono::Duration::milliseconds((1000 + blocks as i64) * 500)
+    );
+
+    let max = chrono::Duration::milliseconds(EonClock::MAX_ESTIMATE_MILLIS);
+    assert_eq!(clock.time_of(u64::MAX), latest + max);
+}
+
+#[test]
+fn saturates_the_blocks_of_distant_eons() {
+    let time = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    let clock = EonClock::observed_at(&status(1050, 10, 10), time);
+    assert_eq!(clock.first_block(10), 1050);
+    assert_eq!(clock.last_block(10), 1149);
+
+    assert_eq!(clock.first_block(u64::MAX), u64::MAX);
+    assert_eq!(clock.last_block(u64::MAX), u64::MAX - 1);
+    assert_eq!(clock.last_block(u64::MAX / 100), u64::MAX - 1);
+
+    let max = chrono::Duration::milliseconds(EonClock::MAX_ESTIMATE_MILLIS);
+    assert_eq!(clock.eon_end_time(u64::MAX), time + max);
+
+    let clock = EonClock::observed_at(&status(u64::MAX, 0, 0), time).with_first_block(u64::MAX, 0);
+    assert_eq!(clock.first_block(0), 0);
+    assert_eq!(clock.first_block(u64::MAX), 0);
+    assert_eq!(clock.eon_of(1000), None);
+}
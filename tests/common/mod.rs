@@ -0,0 +1,281 @@
+//! `common` defines the helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use async_std::io::{ReadExt, WriteExt};
+use async_std::net::{TcpListener, TcpStream};
+use async_std::task;
+use serde_json as json;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// `PRIVATE_KEY` is the private key of `ADDRESS`.
+pub const PRIVATE_KEY: &str = "0xc87509a1c067bbde78beb793e6fa76530b6382a4c0241e5e4a9ec0a0f44dc0d3";
+
+/// `OPERATOR_KEY` is the private key of the operator signing the active states of the fixture.
+pub const OPERATOR_KEY: &str = "0xae6ae8e5ccbfb04590405997ee2d52d2b330726137b875053c36d94e974d162f";
+
+pub const ADDRESS: &str = "0x627306090abab3a6e1400e9345bc60c78a8bef57";
+
+/// `CONTRACT` is the hub contract of the fixture.
+pub const CONTRACT: &str = "0x7e8d4c1b3f2d1d3f6e5c0c3b1a2d4e5f60718293";
+
+pub const DEBIT_TOKEN: &str = "0xa9d1e08c7793af67e9d92fe308d5697fb81d3e43";
+
+pub const CREDIT_TOKEN: &str = "0xf17f52151ebef6c7334fad080c5704d77216b732";
+
+/// `FIXTURE` is a checkpoint of two wallets of `CONTRACT`, with the delivery proof of a
+/// passive transfer to the first wallet and of an active transfer of the second wallet.
+/// Its active states are signed by the wallet and operator keys.
+const FIXTURE: &str = include_str!("../fixtures/delivery_proofs.json");
+
+/// `fixture` returns the checkpoint fixture.
+pub fn fixture() -> json::Value {
+    json::from_str(FIXTURE).unwrap()
+}
+
+const REFERENCE_VECTORS: &str = include_str!("../fixtures/reference_vectors.json");
+
+/// `reference_vectors` returns the checkpoint vectors computed independently of the crate.
+pub fn reference_vectors() -> json::Value {
+    json::from_str(REFERENCE_VECTORS).unwrap()
+}
+
+/// `active_state` returns an unsigned active state.
+pub fn active_state() -> json::Value {
+    json::json!({
+        "operator_signature": "",
+        "tx_set_hash": "",
+        "tx_set_index": "",
+        "tx_set_proof": [],
+        "updated_gains": "0",
+        "updated_spendings": "0",
+        "wallet_signature": "",
+    })
+}
+
+/// `deposit` returns a confirmed deposit of 1000 debit tokens.
+pub fn deposit() -> json::Value {
+    json::json!({
+        "address": ADDRESS,
+        "token": DEBIT_TOKEN,
+        "amount": 1000,
+        "block": 120,
+        "eon_number": 3,
+        "time": "2020-01-06T12:00:00Z",
+        "txid": "0x4e0f7f0c1a2b9d3e5f6a7b8c9d0e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a",
+    })
+}
+
+/// `transfer` returns a swap of 1000 debit tokens for 250 credit tokens, partially matched.
+pub fn transfer() -> json::Value {
+    let fixture = fixture();
+
+    json::json!({
+        "amount": 1000,
+        "amount_swapped": 250,
+        "appended": true,
+        "cancelled": false,
+        "complete": false,
+        "delivery_proof": fixture["passive"]["delivery_proof"],
+        "eon_number": 3,
+        "id": 42,
+        "matched_amounts": { "in": 0, "matched_in": 100, "matched_out": 25, "out": 0 },
+        "nonce": 7,
+        "passive": false,
+        "position": null,
+        "processed": true,
+        "recipient": { "address": ADDRESS, "token": CREDIT_TOKEN },
+        "recipient_active_state": active_state(),
+        "recipient_cancellation_active_state": active_state(),
+        "recipient_finalization_active_state": active_state(),
+        "recipient_fulfillment_active_state": active_state(),
+        "recipient_starting_balance": null,
+        "recipient_trail_identifier": 1,
+        "sender_active_state": active_state(),
+        "sender_cancellation_active_state": active_state(),
+        "sender_finalization_active_state": active_state(),
+        "sender_starting_balance": 2000,
+        "swap_freezing_signature": "",
+        "time": "2020-01-06T12:00:00Z",
+        "timestamp": 1578312000,
+        "tx_id": null,
+        "voided": false,
+        "wallet": { "address": ADDRESS, "token": DEBIT_TOKEN },
+        "wallet_trail_identifier": 0,
+    })
+}
+
+/// `wallet_state` returns the state of a wallet registered in eon 0, with a set of transfers.
+pub fn wallet_state(transfers: Vec<json::Value>) -> json::Value {
+    json::json!({
+        "deposits": [],
+        "merkle_proofs": [],
+        "registration": {
+            "eon_number": 0,
+            "operator_signature": "",
+            "trail_identifier": 0,
+            "wallet_signature": "",
+        },
+        "transfers": transfers,
+        "withdrawal_request": [],
+        "withdrawals": [],
+    })
+}
+
+/// `Response` is a response of an `HttpServer`, with its status code and JSON body.
+pub type Response = (u16, String);
+
+/// `HttpServer` is a local HTTP server answering requests with fixed JSON bodies.
+/// It records the path, including the query, and the body of every request.
+pub struct HttpServer {
+    pub host: String,
+    pub requests: Arc<Mutex<Vec<String>>>,
+    pub bodies: Arc<Mutex<Vec<String>>>,
+}
+
+/// `serve_http` starts an `HttpServer` answering the requests of a set of paths,
+/// including their query, and 404 to any other request.
+pub async fn serve_http(routes: Vec<(String, String)>) -> HttpServer {
+    let routes = routes
+        .into_iter()
+        .map(|(path, body)| (path, vec![(200, body)]))
+        .collect();
+
+    serve_http_responses(routes).await
+}
+
+/// `serve_http_responses` starts an `HttpServer` answering the successive requests of
+/// a set of paths with successive responses, repeating the last one, and 404 to any
+/// other request. `{host}` in a response body is replaced by the host of the server.
+pub async fn serve_http_responses(routes: Vec<(String, Vec<Response>)>) -> HttpServer {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+    let mut routes: HashMap<String, VecDeque<Response>> = routes
+        .into_iter()
+        .map(|(path, responses)| (path, responses.into_iter().collect()))
+        .collect();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let (log, body_log) = (requests.clone(), bodies.clone());
+    let server_host = host.clone();
+
+    task::spawn(async move {
+        loop {
+            let (mut stream, _) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(_) => return,
+            };
+
+            let (path, body) = read_request(&mut stream).await;
+            log.lock().unwrap().push(path.clone());
+            body_log.lock().unwrap().push(body);
+
+            let (status, body) = match routes.get_mut(&path) {
+                Some(responses) if responses.len() > 1 => responses.pop_front().unwrap(),
+                Some(responses) if !responses.is_empty() => responses[0].clone(),
+                _ => (404, "{}".to_owned()),
+            };
+            let body = body.replace("{host}", &server_host);
+            let reason = if status < 400 { "OK" } else { "Error" };
+
+            let res = format!(
+                "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                reason,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(res.as_bytes()).await;
+        }
+    });
+
+    HttpServer {
+        host,
+        requests,
+        bodies,
+    }
+}
+
+/// `read_request` reads a request, and returns its path and its body.
+async fn read_request(stream: &mut TcpStream) -> (String, String) {
+    let mut req = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    let header_end = loop {
+        if let Some(end) = req.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break req.len(),
+            Ok(n) => req.extend_from_slice(&buf[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&req[..header_end]).to_ascii_lowercase();
+    let path = String::from_utf8_lossy(&req[..header_end])
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("/")
+        .to_owned();
+    let length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|length| length.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if head.contains("expect: 100-continue") {
+        let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").await;
+    }
+
+    let chunked = head.contains("transfer-encoding: chunked");
+
+    loop {
+        let received = &req[header_end..];
+        let complete = if chunked {
+            received.ends_with(b"0\r\n\r\n")
+        } else {
+            received.len() >= length
+        };
+
+        if complete {
+            break;
+        }
+
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => req.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let body = if chunked {
+        dechunk(&req[header_end..])
+    } else {
+        req[header_end..].to_vec()
+    };
+    let body = String::from_utf8_lossy(&body).into_owned();
+
+    (path, body)
+}
+
+/// `dechunk` decodes a body sent with the chunked transfer encoding.
+fn dechunk(mut chunks: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+
+    while let Some(end) = chunks.windows(2).position(|window| window == b"\r\n") {
+        let size = String::from_utf8_lossy(&chunks[..end]);
+        let size = usize::from_str_radix(size.trim(), 16).unwrap_or(0);
+        let start = end + 2;
+
+        if size == 0 || chunks.len() < start + size {
+            break;
+        }
+
+        body.extend_from_slice(&chunks[start..start + size]);
+        chunks = &chunks[(start + size + 2).min(chunks.len())..];
+    }
+
+    body
+}
@@ -0,0 +1,131 @@
+use libliquefy::crypto::hash::{format_hash, keccak256, parse_hash, PackedEncoder};
+use libliquefy::crypto::key::{
+    message_hash, recover, recover_message, verify, verify_message, PrivateKey, MESSAGE_PREFIX,
+};
+use libliquefy::model::common::{Address, Decimal};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{ADDRESS, PRIVATE_KEY};
+
+/// `EIP55_ADDRESSES` are the test addresses of the EIP-55 specification.
+const EIP55_ADDRESSES: [&str; 8] = [
+    "0x52908400098527886E0F7030069857D2E4169EE7",
+    "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+    "0xde709f2102306220921060314715629080e2fb77",
+    "0x27b1fdb04752bbc536007a920d24acb045561c26",
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn keccak256_known_answers() {
+    let cases = [
+        (
+            &b""[..],
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        ),
+        (
+            &b"abc"[..],
+            "0x4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        ),
+        (
+            &b"hello world"[..],
+            "0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad",
+        ),
+        (
+            &[0u8; 32][..],
+            "0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563",
+        ),
+    ];
+
+    for (data, hash) in cases.iter() {
+        assert_eq!(format_hash(&keccak256(data)), *hash);
+        assert_eq!(parse_hash(hash).unwrap(), keccak256(data));
+    }
+}
+
+#[test]
+fn eip55_checksums() {
+    for checksummed in EIP55_ADDRESSES.iter() {
+        let lower = checksummed.to_lowercase();
+        let address = Address::from_str(&lower).unwrap();
+
+        assert_eq!(address.to_checksum(), *checksummed);
+        assert_eq!(address.to_string(), *checksummed);
+        assert_eq!(Address::from_str(checksummed).unwrap(), address);
+    }
+
+    let invalid = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+    assert!(Address::from_str(invalid).is_err());
+}
+
+#[test]
+fn packed_encoder_output() {
+    let address = Address::from_str(ADDRESS).unwrap();
+    let hash = keccak256(b"abc");
+
+    let mut encoder = PackedEncoder::new();
+    encoder
+        .address(&address)
+        .number(1)
+        .boolean(true)
+        .bytes32(&hash)
+        .bytes(&[0xab, 0xcd]);
+    encoder.decimal(&Decimal::from(258)).unwrap();
+
+    let mut expected = address.as_bytes().to_vec();
+    expected.extend_from_slice(&[0u8; 31]);
+    expected.push(1);
+    expected.push(1);
+    expected.extend_from_slice(&hash);
+    expected.extend_from_slice(&[0xab, 0xcd]);
+    expected.extend_from_slice(&[0u8; 30]);
+    expected.extend_from_slice(&[1, 2]);
+
+    assert_eq!(encoder.as_bytes(), &expected[..]);
+    assert_eq!(encoder.hash(), keccak256(&expected));
+
+    // keccak256(abi.encodePacked(uint256(1)))
+    assert_eq!(
+        format_hash(&PackedEncoder::new().number(1).hash()),
+        "0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"
+    );
+
+    let too_large = Decimal::from_str(&format!("1{}", "0".repeat(78))).unwrap();
+    assert!(PackedEncoder::new().decimal(&too_large).is_err());
+
+    let negative = Decimal::from_str("-1").unwrap();
+    assert!(PackedEncoder::new().decimal(&negative).is_err());
+}
+
+#[test]
+fn sign_recover_verify_round_trip() {
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+    let address = Address::from_str(ADDRESS).unwrap();
+    assert_eq!(key.address(), address);
+
+    let hash = keccak256(b"liquidity");
+
+    let signature = key.sign(&hash);
+    assert_eq!(recover(&hash, &signature).unwrap(), address);
+    assert!(verify(&hash, &signature, &address));
+    assert!(!verify_message(&hash, &signature, &address));
+
+    let mut prefixed = MESSAGE_PREFIX.to_vec();
+    prefixed.extend_from_slice(&hash);
+    assert_eq!(message_hash(&hash), keccak256(&prefixed));
+
+    let signature = key.sign_message(&hash);
+    assert_eq!(recover_message(&hash, &signature).unwrap(), address);
+    assert_eq!(recover(&message_hash(&hash), &signature).unwrap(), address);
+    assert!(verify_message(&hash, &signature, &address));
+    assert!(!verify(&hash, &signature, &address));
+
+    let other = PrivateKey::random();
+    assert!(!verify_message(&hash, &signature, &other.address()));
+}
@@ -0,0 +1,109 @@
+use serde_json as json;
+
+use libliquefy::crypto::proof::ProofFailure;
+use libliquefy::error::Error;
+use libliquefy::model::common::Address;
+use libliquefy::model::http::audit::WalletState;
+use libliquefy::wallet::ledger::{EonBalance, Ledger};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{ADDRESS, DEBIT_TOKEN};
+
+fn wallet_state(allotments: &[(u64, u64, u64)]) -> WalletState {
+    let fixture = common::fixture();
+    let proof = &fixture["passive"]["delivery_proof"]["merkle_proof"];
+
+    let merkle_proofs = allotments
+        .iter()
+        .map(|(eon_number, left, right)| {
+            let mut proof = proof.clone();
+            proof["eon_number"] = json::json!(eon_number);
+            proof["left"] = json::json!(left);
+            proof["right"] = json::json!(right);
+            proof
+        })
+        .collect::<Vec<_>>();
+
+    json::from_value(json::json!({
+        "deposits": [{
+            "amount": 40,
+            "block": 10,
+            "eon_number": 3,
+            "time": "2020-01-01T00:00:00Z",
+            "txid": "0x01",
+        }],
+        "merkle_proofs": merkle_proofs,
+        "registration": {
+            "eon_number": 0,
+            "operator_signature": "",
+            "trail_identifier": 0,
+            "wallet_signature": "",
+        },
+        "transfers": [],
+        "withdrawal_request": [],
+        "withdrawals": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn carries_the_balance_over_to_the_current_eon() {
+    let token = Address::from_str(DEBIT_TOKEN).unwrap();
+    let mut ledger = Ledger::new(Address::from_str(ADDRESS).unwrap());
+
+    ledger
+        .apply_wallet_state(&token, &wallet_state(&[(3, 0, 100)]))
+        .unwrap();
+
+    assert_eq!(ledger.last_eon(&token), Some(3));
+    assert_eq!(ledger.spendable(&token, 3).unwrap(), 140);
+    assert_eq!(ledger.spendable(&token, 5).unwrap(), 140);
+    assert_eq!(
+        ledger
+            .spendable(&Address::from_str(ADDRESS).unwrap(), 5)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn rejects_allotments_ending_before_they_start() {
+    let token = Address::from_str(DEBIT_TOKEN).unwrap();
+    let mut ledger = Ledger::new(Address::from_str(ADDRESS).unwrap());
+
+    match ledger.apply_wallet_state(&token, &wallet_state(&[(3, 0, 100), (4, 200, 150)])) {
+        Err(Error::InvalidProof { failure }) => assert_eq!(failure, ProofFailure::Allotment),
+        _ => panic!("expected an invalid allotment"),
+    }
+
+    assert_eq!(ledger.token(&token), None);
+}
+
+#[test]
+fn reports_overspent_and_overflowing_balances() {
+    let mut balance = EonBalance {
+        eon_number: 3,
+        opening: 100,
+        deposits: 40,
+        spent: 130,
+        pending_out: 20,
+        ..EonBalance::default()
+    };
+    assert_eq!(balance.closing().unwrap(), 10);
+    assert_eq!(balance.spendable().unwrap(), 0);
+
+    balance.withdrawals = 11;
+    match balance.closing() {
+        Err(Error::Underflow { field }) => assert_eq!(field, "balance"),
+        other => panic!("unexpected closing balance {:?}", other),
+    }
+
+    balance.received = u64::MAX;
+    match balance.spendable() {
+        Err(Error::Overflow { field }) => assert_eq!(field, "credits"),
+        other => panic!("unexpected spendable balance {:?}", other),
+    }
+}
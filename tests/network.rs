@@ -0,0 +1,73 @@
+use libliquefy::error::Error;
+use libliquefy::model::common::Address;
+use libliquefy::model::traits::ToJson;
+use libliquefy::request::network::{Network, Scheme};
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+mod common;
+
+use common::ADDRESS;
+
+/// `profile` returns a custom profile with every field set.
+fn profile() -> Network {
+    let mut network = Network::new("staging", Scheme::Http, "127.0.0.1:8123");
+    network.ws_path = "/notifications/".to_owned();
+    network.chain_id = Some(1337);
+    network.hub_contract = Some(Address::from_str(ADDRESS).unwrap());
+    network.blocks_per_eon = Some(180);
+    network
+}
+
+/// `path` returns a path of the temporary directory unique to a test.
+fn path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("liquefy-network-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn loads_toml_and_json_profiles() {
+    let network = profile();
+
+    let toml_path = path("staging.toml");
+    fs::write(&toml_path, toml::to_string(&network).unwrap()).unwrap();
+    assert_eq!(Network::load(&toml_path).unwrap(), network);
+
+    let json_path = path("staging.JSON");
+    fs::write(&json_path, network.to_json().unwrap()).unwrap();
+    assert_eq!(Network::load(&json_path).unwrap(), network);
+
+    let minimal = path("minimal.toml");
+    fs::write(
+        &minimal,
+        "name = \"local\"\nscheme = \"http\"\nhost = \"localhost:8000\"\n",
+    )
+    .unwrap();
+    let local = Network::load(&minimal).unwrap();
+    assert_eq!(local, Network::local("localhost:8000"));
+    assert_eq!(local.ws_url(), "ws://localhost:8000/ws/");
+
+    let yaml = path("staging.yaml");
+    fs::write(&yaml, "").unwrap();
+    match Network::load(&yaml) {
+        Err(Error::UnsupportedFormat { format }) => assert_eq!(format, "yaml"),
+        res => panic!("unexpected result {:?}", res),
+    }
+}
+
+#[test]
+fn returns_the_presets_by_name() {
+    let mainnet = Network::preset("mainnet").unwrap();
+    assert_eq!(mainnet.chain_id, Some(Network::MAINNET_CHAIN_ID));
+    assert_eq!(mainnet.http_url(), "https://public.liquidity.network");
+    assert_eq!(mainnet.ws_url(), "wss://public.liquidity.network/ws/");
+
+    let rinkeby = Network::preset("rinkeby").unwrap();
+    assert_eq!(rinkeby.chain_id, Some(Network::RINKEBY_CHAIN_ID));
+
+    assert_eq!(Network::preset("limbo").unwrap(), Network::limbo());
+    assert!(Network::preset("ropsten").is_none());
+}
@@ -0,0 +1,194 @@
+use async_std::task;
+use rug::Rational;
+use serde_json as json;
+
+use libliquefy::market::orderbook::{BookUpdate, OrderBook, Side};
+use libliquefy::model::common::Address;
+use libliquefy::model::http::audit::TokenOrderBook;
+use libliquefy::model::ws::ws::{Event, NotificationData, TokenPairEvent};
+use libliquefy::request::http::client::HubClient;
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{serve_http, transfer, CREDIT_TOKEN, DEBIT_TOKEN};
+
+/// `swap` returns a swap of a snapshot from its amounts and remaining amounts.
+fn swap(amount: u64, amount_swapped: u64, remaining_in: u64, remaining_out: u64) -> json::Value {
+    json::json!({
+        "amount": amount,
+        "amount_swapped": amount_swapped,
+        "remaining_in": remaining_in,
+        "remaining_out": remaining_out,
+    })
+}
+
+/// `snapshot` returns a snapshot with asks at 1/4 and 1/2 and bids at 1/5 and 1/10,
+/// in credit tokens per debit token.
+fn snapshot() -> json::Value {
+    json::json!({
+        "buy_orders": [swap(100, 1000, 100, 1000), swap(50, 250, 50, 250)],
+        "sell_orders": [
+            swap(1000, 250, 1000, 250),
+            swap(400, 200, 400, 200),
+            swap(200, 50, 200, 50),
+            swap(300, 100, 0, 0),
+        ],
+    })
+}
+
+fn load(snapshot: json::Value) -> OrderBook {
+    let snapshot: TokenOrderBook = json::from_value(snapshot).unwrap();
+
+    OrderBook::from_snapshot(
+        Address::from_str(DEBIT_TOKEN).unwrap(),
+        Address::from_str(CREDIT_TOKEN).unwrap(),
+        &snapshot,
+    )
+}
+
+/// `matched_swap` returns the event of the match of 100 of the 1000 debit tokens of a swap.
+fn matched_swap() -> TokenPairEvent {
+    TokenPairEvent::MatchedSwap(json::from_value(transfer()).unwrap())
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Rational {
+    Rational::from((numerator, denominator))
+}
+
+#[test]
+fn prices_orders_by_their_remaining_amounts() {
+    let book = load(snapshot());
+    assert_eq!(book.orders().len(), 5);
+
+    let prices: Vec<_> = book.orders().iter().map(|order| order.price()).collect();
+    assert_eq!(
+        prices,
+        vec![
+            Some(ratio(1, 10)),
+            Some(ratio(1, 5)),
+            Some(ratio(1, 4)),
+            Some(ratio(1, 2)),
+            Some(ratio(1, 4)),
+        ]
+    );
+
+    let best_ask = book.best_ask().unwrap();
+    assert_eq!(best_ask.price, ratio(1, 4));
+    assert_eq!(best_ask.volume, 1200);
+    assert_eq!(best_ask.orders, 2);
+
+    let best_bid = book.best_bid().unwrap();
+    assert_eq!(best_bid.price, ratio(1, 5));
+    assert_eq!(best_bid.volume, 250);
+
+    assert_eq!(book.spread(), Some(ratio(1, 20)));
+    assert_eq!(book.mid_price(), Some(ratio(9, 40)));
+
+    let asks: Vec<_> = book
+        .levels(Side::Ask)
+        .into_iter()
+        .map(|level| level.price)
+        .collect();
+    assert_eq!(asks, vec![ratio(1, 4), ratio(1, 2)]);
+
+    let bids: Vec<_> = book
+        .depth(Side::Bid, 1)
+        .into_iter()
+        .map(|level| (level.price, level.volume))
+        .collect();
+    assert_eq!(bids, vec![(ratio(1, 5), 250)]);
+
+    assert_eq!(book.volume_at(Side::Bid, &ratio(1, 10)), 1000);
+    assert_eq!(book.volume_at(Side::Ask, &ratio(1, 3)), 0);
+}
+
+#[test]
+fn saturates_the_volume_of_levels() {
+    let max = u64::MAX;
+    let book = load(json::json!({
+        "buy_orders": [],
+        "sell_orders": [swap(max, max, max, max), swap(max, max, max, max)],
+    }));
+
+    let best_ask = book.best_ask().unwrap();
+    assert_eq!(best_ask.price, ratio(1, 1));
+    assert_eq!(best_ask.volume, max);
+    assert_eq!(best_ask.orders, 2);
+    assert_eq!(book.volume_at(Side::Ask, &ratio(1, 1)), max);
+}
+
+#[test]
+fn applies_matches_of_snapshot_orders() {
+    let mut book = load(snapshot());
+
+    assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Applied);
+    assert_eq!(book.volume_at(Side::Ask, &ratio(1, 4)), 1100);
+    assert_eq!(book.orders()[2].id, Some(42));
+
+    assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Applied);
+
+    let mut grown = transfer();
+    grown["matched_amounts"]["matched_in"] = json::json!(0);
+    grown["matched_amounts"]["matched_out"] = json::json!(0);
+    let event = TokenPairEvent::MatchedSwap(json::from_value(grown).unwrap());
+    assert_eq!(book.apply_event(&event), BookUpdate::Gap);
+    assert!(book.is_stale());
+}
+
+#[test]
+fn matches_duplicate_snapshot_orders_only_when_identical() {
+    let mut identical = snapshot();
+    identical["sell_orders"][1] = swap(1000, 250, 1000, 250);
+
+    let mut book = load(identical);
+    assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Applied);
+    assert_eq!(book.volume_at(Side::Ask, &ratio(1, 4)), 2100);
+
+    let mut different = snapshot();
+    different["sell_orders"][1] = swap(1000, 250, 960, 240);
+
+    let mut book = load(different);
+    assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Gap);
+    assert!(book.is_stale());
+
+    let mut consistent = snapshot();
+    consistent["sell_orders"][1] = swap(1000, 250, 800, 200);
+
+    let mut book = load(consistent);
+    assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Applied);
+    assert_eq!(book.orders()[2].id, Some(42));
+}
+
+#[test]
+fn resnapshots_after_a_gap() {
+    task::block_on(async {
+        let left = Address::from_str(DEBIT_TOKEN).unwrap();
+        let right = Address::from_str(CREDIT_TOKEN).unwrap();
+        let path = format!("/audit/swaps/{}/{}", left, right);
+        let server = serve_http(vec![(path.clone(), snapshot().to_string())]).await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let mut book = OrderBook::new(left, right);
+        assert!(book.is_stale());
+        assert_eq!(book.apply_event(&matched_swap()), BookUpdate::Gap);
+
+        let mut unknown = transfer();
+        unknown["amount"] = json::json!(5000);
+        let notification = NotificationData::new(Event::TokenPair(TokenPairEvent::MatchedSwap(
+            json::from_value(unknown).unwrap(),
+        )));
+
+        book.resnapshot(&client).await.unwrap();
+        assert!(!book.is_stale());
+        assert_eq!(book.orders().len(), 5);
+
+        let update = book.sync(&client, &notification).await.unwrap();
+        assert_eq!(update, BookUpdate::Gap);
+        assert!(!book.is_stale());
+        assert_eq!(book.orders().len(), 5);
+
+        assert_eq!(*server.requests.lock().unwrap(), vec![path.clone(), path]);
+    });
+}
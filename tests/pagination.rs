@@ -0,0 +1,204 @@
+use async_std::task;
+use futures::stream::StreamExt;
+use serde_json as json;
+
+use libliquefy::error::Error;
+use libliquefy::model::common::Address;
+use libliquefy::request::http::client::HubClient;
+use libliquefy::request::http::pagination::{
+    TransferCursor, TransferFilter, TransferKind, TransferStream,
+};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{serve_http_responses, HttpServer, Response, ADDRESS, CREDIT_TOKEN, DEBIT_TOKEN};
+
+const FIRST_PAGE: &str = "/audit/transactions?swap=true";
+
+const SECOND_PAGE: &str = "/audit/transactions?swap=true&cursor=b2Zmc2V0PTM";
+
+/// `OTHER` is an address other than `ADDRESS`.
+const OTHER: &str = "0xf17f52151ebef6c7334fad080c5704d77216b733";
+
+/// `transfer` returns a swap of `ADDRESS` of an eon.
+fn transfer(id: i64, eon_number: i64) -> json::Value {
+    json::json!({
+        "amount": 1000,
+        "amount_swapped": 250,
+        "cancelled": false,
+        "complete": false,
+        "eon_number": eon_number,
+        "id": id,
+        "nonce": id,
+        "passive": false,
+        "recipient": { "address": ADDRESS, "token": CREDIT_TOKEN },
+        "swap": true,
+        "time": "2020-01-01T00:00:00Z",
+        "voided": false,
+        "wallet": { "address": ADDRESS, "token": DEBIT_TOKEN },
+    })
+}
+
+/// `page` returns a page of transfers, linking to the next page if any.
+fn page(results: Vec<json::Value>, next: Option<&str>) -> Response {
+    let body = json::json!({
+        "count": results.len(),
+        "next": next.map(|next| format!("http://{{host}}{}", next)),
+        "previous": null,
+        "results": results,
+    });
+
+    (200, body.to_string())
+}
+
+/// `serve` serves the two pages of swaps, of three and one transfers.
+async fn serve() -> HttpServer {
+    serve_http_responses(vec![
+        (
+            FIRST_PAGE.to_owned(),
+            vec![page(
+                vec![transfer(1, 2), transfer(2, 2), transfer(3, 3)],
+                Some(SECOND_PAGE),
+            )],
+        ),
+        (
+            SECOND_PAGE.to_owned(),
+            vec![page(vec![transfer(4, 3)], None)],
+        ),
+    ])
+    .await
+}
+
+fn swaps() -> TransferFilter {
+    TransferFilter::new().with_kind(TransferKind::Swap)
+}
+
+async fn ids(stream: TransferStream) -> Vec<i64> {
+    stream
+        .map(|transfer| transfer.unwrap().id)
+        .collect::<Vec<_>>()
+        .await
+}
+
+#[test]
+fn follows_the_next_links_of_the_pages() {
+    task::block_on(async {
+        let server = serve().await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let stream = client.transfer_stream(swaps()).unwrap();
+        assert_eq!(ids(stream).await, vec![1, 2, 3, 4]);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![FIRST_PAGE.to_owned(), SECOND_PAGE.to_owned()]
+        );
+    });
+}
+
+#[test]
+fn applies_the_filter_to_the_transfers_received() {
+    task::block_on(async {
+        let mut passive = transfer(2, 2);
+        passive["passive"] = json::json!(true);
+        let mut other = transfer(3, 2);
+        other["wallet"]["address"] = json::json!(OTHER);
+        other["recipient"]["address"] = json::json!(OTHER);
+        let mut plain = transfer(4, 2);
+        plain["swap"] = json::json!(false);
+
+        let path = format!(
+            "/audit/transactions?wallet={}&eon_number__gte=2&eon_number__lte=2&swap=true&passive=false",
+            Address::from_str(ADDRESS).unwrap()
+        );
+        let results = vec![
+            transfer(1, 2),
+            passive,
+            other,
+            plain,
+            transfer(5, 1),
+            transfer(6, 3),
+            transfer(7, 2),
+        ];
+        let server = serve_http_responses(vec![(path, vec![page(results, None)])]).await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let filter = swaps()
+            .with_wallet(Address::from_str(ADDRESS).unwrap())
+            .with_eons(2, 2)
+            .with_passive(false);
+        let stream = client.transfer_stream(filter).unwrap();
+        assert_eq!(ids(stream).await, vec![1, 7]);
+    });
+}
+
+#[test]
+fn resumes_from_a_serialized_cursor() {
+    task::block_on(async {
+        let server = serve().await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let mut stream = client.transfer_stream(swaps()).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().id, 1);
+
+        let cursor = json::to_string(&stream.cursor()).unwrap();
+        drop(stream);
+
+        let cursor: TransferCursor = json::from_str(&cursor).unwrap();
+        assert_eq!(cursor.position, 1);
+        assert_eq!(
+            cursor.page,
+            Some(format!("http://{}{}", server.host, FIRST_PAGE))
+        );
+
+        let stream = TransferStream::resume(&client, cursor);
+        assert_eq!(ids(stream).await, vec![2, 3, 4]);
+    });
+}
+
+#[test]
+fn retries_a_failed_page() {
+    task::block_on(async {
+        let server = serve_http_responses(vec![(
+            FIRST_PAGE.to_owned(),
+            vec![
+                (500, "{}".to_owned()),
+                (500, "{}".to_owned()),
+                page(vec![transfer(1, 2)], None),
+            ],
+        )])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let results = client
+            .transfer_stream(swaps())
+            .unwrap()
+            .with_max_retries(2)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Err(Error::HTTPStatus { status: 500 })));
+        assert!(matches!(results[1], Err(Error::HTTPStatus { status: 500 })));
+        assert_eq!(results[2].as_ref().unwrap().id, 1);
+    });
+}
+
+#[test]
+fn ends_after_the_maximum_number_of_retries() {
+    task::block_on(async {
+        let server =
+            serve_http_responses(vec![(FIRST_PAGE.to_owned(), vec![(500, "{}".to_owned())])]).await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let mut stream = client.transfer_stream(swaps()).unwrap().with_max_retries(1);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+
+        let cursor = stream.cursor();
+        assert!(!cursor.is_exhausted());
+        assert_eq!(cursor.position, 0);
+    });
+}
@@ -0,0 +1,206 @@
+use serde_json as json;
+
+use libliquefy::crypto::hash::{parse_hash, Hash};
+use libliquefy::crypto::proof::{ProofFailure, ProofVerifier};
+use libliquefy::crypto::protocol::transfer_hash;
+use libliquefy::error::Error;
+use libliquefy::model::common::{Address, DeliveryProof, Wallet};
+
+use std::str::FromStr;
+
+mod common;
+
+struct Fixture {
+    verifier: ProofVerifier,
+    root: Hash,
+    passive: Delivery,
+    active: Delivery,
+}
+
+struct Delivery {
+    wallet: Wallet,
+    transfer: Hash,
+    interval: (u64, u64),
+    proof: DeliveryProof,
+}
+
+fn fixture() -> Fixture {
+    let fixture = common::fixture();
+    let token = Address::from_str(fixture["token"].as_str().unwrap()).unwrap();
+
+    let delivery = |value: &json::Value| Delivery {
+        wallet: Wallet {
+            address: Address::from_str(value["wallet"].as_str().unwrap()).unwrap(),
+            token,
+        },
+        transfer: parse_hash(value["transfer"].as_str().unwrap()).unwrap(),
+        interval: json::from_value(value["interval"].clone()).unwrap_or((0, 0)),
+        proof: json::from_value(value["delivery_proof"].clone()).unwrap(),
+    };
+
+    Fixture {
+        verifier: ProofVerifier::new(
+            Address::from_str(fixture["contract"].as_str().unwrap()).unwrap(),
+        ),
+        root: parse_hash(fixture["checkpoint_root"].as_str().unwrap()).unwrap(),
+        passive: delivery(&fixture["passive"]),
+        active: delivery(&fixture["active"]),
+    }
+}
+
+fn failure<T>(res: libliquefy::result::Result<T>) -> ProofFailure {
+    match res {
+        Err(Error::InvalidProof { failure }) => failure,
+        _ => panic!("expected an invalid proof"),
+    }
+}
+
+#[test]
+fn verifies_merkle_proofs() {
+    let f = fixture();
+
+    for delivery in [&f.passive, &f.active].iter() {
+        let proof = &delivery.proof.merkle_proof;
+        f.verifier
+            .verify_merkle_proof(&delivery.wallet, proof, &f.root)
+            .unwrap();
+
+        let (_, left, right) = f.verifier.allotment_root(&delivery.wallet, proof).unwrap();
+        assert_eq!((left, right), (0, 250));
+    }
+
+    let mut proof = f.passive.proof.merkle_proof.clone();
+    proof.right = 120;
+    let res = f
+        .verifier
+        .verify_merkle_proof(&f.passive.wallet, &proof, &f.root);
+    assert_eq!(failure(res), ProofFailure::CheckpointRoot);
+
+    let mut proof = f.passive.proof.merkle_proof.clone();
+    proof.active_state.updated_spendings = "30".to_owned();
+    let res = f
+        .verifier
+        .verify_merkle_proof(&f.passive.wallet, &proof, &f.root);
+    assert_eq!(failure(res), ProofFailure::ActiveStateChecksum);
+}
+
+#[test]
+fn verifies_passive_delivery_with_membership_values() {
+    let f = fixture();
+    let p = &f.passive;
+
+    f.verifier
+        .verify_passive_delivery_proof(&p.wallet, &p.transfer, p.interval, &p.proof, &f.root)
+        .unwrap();
+
+    let res = f.verifier.verify_passive_delivery_proof(
+        &p.wallet,
+        &p.transfer,
+        (30, 90),
+        &p.proof,
+        &f.root,
+    );
+    assert_eq!(failure(res), ProofFailure::TransferMembership);
+
+    let mut proof = p.proof.clone();
+    proof.transfer_membership_values = vec![10];
+    let res = f.verifier.verify_passive_delivery_proof(
+        &p.wallet,
+        &p.transfer,
+        p.interval,
+        &proof,
+        &f.root,
+    );
+    assert_eq!(failure(res), ProofFailure::TransferMembership);
+
+    proof.transfer_membership_values = vec![];
+    let res = f.verifier.verify_passive_delivery_proof(
+        &p.wallet,
+        &p.transfer,
+        p.interval,
+        &proof,
+        &f.root,
+    );
+    assert_eq!(failure(res), ProofFailure::ChainLength);
+}
+
+#[test]
+fn verifies_active_delivery() {
+    let f = fixture();
+    let a = &f.active;
+
+    f.verifier
+        .verify_delivery_proof(&a.wallet, &a.transfer, &a.proof, &f.root)
+        .unwrap();
+
+    let res = f
+        .verifier
+        .verify_delivery_proof(&a.wallet, &f.passive.transfer, &a.proof, &f.root);
+    assert_eq!(failure(res), ProofFailure::TransferMembership);
+
+    let mut proof = a.proof.clone();
+    proof.transfer_membership_values = vec![0, 0];
+    let res = f
+        .verifier
+        .verify_delivery_proof(&a.wallet, &a.transfer, &proof, &f.root);
+    assert_eq!(failure(res), ProofFailure::ChainLength);
+
+    let res = f
+        .verifier
+        .verify_delivery_proof(&a.wallet, &a.transfer, &a.proof, &[0u8; 32]);
+    assert_eq!(failure(res), ProofFailure::CheckpointRoot);
+}
+
+#[test]
+fn matches_independently_computed_vectors() {
+    let vectors = common::reference_vectors();
+    let hash = |key: &str| parse_hash(vectors[key].as_str().unwrap()).unwrap();
+    let address = |value: &json::Value| Address::from_str(value.as_str().unwrap()).unwrap();
+
+    let contract = address(&vectors["contract"]);
+    let token = address(&vectors["token"]);
+    let passive = &vectors["passive"];
+    let wallet = Wallet {
+        address: address(&passive["wallet"]),
+        token,
+    };
+    let sender = Wallet {
+        address: address(&passive["sender"]),
+        token,
+    };
+
+    let transfer = transfer_hash(
+        &contract,
+        &sender,
+        &wallet,
+        passive["amount"].as_u64().unwrap(),
+        passive["nonce"].as_u64().unwrap(),
+    );
+    assert_eq!(
+        transfer,
+        parse_hash(passive["transfer"].as_str().unwrap()).unwrap()
+    );
+
+    let verifier = ProofVerifier::new(contract);
+    let proof: DeliveryProof = json::from_value(passive["delivery_proof"].clone()).unwrap();
+    let merkle_proof = &proof.merkle_proof;
+
+    assert_eq!(
+        verifier.leaf_hash(&wallet, merkle_proof).unwrap(),
+        hash("leaf_hash")
+    );
+    assert_eq!(
+        verifier.allotment_root(&wallet, merkle_proof).unwrap(),
+        (hash("allotment_root"), 0, 260)
+    );
+    assert_eq!(
+        verifier.membership_root(&wallet, merkle_proof).unwrap(),
+        hash("membership_root")
+    );
+
+    let root = hash("checkpoint_root");
+    let interval = json::from_value(passive["interval"].clone()).unwrap();
+    verifier
+        .verify_passive_delivery_proof(&wallet, &transfer, interval, &proof, &root)
+        .unwrap();
+}
@@ -0,0 +1,59 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use libliquefy::model::common::{Address, DateTime, Decimal, Wallet};
+use libliquefy::model::http::sla::{WalletSLA, SLA};
+use libliquefy::wallet::sla::{SLAQuota, SLAWarning};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{ADDRESS, DEBIT_TOKEN};
+
+fn quota(limit: u64) -> SLAQuota {
+    let wallet = Wallet {
+        address: Address::from_str(ADDRESS).unwrap(),
+        token: Address::from_str(DEBIT_TOKEN).unwrap(),
+    };
+    let sla = SLA::new(Decimal::from(1), limit, wallet.address, wallet.token).unwrap();
+    let expiry = Utc.timestamp_opt(1_600_000_000, 0).unwrap() + Duration::days(30);
+
+    SLAQuota::new(wallet, &sla, &WalletSLA::new(DateTime::new(expiry)))
+}
+
+#[test]
+fn warns_from_the_usage_threshold() {
+    let now = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    let mut quota = quota(10);
+
+    for _ in 0..8 {
+        quota.record_transfer(3);
+    }
+    assert!(quota.warnings(now).is_empty());
+
+    quota.record_transfer(3);
+    assert_eq!(
+        quota.warnings(now),
+        vec![SLAWarning::Exhausting { used: 9, limit: 10 }]
+    );
+
+    quota.record_transfer(3);
+    assert_eq!(
+        quota.warnings(now),
+        vec![SLAWarning::Exhausted { limit: 10 }]
+    );
+    assert!(!quota.is_covered(now));
+
+    quota.record_transfer(4);
+    assert!(quota.warnings(now).is_empty());
+}
+
+#[test]
+fn warns_without_overflow_on_large_limits() {
+    let now = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    let mut quota = quota(u64::MAX);
+
+    quota.record_transfer(3);
+    assert!(quota.warnings(now).is_empty());
+    assert!(quota.is_covered(now));
+}
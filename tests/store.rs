@@ -0,0 +1,211 @@
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::model::common::{Address, SenderActiveState, Wallet};
+use libliquefy::model::http::audit::WalletState;
+use libliquefy::model::http::swap::Swap;
+use libliquefy::model::http::transfer::TransferReceipt;
+use libliquefy::store::json::JsonFileStore;
+#[cfg(feature = "sqlite")]
+use libliquefy::store::sqlite::SqliteStore;
+use libliquefy::store::traits::{
+    LastNotification, Record, Store, StoreExt, WalletActiveState, WalletNonce,
+};
+use libliquefy::wallet::signatures::SwapSignatures;
+use libliquefy::wallet::transfer::build_transfer;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+mod common;
+
+use common::{wallet_state, CONTRACT, CREDIT_TOKEN, DEBIT_TOKEN, PRIVATE_KEY};
+
+/// `Backend` is a `Store` implementation under test.
+#[derive(Copy, Clone, Debug)]
+enum Backend {
+    Json,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl Backend {
+    const ALL: &'static [Backend] = &[
+        Backend::Json,
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite,
+    ];
+
+    /// `path` returns the path of the store of a test.
+    fn path(self, test: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "liquefy-store-{}-{:?}-{}",
+            test,
+            self,
+            std::process::id()
+        ))
+    }
+
+    /// `open` opens the store at a path.
+    fn open(self, path: &PathBuf) -> Box<dyn Store> {
+        match self {
+            Backend::Json => Box::new(JsonFileStore::open(path).unwrap()),
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite => Box::new(SqliteStore::open(path).unwrap()),
+        }
+    }
+}
+
+fn swap() -> Swap {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    SwapSignatures::new(
+        &contract,
+        &key,
+        &Address::from_str(DEBIT_TOKEN).unwrap(),
+        &Address::from_str(CREDIT_TOKEN).unwrap(),
+        1000,
+        250,
+        0,
+    )
+    .with_lifetime(5, 2)
+    .build()
+    .unwrap()
+}
+
+#[test]
+fn keeps_the_key_of_a_swap_once_created() {
+    for backend in Backend::ALL {
+        let path = backend.path("swap");
+        let store = backend.open(&path);
+
+        let mut swap = swap();
+        store.save(&swap).unwrap();
+
+        swap.id = Some(42);
+        store.save(&swap).unwrap();
+
+        assert_eq!(store.load_all::<Swap>().unwrap(), vec![swap.clone()]);
+
+        let reopened = backend.open(&path);
+        assert_eq!(reopened.load::<Swap>(&swap.key()).unwrap(), Some(swap));
+
+        drop((store, reopened));
+        fs::remove_file(&path).unwrap();
+    }
+}
+
+#[test]
+fn persists_receipts_nonces_and_notifications() {
+    for backend in Backend::ALL {
+        let path = backend.path("records");
+        let store = backend.open(&path);
+
+        let contract = Address::from_str(CONTRACT).unwrap();
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+        let recipient = Wallet {
+            address: Address::from_str("0x0000000000000000000000000000000000000001").unwrap(),
+            token: Address::from_str(DEBIT_TOKEN).unwrap(),
+        };
+        let state: WalletState = json::from_value(wallet_state(Vec::new())).unwrap();
+
+        let receipt = build_transfer(&contract, &key, &state, 3, &recipient, 10).unwrap();
+        let next = TransferReceipt {
+            nonce: 1,
+            ..receipt.clone()
+        };
+        store.save(&next).unwrap();
+        store.save(&receipt).unwrap();
+        store.save(&receipt).unwrap();
+        assert_eq!(
+            store.load_all::<TransferReceipt>().unwrap(),
+            vec![receipt.clone(), next]
+        );
+
+        let nonce = WalletNonce {
+            wallet: receipt.wallet.clone(),
+            nonce: 1,
+        };
+        store.save(&nonce).unwrap();
+        store
+            .save(&WalletNonce {
+                nonce: 2,
+                ..nonce.clone()
+            })
+            .unwrap();
+
+        let notification = LastNotification {
+            url: "ws://localhost/".to_owned(),
+            uuid: json::from_value(json::json!("2c4b3f8a-2f0f-11ea-8cfa-0242ac110002")).unwrap(),
+        };
+        store.save(&notification).unwrap();
+
+        let reopened = backend.open(&path);
+        assert_eq!(
+            reopened
+                .load::<WalletNonce>(&nonce.key())
+                .unwrap()
+                .map(|n| n.nonce),
+            Some(2)
+        );
+        assert_eq!(
+            reopened
+                .load::<LastNotification>("ws://localhost/")
+                .unwrap()
+                .map(|last| last.uuid),
+            Some(notification.uuid)
+        );
+
+        drop((store, reopened));
+        fs::remove_file(&path).unwrap();
+    }
+}
+
+#[test]
+fn keys_active_states_by_wallet_and_eon() {
+    let wallet = Wallet {
+        address: PrivateKey::from_str(PRIVATE_KEY).unwrap().address(),
+        token: Address::from_str(DEBIT_TOKEN).unwrap(),
+    };
+    let other = Wallet {
+        token: Address::from_str(CREDIT_TOKEN).unwrap(),
+        ..wallet.clone()
+    };
+
+    let unsigned = WalletActiveState {
+        wallet: wallet.clone(),
+        eon_number: 5,
+        active_state: SenderActiveState {
+            tx_set_hash: "0x01".to_owned(),
+            ..SenderActiveState::default()
+        },
+    };
+    let signed = WalletActiveState {
+        active_state: SenderActiveState {
+            wallet_signature: "0x02".to_owned(),
+            ..unsigned.active_state.clone()
+        },
+        ..unsigned.clone()
+    };
+
+    assert_eq!(unsigned.key(), signed.key());
+    assert_ne!(
+        unsigned.key(),
+        WalletActiveState {
+            wallet: other,
+            ..unsigned.clone()
+        }
+        .key()
+    );
+    assert_ne!(
+        unsigned.key(),
+        WalletActiveState {
+            eon_number: 6,
+            ..unsigned.clone()
+        }
+        .key()
+    );
+}
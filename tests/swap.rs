@@ -0,0 +1,258 @@
+use async_std::task;
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::error::Error;
+use libliquefy::model::common::Address;
+use libliquefy::model::http::swap::Swap;
+use libliquefy::request::http::client::HubClient;
+use libliquefy::request::http::swap::{SwapCancelError, SwapFinalizeError, SwapFreezeError};
+use libliquefy::store::json::JsonFileStore;
+use libliquefy::store::traits::{Record, StoreExt};
+use libliquefy::wallet::signatures::SwapSignatures;
+use libliquefy::wallet::swap::{SwapOrder, SwapState, SwapTrader, SwapTransitionError};
+
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+mod common;
+
+use common::{
+    serve_http, transfer, wallet_state, ADDRESS, CONTRACT, CREDIT_TOKEN, DEBIT_TOKEN, PRIVATE_KEY,
+};
+
+use SwapState::*;
+
+const STATES: [SwapState; 7] = [
+    Created, Matched, Fulfilled, Finalized, Frozen, Cancelled, Voided,
+];
+
+fn freeze(code: SwapFreezeError) -> Result<(), SwapTransitionError> {
+    Err(SwapTransitionError::Freeze(code))
+}
+
+fn cancel(code: SwapCancelError) -> Result<(), SwapTransitionError> {
+    Err(SwapTransitionError::Cancel(code))
+}
+
+fn finalize(code: SwapFinalizeError) -> Result<(), SwapTransitionError> {
+    Err(SwapTransitionError::Finalize(code))
+}
+
+#[test]
+fn checks_every_transition() {
+    let unsupported = Err(SwapTransitionError::Unsupported);
+    let not_fulfilled = finalize(SwapFinalizeError::SwapNotFulfilled);
+    let not_frozen = cancel(SwapCancelError::SwapNotFrozen);
+    let closed = cancel(SwapCancelError::SwapAlreadyClosed);
+
+    // The outcomes of the transitions from each state to the states in the order of `STATES`.
+    let transitions = vec![
+        (
+            Created,
+            vec![
+                unsupported.clone(),
+                Ok(()),
+                Ok(()),
+                not_fulfilled.clone(),
+                Ok(()),
+                not_frozen.clone(),
+                Ok(()),
+            ],
+        ),
+        (
+            Matched,
+            vec![
+                unsupported.clone(),
+                Ok(()),
+                Ok(()),
+                not_fulfilled.clone(),
+                Ok(()),
+                not_frozen.clone(),
+                Ok(()),
+            ],
+        ),
+        (
+            Fulfilled,
+            vec![
+                unsupported.clone(),
+                unsupported.clone(),
+                unsupported.clone(),
+                Ok(()),
+                freeze(SwapFreezeError::SwapAlreadyFulfilled),
+                not_frozen,
+                unsupported.clone(),
+            ],
+        ),
+        (
+            Finalized,
+            vec![
+                unsupported.clone(),
+                unsupported.clone(),
+                unsupported.clone(),
+                finalize(SwapFinalizeError::SwapAlreadyFinalized),
+                freeze(SwapFreezeError::SwapAlreadyClosed),
+                closed.clone(),
+                unsupported.clone(),
+            ],
+        ),
+        (
+            Frozen,
+            vec![
+                unsupported.clone(),
+                unsupported.clone(),
+                unsupported.clone(),
+                finalize(SwapFinalizeError::SwapAlreadyFrozen),
+                freeze(SwapFreezeError::SwapAlreadyFrozen),
+                Ok(()),
+                unsupported.clone(),
+            ],
+        ),
+        (
+            Cancelled,
+            vec![
+                unsupported.clone(),
+                unsupported.clone(),
+                unsupported.clone(),
+                finalize(SwapFinalizeError::SwapAlreadyClosed),
+                freeze(SwapFreezeError::SwapAlreadyClosed),
+                cancel(SwapCancelError::SwapAlreadyCancelled),
+                unsupported.clone(),
+            ],
+        ),
+        (
+            Voided,
+            vec![
+                unsupported.clone(),
+                unsupported.clone(),
+                unsupported.clone(),
+                finalize(SwapFinalizeError::SwapAlreadyVoided),
+                freeze(SwapFreezeError::SwapAlreadyVoided),
+                closed,
+                unsupported,
+            ],
+        ),
+    ];
+
+    for (from, outcomes) in transitions {
+        for (to, outcome) in STATES.iter().zip(outcomes) {
+            assert_eq!(from.check_transition(*to), outcome, "{} to {}", from, to);
+        }
+    }
+
+    let mut order = SwapOrder::new(swap(), 1);
+    order.transition(Frozen).unwrap();
+    match order.transition(Finalized) {
+        Err(Error::InvalidSwapTransition { from, to, reason }) => {
+            assert_eq!((from, to), (Frozen, Finalized));
+            assert_eq!(
+                reason,
+                SwapTransitionError::Finalize(SwapFinalizeError::SwapAlreadyFrozen)
+            );
+        }
+        other => panic!("unexpected transition {:?}", other),
+    }
+    assert_eq!(order.state, Frozen);
+}
+
+fn swap() -> Swap {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    SwapSignatures::new(
+        &contract,
+        &key,
+        &Address::from_str(DEBIT_TOKEN).unwrap(),
+        &Address::from_str(CREDIT_TOKEN).unwrap(),
+        1000,
+        250,
+        7,
+    )
+    .with_lifetime(3, 2)
+    .build()
+    .unwrap()
+}
+
+#[test]
+fn bounds_the_last_eon_of_orders() {
+    let mut order = SwapOrder::new(swap(), 2);
+    assert_eq!(order.last_eon().unwrap(), 4);
+
+    order.lifetime = 0;
+    assert_eq!(order.last_eon().unwrap(), 3);
+
+    order.swap.eon_number = u64::MAX;
+    order.lifetime = 2;
+    match order.last_eon() {
+        Err(Error::Overflow { field }) => assert_eq!(field, "last_eon"),
+        other => panic!("unexpected last eon {:?}", other),
+    }
+}
+
+#[test]
+fn saves_orders_on_creation_and_transition() {
+    task::block_on(async {
+        let status = json::json!({
+            "blocks_per_eon": 100,
+            "confirmed": [],
+            "current_eon_number": 3,
+            "latest": { "block": 350, "eon_number": 3 },
+        });
+
+        let mut sent = transfer();
+        sent["amount_swapped"] = json::Value::Null;
+        sent["nonce"] = json::json!(6);
+
+        let mut created = swap();
+        created.id = Some(42);
+
+        let mut fulfilled = transfer();
+        fulfilled["complete"] = json::json!(true);
+
+        let debit = Address::from_str(DEBIT_TOKEN).unwrap();
+        let credit = Address::from_str(CREDIT_TOKEN).unwrap();
+        let address = Address::from_str(ADDRESS).unwrap();
+
+        let server = serve_http(vec![
+            ("/analytics/status".to_owned(), status.to_string()),
+            (
+                format!("/audit/{}/{}", debit, address),
+                wallet_state(vec![sent]).to_string(),
+            ),
+            (
+                format!("/audit/{}/{}", credit, address),
+                wallet_state(Vec::new()).to_string(),
+            ),
+            ("/swap/".to_owned(), json::to_string(&created).unwrap()),
+            ("/audit/transactions/42".to_owned(), fulfilled.to_string()),
+        ])
+        .await;
+        let client = HubClient::with_scheme("http", &server.host);
+
+        let path = env::temp_dir().join(format!("liquefy-swap-{}.json", std::process::id()));
+        let store = JsonFileStore::open(&path).unwrap();
+        let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+        let trader =
+            SwapTrader::new(&client, Address::from_str(CONTRACT).unwrap()).with_store(&store);
+        let mut order = trader
+            .create_order(&key, &debit, &credit, 1000, 250, 2)
+            .await
+            .unwrap();
+
+        let body: Swap = json::from_str(server.bodies.lock().unwrap().last().unwrap()).unwrap();
+        assert_eq!(body.nonce, 7);
+        assert_eq!(order, SwapOrder::new(created, 2));
+        assert_eq!(store.load_all::<SwapOrder>().unwrap(), vec![order.clone()]);
+
+        assert_eq!(trader.refresh(&mut order).await.unwrap(), Fulfilled);
+        assert_eq!(
+            store.load::<SwapOrder>(&order.key()).unwrap(),
+            Some(order.clone())
+        );
+        assert_eq!(store.load_all::<SwapOrder>().unwrap().len(), 1);
+
+        fs::remove_file(&path).unwrap();
+    });
+}
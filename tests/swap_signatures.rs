@@ -0,0 +1,239 @@
+use serde_json as json;
+
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::crypto::merkle;
+use libliquefy::crypto::protocol::ActiveStateUpdate;
+use libliquefy::error::Error;
+use libliquefy::model::common::{Address, Decimal, Wallet};
+use libliquefy::model::http::audit::WalletState;
+use libliquefy::wallet::signatures::SwapSignatures;
+use libliquefy::wallet::transfer::TransactionSet;
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{transfer, wallet_state, CONTRACT, CREDIT_TOKEN, DEBIT_TOKEN, PRIVATE_KEY};
+
+const AMOUNT: u64 = 1000;
+
+const AMOUNT_SWAPPED: u64 = 250;
+
+fn signatures<'a>(contract: &'a Address, key: &'a PrivateKey) -> SwapSignatures<'a> {
+    SwapSignatures::new(
+        contract,
+        key,
+        &Address::from_str(DEBIT_TOKEN).unwrap(),
+        &Address::from_str(CREDIT_TOKEN).unwrap(),
+        AMOUNT,
+        AMOUNT_SWAPPED,
+        0,
+    )
+}
+
+#[test]
+fn signs_once_per_open_eon() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    for lifetime in 1..=4 {
+        let signatures = signatures(&contract, &key).with_lifetime(5, lifetime);
+        let swap = signatures.build().unwrap();
+        let count = lifetime as usize;
+
+        assert_eq!(swap.eon_number, 5);
+        assert_eq!(swap.debit_signature.len(), count);
+        assert_eq!(swap.credit_signature.len(), count);
+        assert_eq!(swap.debit_balance_signature.len(), count);
+        assert_eq!(swap.credit_balance_signature.len(), count);
+        assert_eq!(swap.fulfillment_signature.len(), count);
+
+        assert_eq!(signatures.freeze().unwrap().freezing_signature.len(), count);
+
+        let cancellation = signatures.cancellation().unwrap();
+        assert_eq!(cancellation.sender_cancellation_signature.len(), count);
+        assert_eq!(cancellation.recipient_cancellation_signature.len(), count);
+
+        let finalization = signatures.finalization().unwrap();
+        assert_eq!(finalization.finalization_signature.len(), count);
+    }
+}
+
+#[test]
+fn signs_the_projection_of_each_eon() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let signatures = signatures(&contract, &key).with_lifetime(5, 3);
+    let swap = signatures.build().unwrap();
+    let tx_set_hash = merkle::root(&[signatures.hash()]);
+
+    for (i, eon_number) in (5..=7).enumerate() {
+        let debit = ActiveStateUpdate {
+            wallet: signatures.debit_wallet().clone(),
+            trail_identifier: 0,
+            eon_number,
+            tx_set_hash,
+            updated_spendings: Decimal::from(AMOUNT),
+            updated_gains: Decimal::from(0),
+        };
+        let signer = debit.signer(&contract, &swap.debit_signature[i]).unwrap();
+        assert_eq!(signer, key.address());
+
+        let credit = ActiveStateUpdate {
+            wallet: signatures.credit_wallet().clone(),
+            updated_spendings: Decimal::from(0),
+            ..debit
+        };
+        let signer = credit.signer(&contract, &swap.credit_signature[i]).unwrap();
+        assert_eq!(signer, key.address());
+    }
+}
+
+#[test]
+fn signs_the_remaining_eons_of_an_existing_swap() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let mut swap = signatures(&contract, &key)
+        .with_lifetime(5, 4)
+        .build()
+        .unwrap();
+    swap.id = Some(42);
+
+    let signatures = SwapSignatures::from_swap(&contract, &key, &swap).with_lifetime(7, 4);
+    assert_eq!(signatures.eons().unwrap(), 7..=8);
+    assert_eq!(signatures.freeze().unwrap().freezing_signature.len(), 2);
+
+    let signatures = SwapSignatures::from_swap(&contract, &key, &swap).with_lifetime(9, 4);
+    assert!(signatures.freeze().is_err());
+}
+
+#[test]
+fn rejects_lifetimes_out_of_range() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let signatures = signatures(&contract, &key).with_lifetime(5, 0);
+    assert!(signatures.build().is_err());
+
+    let signatures = signatures.with_lifetime(5, 3).with_max_lifetime(2);
+
+    match signatures.build() {
+        Err(Error::OutOfRange { value, max, .. }) => {
+            assert_eq!(value, 3);
+            assert_eq!(max, Some(2));
+        }
+        _ => panic!("expected an out of range lifetime"),
+    }
+}
+
+#[test]
+fn projects_existing_swaps_in_place_of_their_amounts() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let mut swap = signatures(&contract, &key)
+        .with_lifetime(5, 2)
+        .build()
+        .unwrap();
+    swap.id = Some(42);
+
+    let other = Wallet {
+        address: Address::from_str("0x0000000000000000000000000000000000000001").unwrap(),
+        ..swap.wallet.clone()
+    };
+
+    // The hub state holds the fulfilled swap, a transfer sent from the debit
+    // wallet and a transfer received by the credit wallet.
+    let mut audit = transfer();
+    audit["id"] = json::json!(42);
+    audit["eon_number"] = json::json!(5);
+    audit["nonce"] = json::json!(0);
+    audit["complete"] = json::json!(true);
+
+    let mut sent = audit.clone();
+    sent["id"] = json::json!(7);
+    sent["recipient"] = json::to_value(&other).unwrap();
+    sent["amount"] = json::json!(100);
+    sent["amount_swapped"] = json::Value::Null;
+    sent["nonce"] = json::json!(1);
+
+    let mut received = sent.clone();
+    received["id"] = json::json!(50);
+    received["wallet"] = json::to_value(&other).unwrap();
+    received["recipient"] = json::to_value(&swap.recipient).unwrap();
+    received["amount"] = json::json!(60);
+
+    let state: WalletState = json::from_value(wallet_state(vec![audit, sent, received])).unwrap();
+    let debit_set = TransactionSet::from_state(&contract, &swap.wallet, &state, 5).unwrap();
+    let credit_set = TransactionSet::from_state(&contract, &swap.recipient, &state, 5).unwrap();
+    assert_eq!(
+        (debit_set.spendings, credit_set.gains),
+        (AMOUNT + 100, AMOUNT_SWAPPED + 60)
+    );
+
+    let signatures = SwapSignatures::from_swap(&contract, &key, &swap)
+        .with_lifetime(5, 2)
+        .with_debit_state(0, debit_set.clone())
+        .with_credit_state(0, credit_set.clone());
+    assert_eq!(signatures.hash(), debit_set.leaves[1]);
+
+    let cancellation = signatures.cancellation().unwrap();
+    let finalization = signatures.finalization().unwrap();
+
+    for (i, eon_number) in (5..=6).enumerate() {
+        let (debit_leaves, credit_leaves, spendings, gains) = if eon_number == 5 {
+            (debit_set.leaves.clone(), credit_set.leaves.clone(), 100, 60)
+        } else {
+            (vec![signatures.hash()], vec![signatures.hash()], 0, 0)
+        };
+
+        let debit = ActiveStateUpdate {
+            wallet: swap.wallet.clone(),
+            trail_identifier: 0,
+            eon_number,
+            tx_set_hash: merkle::root(&debit_leaves),
+            updated_spendings: Decimal::from(spendings),
+            updated_gains: Decimal::from(0),
+        };
+        let signature = &cancellation.sender_cancellation_signature[i];
+        assert_eq!(debit.signer(&contract, signature).unwrap(), key.address());
+
+        let credit = ActiveStateUpdate {
+            wallet: swap.recipient.clone(),
+            tx_set_hash: merkle::root(&credit_leaves),
+            updated_spendings: Decimal::from(0),
+            updated_gains: Decimal::from(gains + AMOUNT_SWAPPED),
+            ..debit
+        };
+        let signature = &finalization.finalization_signature[i];
+        assert_eq!(credit.signer(&contract, signature).unwrap(), key.address());
+    }
+}
+
+#[test]
+fn bounds_lifetimes_by_default() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+
+    let lifetime = SwapSignatures::DEFAULT_MAX_LIFETIME;
+    assert!(signatures(&contract, &key)
+        .with_lifetime(5, lifetime)
+        .build()
+        .is_ok());
+
+    match signatures(&contract, &key)
+        .with_lifetime(5, lifetime + 1)
+        .build()
+    {
+        Err(Error::OutOfRange { max, .. }) => assert_eq!(max, Some(lifetime as i64)),
+        _ => panic!("expected an out of range lifetime"),
+    }
+
+    let signatures = signatures(&contract, &key).with_lifetime(u64::MAX, 2);
+    match signatures.build() {
+        Err(Error::Overflow { field }) => assert_eq!(field, "eon_number"),
+        _ => panic!("expected an overflow of the last eon"),
+    }
+}
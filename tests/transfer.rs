@@ -0,0 +1,143 @@
+use serde_json as json;
+
+use libliquefy::crypto::hash::{format_hash, Hash};
+use libliquefy::crypto::key::PrivateKey;
+use libliquefy::crypto::merkle;
+use libliquefy::crypto::protocol::{swap_hash, transfer_hash};
+use libliquefy::error::Error;
+use libliquefy::model::common::{Address, Wallet};
+use libliquefy::model::http::audit::WalletState;
+use libliquefy::wallet::transfer::{build_transfer, next_nonce, TransactionSet};
+
+use std::str::FromStr;
+
+mod common;
+
+use common::{transfer, wallet_state, ADDRESS, CONTRACT, CREDIT_TOKEN, DEBIT_TOKEN, PRIVATE_KEY};
+
+const OTHER: &str = "0x0000000000000000000000000000000000000001";
+
+fn wallet(address: &str, token: &str) -> Wallet {
+    Wallet {
+        address: Address::from_str(address).unwrap(),
+        token: Address::from_str(token).unwrap(),
+    }
+}
+
+/// `plain` returns a complete transfer of an amount between two wallets.
+fn plain(id: i64, sender: &Wallet, recipient: &Wallet, amount: u64, nonce: u64) -> json::Value {
+    let mut transfer = transfer();
+    transfer["id"] = json::json!(id);
+    transfer["wallet"] = json::to_value(sender).unwrap();
+    transfer["recipient"] = json::to_value(recipient).unwrap();
+    transfer["amount"] = json::json!(amount);
+    transfer["amount_swapped"] = json::Value::Null;
+    transfer["nonce"] = json::json!(nonce);
+    transfer["complete"] = json::json!(true);
+    transfer
+}
+
+/// `state` returns a wallet state mixing transfers and a swap of 1000 debit tokens
+/// for 250 credit tokens in eon 3, with transfers of other eons or voided.
+fn state() -> WalletState {
+    let debit = wallet(ADDRESS, DEBIT_TOKEN);
+    let credit = wallet(ADDRESS, CREDIT_TOKEN);
+    let other_debit = wallet(OTHER, DEBIT_TOKEN);
+    let other_credit = wallet(OTHER, CREDIT_TOKEN);
+
+    let mut swap = transfer();
+    swap["id"] = json::json!(2);
+    swap["nonce"] = json::json!(1);
+    swap["complete"] = json::json!(true);
+
+    let mut voided = plain(5, &debit, &other_debit, 70, 3);
+    voided["voided"] = json::json!(true);
+
+    let mut previous = plain(0, &debit, &other_debit, 500, 0);
+    previous["eon_number"] = json::json!(2);
+
+    json::from_value(wallet_state(vec![
+        plain(4, &other_credit, &credit, 60, 0),
+        swap,
+        plain(1, &debit, &other_debit, 100, 2),
+        plain(3, &other_debit, &debit, 40, 0),
+        voided,
+        previous,
+    ]))
+    .unwrap()
+}
+
+/// `leaves` returns the leaves of the eon 3 transaction set, in id order.
+fn leaves(contract: &Address) -> Vec<Hash> {
+    let debit = wallet(ADDRESS, DEBIT_TOKEN);
+    let credit = wallet(ADDRESS, CREDIT_TOKEN);
+    let other_debit = wallet(OTHER, DEBIT_TOKEN);
+    let other_credit = wallet(OTHER, CREDIT_TOKEN);
+
+    vec![
+        transfer_hash(contract, &debit, &other_debit, 100, 2),
+        swap_hash(contract, &debit, &credit, 1000, 250, 1),
+        transfer_hash(contract, &other_debit, &debit, 40, 0),
+        transfer_hash(contract, &other_credit, &credit, 60, 0),
+    ]
+}
+
+#[test]
+fn commits_to_transfers_and_swaps() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let state = state();
+
+    let debit =
+        TransactionSet::from_state(&contract, &wallet(ADDRESS, DEBIT_TOKEN), &state, 3).unwrap();
+    assert_eq!(debit.ids, vec![Some(1), Some(2), Some(3), Some(4)]);
+    assert_eq!(debit.leaves, leaves(&contract));
+    assert_eq!(debit.spendings, 1100);
+    assert_eq!(debit.gains, 40);
+
+    let credit =
+        TransactionSet::from_state(&contract, &wallet(ADDRESS, CREDIT_TOKEN), &state, 3).unwrap();
+    assert_eq!(credit.leaves, debit.leaves);
+    assert_eq!(credit.spendings, 0);
+    assert_eq!(credit.gains, 250 + 60);
+}
+
+#[test]
+fn builds_transfers_on_top_of_swaps() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+    let recipient = wallet(OTHER, DEBIT_TOKEN);
+
+    let receipt = build_transfer(&contract, &key, &state(), 3, &recipient, 30).unwrap();
+    assert_eq!(receipt.nonce, 4);
+
+    let leaf = transfer_hash(&contract, &receipt.wallet, &recipient, 30, 4);
+    let mut leaves = leaves(&contract);
+    leaves.push(leaf);
+
+    let active_state = &receipt.sender_active_state;
+    assert_eq!(
+        active_state.tx_set_hash,
+        format_hash(&merkle::root(&leaves))
+    );
+    assert_eq!(active_state.tx_set_index, "4");
+    assert_eq!(active_state.updated_spendings, "1130");
+    assert_eq!(active_state.updated_gains, "40");
+}
+
+#[test]
+fn fails_on_nonce_overflow() {
+    let contract = Address::from_str(CONTRACT).unwrap();
+    let key = PrivateKey::from_str(PRIVATE_KEY).unwrap();
+    let debit = wallet(ADDRESS, DEBIT_TOKEN);
+
+    let mut sent = plain(1, &debit, &wallet(OTHER, DEBIT_TOKEN), 100, u64::MAX);
+    sent["eon_number"] = json::json!(2);
+    let state: WalletState = json::from_value(wallet_state(vec![sent])).unwrap();
+    assert!(matches!(
+        next_nonce(&debit, &state),
+        Err(Error::Overflow { field }) if field == "nonce"
+    ));
+
+    let result = build_transfer(&contract, &key, &state, 3, &debit, 30);
+    assert!(matches!(result, Err(Error::Overflow { .. })));
+}
@@ -0,0 +1,305 @@
+use async_std::future::timeout;
+use async_std::net::TcpListener;
+use async_std::task;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::{accept_async, WebSocketStream};
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use serde_json as json;
+
+use libliquefy::request::ws::client::{AckMode, WsClient, WsEvent};
+use libliquefy::store::json::JsonFileStore;
+use libliquefy::store::traits::{LastNotification, StoreExt};
+
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+use common::{deposit, ADDRESS, DEBIT_TOKEN};
+
+/// `stream` returns the stream of the wallet of the notifications.
+fn stream() -> String {
+    format!("wallet/{}/{}", DEBIT_TOKEN, ADDRESS)
+}
+
+const FIRST_UUID: &str = "2c4b3f8a-2f0f-11ea-8cfa-0242ac110002";
+
+const SECOND_UUID: &str = "3d5c4a9b-2f0f-11ea-8cfa-0242ac110002";
+
+fn notification(uuid: &str) -> Message {
+    let res = json::json!({
+        "type": "notification",
+        "uuid": uuid,
+        "data": {
+            "type": "CONFIRMED_DEPOSIT",
+            "data": deposit(),
+        },
+    });
+
+    Message::text(res.to_string())
+}
+
+/// `recv` returns the next request sent by the client, if any.
+async fn recv<S>(ws: &mut WebSocketStream<S>) -> Option<json::Value>
+where
+    S: futures::io::AsyncRead + futures::io::AsyncWrite + Unpin,
+{
+    loop {
+        match ws.next().await {
+            Some(Ok(Message::Text(text))) => return Some(json::from_str(&text).unwrap()),
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+/// `serve` redelivers the first notification once it is acknowledged,
+/// and returns the notifications acknowledged by the client.
+async fn serve(listener: TcpListener) -> Vec<json::Value> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+    let mut acks = Vec::new();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+
+    ws.send(notification(FIRST_UUID)).await.unwrap();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "ack");
+    acks.push(req["args"].clone());
+
+    ws.send(notification(FIRST_UUID)).await.unwrap();
+    ws.send(notification(SECOND_UUID)).await.unwrap();
+
+    while let Some(req) = recv(&mut ws).await {
+        assert_eq!(req["op"], "ack");
+        acks.push(req["args"].clone());
+    }
+
+    acks
+}
+
+/// `serve_manual` sends two notifications and returns the notifications
+/// acknowledged by the client.
+async fn serve_manual(listener: TcpListener) -> Vec<json::Value> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+    let mut acks = Vec::new();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+
+    ws.send(notification(FIRST_UUID)).await.unwrap();
+    ws.send(notification(SECOND_UUID)).await.unwrap();
+
+    while let Some(req) = recv(&mut ws).await {
+        assert_eq!(req["op"], "ack");
+        acks.push(req["args"].clone());
+    }
+
+    acks
+}
+
+#[test]
+fn acks_and_dedupes_notifications() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve(listener));
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let mut uuids = Vec::new();
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+
+            match event.unwrap().unwrap().unwrap() {
+                WsEvent::Notification(notification) => uuids.push(notification.uuid),
+                WsEvent::Gap => panic!("unexpected gap"),
+            }
+        }
+
+        assert_eq!(json::json!(uuids), json::json!([FIRST_UUID, SECOND_UUID]));
+
+        client.ack(uuids[1]).await.unwrap();
+        client.close().await.unwrap();
+
+        let acks = server.await;
+        assert_eq!(
+            json::json!(acks),
+            json::json!([FIRST_UUID, FIRST_UUID, SECOND_UUID])
+        );
+    });
+}
+
+#[test]
+fn acks_only_explicitly_in_manual_mode() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve_manual(listener));
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.set_ack_mode(AckMode::Manual);
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let mut uuids = Vec::new();
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+
+            match event.unwrap().unwrap().unwrap() {
+                WsEvent::Notification(notification) => uuids.push(notification.uuid),
+                WsEvent::Gap => panic!("unexpected gap"),
+            }
+        }
+
+        // Polling again does not acknowledge the delivered notifications.
+        let event = timeout(Duration::from_millis(100), client.next()).await;
+        assert!(event.is_err());
+
+        client.ack(uuids[1]).await.unwrap();
+        client.close().await.unwrap();
+
+        let acks = server.await;
+        assert_eq!(json::json!(acks), json::json!([SECOND_UUID]));
+    });
+}
+
+/// `serve_redelivery` redelivers a notification before it is acknowledged,
+/// and returns the notifications acknowledged by the client.
+async fn serve_redelivery(listener: TcpListener) -> Vec<json::Value> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+    let mut acks = Vec::new();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+
+    ws.send(notification(FIRST_UUID)).await.unwrap();
+    ws.send(notification(FIRST_UUID)).await.unwrap();
+
+    while let Some(req) = recv(&mut ws).await {
+        assert_eq!(req["op"], "ack");
+        acks.push(req["args"].clone());
+    }
+
+    acks
+}
+
+#[test]
+fn emits_redeliveries_of_unacknowledged_notifications() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve_redelivery(listener));
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.set_ack_mode(AckMode::Manual);
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let mut uuids = Vec::new();
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+
+            match event.unwrap().unwrap().unwrap() {
+                WsEvent::Notification(notification) => uuids.push(notification.uuid),
+                WsEvent::Gap => panic!("unexpected gap"),
+            }
+        }
+
+        assert_eq!(json::json!(uuids), json::json!([FIRST_UUID, FIRST_UUID]));
+
+        client.ack(uuids[0]).await.unwrap();
+        client.close().await.unwrap();
+
+        let acks = server.await;
+        assert_eq!(json::json!(acks), json::json!([FIRST_UUID]));
+    });
+}
+
+/// `serve_restart` delivers the first notification to a first client, then redelivers
+/// it to a second client along with the second notification, and returns the
+/// notifications acknowledged by each client.
+async fn serve_restart(listener: TcpListener) -> Vec<Vec<json::Value>> {
+    let mut clients = Vec::new();
+
+    for uuids in [&[FIRST_UUID][..], &[FIRST_UUID, SECOND_UUID][..]].iter() {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut acks = Vec::new();
+
+        let req = recv(&mut ws).await.unwrap();
+        assert_eq!(req["op"], "subscribe");
+
+        for uuid in uuids.iter() {
+            ws.send(notification(uuid)).await.unwrap();
+        }
+
+        while let Some(req) = recv(&mut ws).await {
+            assert_eq!(req["op"], "ack");
+            acks.push(req["args"].clone());
+        }
+
+        clients.push(acks);
+    }
+
+    clients
+}
+
+#[test]
+fn skips_the_last_acknowledged_notification_after_a_restart() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve_restart(listener));
+        let path = env::temp_dir().join(format!("liquefy-ws-{}.json", std::process::id()));
+        let store = Arc::new(JsonFileStore::open(&path).unwrap());
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.set_store(store.clone()).unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let event = timeout(Duration::from_secs(5), client.next()).await;
+        let uuid = match event.unwrap().unwrap().unwrap() {
+            WsEvent::Notification(notification) => notification.uuid,
+            WsEvent::Gap => panic!("unexpected gap"),
+        };
+
+        // Polling again acknowledges the notification.
+        let event = timeout(Duration::from_millis(100), client.next()).await;
+        assert!(event.is_err());
+        client.close().await.unwrap();
+
+        let last = store.load::<LastNotification>(&url).unwrap().unwrap();
+        assert_eq!(last.uuid, uuid);
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.set_store(store.clone()).unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let event = timeout(Duration::from_secs(5), client.next()).await;
+        let uuid = match event.unwrap().unwrap().unwrap() {
+            WsEvent::Notification(notification) => notification.uuid,
+            WsEvent::Gap => panic!("unexpected gap"),
+        };
+        assert_eq!(json::json!(uuid), json::json!(SECOND_UUID));
+
+        client.ack(uuid).await.unwrap();
+        client.close().await.unwrap();
+
+        let acks = server.await;
+        assert_eq!(
+            json::json!(acks),
+            json::json!([[FIRST_UUID], [FIRST_UUID, SECOND_UUID]])
+        );
+        let last = store.load::<LastNotification>(&url).unwrap().unwrap();
+        assert_eq!(last.uuid, uuid);
+
+        fs::remove_file(&path).unwrap();
+    });
+}
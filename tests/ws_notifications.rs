@@ -0,0 +1,228 @@
+use async_std::future::timeout;
+use async_std::net::TcpListener;
+use async_std::task;
+use async_tungstenite::accept_async;
+use async_tungstenite::tungstenite::Message;
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use serde_json as json;
+
+use libliquefy::model::ws::ws::{
+    Event, EventType, NotificationData, TokenPairEvent, TokenPairEventType, WalletEvent,
+    WalletEventType,
+};
+use libliquefy::request::ws::client::{WsClient, WsEvent};
+
+use std::time::Duration;
+
+mod common;
+
+use common::{fixture, transfer, ADDRESS, CREDIT_TOKEN, DEBIT_TOKEN};
+
+const UUID: &str = "2c4b3f8a-2f0f-11ea-8cfa-0242ac110002";
+
+fn withdrawal_request() -> json::Value {
+    json::json!({
+        "amount": 300,
+        "block": 130,
+        "eon_number": 3,
+        "slashed": false,
+        "time": "2020-01-06T12:00:00Z",
+        "txid": "0x01",
+    })
+}
+
+/// `notifications` returns the data of a notification of every event type.
+fn notifications() -> Vec<(&'static str, json::Value)> {
+    let fixture = fixture();
+    let wallet = json::json!({ "address": ADDRESS, "token": DEBIT_TOKEN });
+
+    let mut deposit = json::json!({
+        "amount": 1000,
+        "block": 120,
+        "eon_number": 3,
+        "time": "2020-01-06T12:00:00Z",
+        "txid": "0x02",
+    });
+    deposit["address"] = wallet["address"].clone();
+    deposit["token"] = wallet["token"].clone();
+
+    let mut request = withdrawal_request();
+    request["address"] = wallet["address"].clone();
+    request["token"] = wallet["token"].clone();
+
+    let mut withdrawal = json::json!({
+        "amount": 300,
+        "block": 160,
+        "eon_number": 4,
+        "request": withdrawal_request(),
+        "time": "2020-01-07T12:00:00Z",
+        "txid": "0x03",
+    });
+    withdrawal["address"] = wallet["address"].clone();
+    withdrawal["token"] = wallet["token"].clone();
+
+    let checkpoint = json::json!({
+        "address": ADDRESS,
+        "token": DEBIT_TOKEN,
+        "deposits": [],
+        "merkle_proofs": [fixture["passive"]["delivery_proof"]["merkle_proof"]],
+        "registration": {
+            "eon_number": 0,
+            "operator_signature": "",
+            "trail_identifier": 0,
+            "wallet_signature": "",
+        },
+        "transfers": [transfer()],
+        "withdrawal_request": [],
+        "withdrawals": [],
+    });
+
+    let admission = json::json!({
+        "address": ADDRESS,
+        "token": DEBIT_TOKEN,
+        "eon_number": 0,
+        "wallet_signature": "",
+        "operator_signature": "",
+        "trail_identifier": 0,
+    });
+
+    vec![
+        ("INCOMING_TRANSFER", transfer()),
+        ("INCOMING_RECEIPT", transfer()),
+        ("INCOMING_CONFIRMATION", transfer()),
+        ("TIMEOUT_TRANSFER", transfer()),
+        ("MATCHED_SWAP", transfer()),
+        ("FINALIZED_SWAP", transfer()),
+        ("CANCELED_SWAP", transfer()),
+        ("REGISTERED_WALLET", admission),
+        ("CONFIRMED_DEPOSIT", deposit),
+        ("REQUEST_WITHDRAWAL", request),
+        ("CONFIRMED_WITHDRAWAL", withdrawal),
+        ("CHECKPOINT_CREATED", checkpoint),
+        ("INCOMING_SWAP", transfer()),
+    ]
+}
+
+fn decode(r#type: &str, data: &json::Value) -> NotificationData {
+    json::from_value(json::json!({ "type": r#type, "data": data })).unwrap()
+}
+
+#[test]
+fn decodes_every_event_type() {
+    use WalletEventType::*;
+
+    let types = [
+        EventType::Wallet(IncomingTransfer),
+        EventType::Wallet(IncomingReceipt),
+        EventType::Wallet(IncomingConfirmation),
+        EventType::Wallet(TimeoutTransfer),
+        EventType::Wallet(MatchedSwap),
+        EventType::Wallet(FinalizedSwap),
+        EventType::Wallet(CanceledSwap),
+        EventType::Wallet(RegisteredWallet),
+        EventType::Wallet(ConfirmedDeposit),
+        EventType::Wallet(RequestWithdrawal),
+        EventType::Wallet(ConfirmedWithdrawal),
+        EventType::Wallet(CheckpointCreated),
+        EventType::TokenPair(TokenPairEventType::IncomingSwap),
+    ];
+
+    for ((name, data), r#type) in notifications().iter().zip(types.iter()) {
+        let notification = decode(name, data);
+
+        assert_eq!(notification.r#type, *r#type, "{}", name);
+        assert_eq!(notification.data.event_type(), *r#type, "{}", name);
+        assert_eq!(
+            notification.data.transfer().is_some(),
+            data.get("delivery_proof").is_some(),
+            "{}",
+            name
+        );
+
+        let encoded = json::to_value(&notification).unwrap();
+        assert_eq!(encoded["type"], **name);
+        assert_eq!(decode(name, &encoded["data"]), notification);
+    }
+
+    let matched = decode("MATCHED_SWAP", &transfer()).into_token_pair();
+    assert_eq!(
+        matched.r#type,
+        EventType::TokenPair(TokenPairEventType::MatchedSwap)
+    );
+    match matched.data {
+        Event::TokenPair(TokenPairEvent::MatchedSwap(swap)) => assert_eq!(swap.id, 42),
+        _ => panic!("expected a token pair matched swap"),
+    }
+
+    let res = json::from_value::<NotificationData>(json::json!({
+        "type": "CONFIRMED_DEPOSIT",
+        "data": transfer(),
+    }));
+    assert!(res.is_err());
+}
+
+/// `serve` confirms the subscription to a stream, then sends a matched swap.
+async fn serve(listener: TcpListener, stream: String) {
+    let (tcp, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(tcp).await.unwrap();
+
+    let req: json::Value = match ws.next().await {
+        Some(Ok(Message::Text(text))) => json::from_str(&text).unwrap(),
+        _ => panic!("expected a subscribe request"),
+    };
+    assert_eq!(req["op"], "subscribe");
+
+    let res = json::json!({
+        "type": "response",
+        "uuid": UUID,
+        "data": { "op": "subscribe", "stream": [stream] },
+    });
+    ws.send(Message::text(res.to_string())).await.unwrap();
+
+    let notification = json::json!({
+        "type": "notification",
+        "uuid": UUID,
+        "data": { "type": "MATCHED_SWAP", "data": transfer() },
+    });
+    ws.send(Message::text(notification.to_string()))
+        .await
+        .unwrap();
+
+    while let Some(Ok(_)) = ws.next().await {}
+}
+
+/// `matched_swap` returns the matched swap received while subscribed to a stream.
+fn matched_swap(stream: String) -> Event {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve(listener, stream.clone()));
+
+        let mut client = WsClient::connect(&url).await.unwrap();
+        client.subscribe(&[stream]).await.unwrap();
+
+        let event = timeout(Duration::from_secs(5), client.next()).await;
+        let notification = match event.unwrap().unwrap().unwrap() {
+            WsEvent::Notification(notification) => notification,
+            WsEvent::Gap => panic!("unexpected gap"),
+        };
+
+        client.close().await.unwrap();
+        server.await;
+
+        notification.data.data
+    })
+}
+
+#[test]
+fn attributes_matched_swaps_to_the_subscribed_stream() {
+    let event = matched_swap(format!("tokenpair/{}/{}", CREDIT_TOKEN, DEBIT_TOKEN));
+    assert!(matches!(
+        event,
+        Event::TokenPair(TokenPairEvent::MatchedSwap(_))
+    ));
+
+    let event = matched_swap(format!("wallet/{}/{}", DEBIT_TOKEN, ADDRESS));
+    assert!(matches!(event, Event::Wallet(WalletEvent::MatchedSwap(_))));
+}
@@ -0,0 +1,271 @@
+use async_std::future::timeout;
+use async_std::net::TcpListener;
+use async_std::task;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::{accept_async, WebSocketStream};
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use serde_json as json;
+
+use libliquefy::request::ws::client::{ReconnectPolicy, WsClient, WsEvent};
+
+use std::time::Duration;
+
+mod common;
+
+use common::{deposit, ADDRESS, CREDIT_TOKEN, DEBIT_TOKEN};
+
+const UUIDS: [&str; 2] = [
+    "2c4b3f8a-2f0f-11ea-8cfa-0242ac110002",
+    "3d5c4a9b-2f0f-11ea-8cfa-0242ac110002",
+];
+
+/// `stream` returns the stream of the wallet of the notifications.
+fn stream() -> String {
+    format!("wallet/{}/{}", DEBIT_TOKEN, ADDRESS)
+}
+
+/// `other_stream` returns the stream of another wallet.
+fn other_stream() -> String {
+    format!("wallet/{}/{}", DEBIT_TOKEN, CREDIT_TOKEN)
+}
+
+fn subscribe_response(streams: &json::Value) -> Message {
+    let res = json::json!({
+        "type": "response",
+        "uuid": "1ecaf2d4-2f0f-11ea-8cfa-0242ac110002",
+        "data": { "op": "subscribe", "stream": streams },
+    });
+
+    Message::text(res.to_string())
+}
+
+fn notification(uuid: &str) -> Message {
+    let res = json::json!({
+        "type": "notification",
+        "uuid": uuid,
+        "data": {
+            "type": "CONFIRMED_DEPOSIT",
+            "data": deposit(),
+        },
+    });
+
+    Message::text(res.to_string())
+}
+
+/// `recv` returns the next request sent by the client, if any.
+async fn recv<S>(ws: &mut WebSocketStream<S>) -> Option<json::Value>
+where
+    S: futures::io::AsyncRead + futures::io::AsyncWrite + Unpin,
+{
+    loop {
+        match ws.next().await {
+            Some(Ok(Message::Text(text))) => return Some(json::from_str(&text).unwrap()),
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+/// `serve` accepts two connections, dropping the first one on purpose after
+/// a notification, and returns the streams subscribed on each connection.
+async fn serve(listener: TcpListener) -> Vec<json::Value> {
+    let mut subscribed = Vec::new();
+
+    for (round, uuid) in UUIDS.iter().enumerate() {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+
+        let req = match ws.next().await.unwrap().unwrap() {
+            Message::Text(text) => json::from_str::<json::Value>(&text).unwrap(),
+            msg => panic!("unexpected message {:?}", msg),
+        };
+        assert_eq!(req["op"], "subscribe");
+
+        ws.send(subscribe_response(&req["args"])).await.unwrap();
+        ws.send(notification(uuid)).await.unwrap();
+        subscribed.push(req["args"].clone());
+
+        if round == 1 {
+            while let Some(Ok(_)) = ws.next().await {}
+        }
+    }
+
+    subscribed
+}
+
+#[test]
+fn reconnects_and_resubscribes_after_drop() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve(listener));
+
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(100),
+            max_attempts: Some(5),
+        };
+
+        let mut client = WsClient::connect_with_policy(&url, policy).await.unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+            events.push(event.unwrap().unwrap().unwrap());
+        }
+
+        assert!(matches!(events[0], WsEvent::Notification(_)));
+        assert!(matches!(events[1], WsEvent::Gap));
+        assert!(matches!(events[2], WsEvent::Notification(_)));
+        assert!(client.subscriptions().contains(&stream()));
+
+        client.close().await.unwrap();
+
+        let subscribed = server.await;
+        assert_eq!(subscribed[0], json::json!([stream()]));
+        assert_eq!(subscribed[1], subscribed[0]);
+    });
+}
+
+/// `serve_changes` accepts two connections, dropping the first one on purpose after
+/// a notification, and returns the requests received on the second connection.
+async fn serve_changes(listener: TcpListener) -> Vec<json::Value> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+
+    ws.send(subscribe_response(&req["args"])).await.unwrap();
+    ws.send(notification(UUIDS[0])).await.unwrap();
+    drop(ws);
+
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+    let mut requests = Vec::new();
+
+    let req = recv(&mut ws).await.unwrap();
+    ws.send(subscribe_response(&req["args"])).await.unwrap();
+    ws.send(notification(UUIDS[1])).await.unwrap();
+    requests.push(req);
+
+    while let Some(req) = recv(&mut ws).await {
+        if req["op"] != "ack" {
+            requests.push(req);
+        }
+    }
+
+    requests
+}
+
+#[test]
+fn sends_subscription_changes_made_while_reconnecting() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve_changes(listener));
+
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(300),
+            max_delay: Duration::from_millis(300),
+            max_attempts: Some(5),
+        };
+
+        let mut client = WsClient::connect_with_policy(&url, policy).await.unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+
+        let event = timeout(Duration::from_secs(5), client.next()).await;
+        assert!(matches!(
+            event.unwrap().unwrap().unwrap(),
+            WsEvent::Notification(_)
+        ));
+
+        // The connection drops and the client waits before reconnecting.
+        let event = timeout(Duration::from_millis(100), client.next()).await;
+        assert!(event.is_err());
+        assert!(!client.is_connected());
+
+        client.subscribe(&[other_stream()]).await.unwrap();
+        client.unsubscribe(&[stream()]).await.unwrap();
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+            events.push(event.unwrap().unwrap().unwrap());
+        }
+
+        assert!(matches!(events[0], WsEvent::Gap));
+        assert!(matches!(events[1], WsEvent::Notification(_)));
+
+        client.close().await.unwrap();
+
+        let requests = server.await;
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0]["op"], "subscribe");
+        assert_eq!(requests[0]["args"], json::json!([stream()]));
+        assert_eq!(requests[1]["op"], "subscribe");
+        assert_eq!(requests[1]["args"], json::json!([other_stream()]));
+        assert_eq!(requests[2]["op"], "unsubscribe");
+        assert_eq!(requests[2]["args"], json::json!([stream()]));
+    });
+}
+
+/// `serve_unconfirmed` drops the first connection without confirming the subscription,
+/// and returns the streams subscribed on the second connection.
+async fn serve_unconfirmed(listener: TcpListener) -> json::Value {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+    drop(ws);
+
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = accept_async(stream).await.unwrap();
+
+    let req = recv(&mut ws).await.unwrap();
+    assert_eq!(req["op"], "subscribe");
+    ws.send(subscribe_response(&req["args"])).await.unwrap();
+    ws.send(notification(UUIDS[0])).await.unwrap();
+
+    while recv(&mut ws).await.is_some() {}
+
+    req["args"].clone()
+}
+
+#[test]
+fn resubscribes_unconfirmed_streams_after_drop() {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = task::spawn(serve_unconfirmed(listener));
+
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(100),
+            max_attempts: Some(5),
+        };
+
+        let mut client = WsClient::connect_with_policy(&url, policy).await.unwrap();
+        client.subscribe(&[stream()]).await.unwrap();
+        assert!(client.subscriptions().is_empty());
+        assert!(client.pending_subscriptions().contains(&stream()));
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(5), client.next()).await;
+            events.push(event.unwrap().unwrap().unwrap());
+        }
+
+        assert!(matches!(events[0], WsEvent::Gap));
+        assert!(matches!(events[1], WsEvent::Notification(_)));
+        assert!(client.subscriptions().contains(&stream()));
+        assert!(client.pending_subscriptions().is_empty());
+
+        client.close().await.unwrap();
+
+        assert_eq!(server.await, json::json!([stream()]));
+    });
+}